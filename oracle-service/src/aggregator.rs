@@ -1,8 +1,201 @@
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use statrs::statistics::Statistics;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+use std::sync::Mutex;
 use tracing::{debug, warn};
 
-use crate::types::{PriceData, PriceSource, Symbol};
+use crate::types::{OracleError, PriceData, PriceSource, Symbol};
+
+/// Exponent used when there are no sources to take one from
+const DEFAULT_EXPO: i32 = -8;
+
+/// Ratio between normalized prices beyond which a decimals/expo bug is more
+/// likely than a genuine market deviation
+const SCALE_MISMATCH_RATIO: f64 = 10.0;
+
+/// Fewest significant digits an inverted price is given, so a short mantissa
+/// like 2 (expo 0) doesn't invert to a rounded 1
+const MIN_INVERTED_DIGITS: i32 = 9;
+
+/// Sources needed before tight agreement counts as suspicious; two honest
+/// feeds quoting a stablecoin at exactly 1.00 is the normal case
+const MIN_CLUSTERING_SOURCES: usize = 3;
+
+/// Standard deviations from the rolling baseline before a move can alert
+const MANIPULATION_Z_THRESHOLD: f64 = 4.0;
+
+/// Relative move from the baseline a single source needs to count as a flash crash
+const FLASH_CRASH_DEVIATION: f64 = 0.1;
+
+/// Relative move from the baseline tightly clustered sources need to look coordinated
+const MIN_CONSENSUS_MOVE: f64 = 0.01;
+
+/// Share of each method in the blended consensus price
+const MEDIAN_SHARE: f64 = 0.5;            // Manipulation resistant
+const CONFIDENCE_WEIGHTED_SHARE: f64 = 0.3;
+const VOLUME_WEIGHTED_SHARE: f64 = 0.2;
+
+/// Fixed-point scale confidence weights are rounded to for the integer weighted sum
+const WEIGHT_SCALE: f64 = 1e9;
+
+/// Modified z-score above which the MAD filter drops a price
+const MAD_Z_THRESHOLD: f64 = 2.5;
+
+/// Oracles behind a typical Switchboard round; readings backed by more carry
+/// more weight than their confidence alone gives them, fewer carry less
+const REFERENCE_ORACLE_COUNT: f64 = 5.0;
+
+/// Multiple of the interquartile range the Tukey fences sit outside the quartiles
+const IQR_FENCE_MULTIPLIER: f64 = 1.5;
+
+/// Share of values dropped from each end before a trimmed mean
+const TRIMMED_MEAN_FRACTION: f64 = 0.2;
+
+/// Statistical method used to drop outlier prices before consensus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutlierMethod {
+    /// Modified z-score against the median absolute deviation
+    #[default]
+    Mad,
+    /// Tukey fences at 1.5x the interquartile range
+    Iqr,
+    /// Keep every price
+    None,
+}
+
+impl FromStr for OutlierMethod {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "mad" => Ok(Self::Mad),
+            "iqr" => Ok(Self::Iqr),
+            "none" => Ok(Self::None),
+            other => anyhow::bail!("Unknown outlier method: {}", other),
+        }
+    }
+}
+
+/// Which value an even-length median takes
+///
+/// `Average` can produce a price no source reported; `Lower` and `Upper`
+/// always return an actual source value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MedianTieBreak {
+    /// Mean of the two middle values
+    #[default]
+    Average,
+    /// The lower middle value
+    Lower,
+    /// The upper middle value
+    Upper,
+}
+
+impl FromStr for MedianTieBreak {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "average" => Ok(Self::Average),
+            "lower" => Ok(Self::Lower),
+            "upper" => Ok(Self::Upper),
+            other => anyhow::bail!("Unknown median tie-break: {}", other),
+        }
+    }
+}
+
+/// How the aggregate's exponent is picked from the contributing sources
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExpoSelection {
+    /// The finest (most negative) exponent, so no source loses precision
+    #[default]
+    Finest,
+    /// The median exponent, the coarser middle one for an even count
+    Median,
+}
+
+impl FromStr for ExpoSelection {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "finest" => Ok(Self::Finest),
+            "median" => Ok(Self::Median),
+            other => anyhow::bail!("Unknown expo selection: {}", other),
+        }
+    }
+}
+
+/// Statistic a source's deviation is measured from in `max_deviation` checks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviationBase {
+    /// Median of the source prices
+    #[default]
+    Median,
+    /// Plain mean of the source prices
+    Mean,
+    /// Mean after dropping the top and bottom 20% of prices
+    TrimmedMean,
+}
+
+impl FromStr for DeviationBase {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "median" => Ok(Self::Median),
+            "mean" => Ok(Self::Mean),
+            "trimmed_mean" | "trimmedmean" => Ok(Self::TrimmedMean),
+            other => anyhow::bail!("Unknown deviation base: {}", other),
+        }
+    }
+}
+
+impl std::fmt::Display for DeviationBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Median => "median",
+            Self::Mean => "mean",
+            Self::TrimmedMean => "trimmed mean",
+        })
+    }
+}
+
+impl OutlierMethod {
+    /// Cutoff the method applies: z-score for MAD, fence multiplier for IQR
+    pub fn threshold(self) -> Option<f64> {
+        match self {
+            Self::Mad => Some(MAD_Z_THRESHOLD),
+            Self::Iqr => Some(IQR_FENCE_MULTIPLIER),
+            Self::None => None,
+        }
+    }
+}
+
+/// How a symbol's source prices are combined into a single price
+///
+/// Symbols list these in `fallback_chain`; each is tried in order until one
+/// produces a price.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationStrategy {
+    /// Outlier-filtered blend of median and weighted averages
+    Consensus,
+    /// Consensus, but only when at least this many sources reported
+    Quorum(usize),
+    /// Median of the available prices, needs at least two
+    Median,
+    /// The single source with the tightest relative confidence
+    HighestConfidence,
+}
 
 /// Advanced price aggregation engine with manipulation resistance
 pub struct PriceAggregator {
@@ -10,6 +203,21 @@ pub struct PriceAggregator {
     _deviation_threshold: f64,
     _confidence_weight: f64,
     min_sources: usize,
+    outlier_method: OutlierMethod,
+    median_tie_break: MedianTieBreak,
+    expo_selection: ExpoSelection,
+    deviation_base: DeviationBase,
+    relaxed_retry: Option<u64>,        // Deviation multiplier for the second, relaxed attempt
+    memo: Option<Mutex<HashMap<String, MemoEntry>>>, // Last result per symbol, when memoization is on
+    source_weights: Mutex<HashMap<String, HashMap<PriceSource, f64>>>, // Per-symbol multipliers on confidence weights
+}
+
+/// The inputs fingerprint and result of a symbol's last aggregation
+#[derive(Debug, Clone)]
+struct MemoEntry {
+    fingerprint: u64,
+    result: PriceData,
+    trace: Option<AggregationTrace>,
 }
 
 impl PriceAggregator {
@@ -18,11 +226,83 @@ impl PriceAggregator {
             _deviation_threshold: 0.01, // 1% maximum deviation
             _confidence_weight: 0.7,    // Weight given to confidence in final score
             min_sources: 1,            // Minimum sources required
+            outlier_method: OutlierMethod::default(),
+            median_tie_break: MedianTieBreak::default(),
+            expo_selection: ExpoSelection::default(),
+            deviation_base: DeviationBase::default(),
+            relaxed_retry: None,
+            memo: None,
+            source_weights: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Select the outlier filtering method
+    pub fn with_outlier_method(mut self, method: OutlierMethod) -> Self {
+        self.outlier_method = method;
+        self
+    }
+    
+    /// Select how even-length medians are resolved
+    pub fn with_median_tie_break(mut self, tie_break: MedianTieBreak) -> Self {
+        self.median_tie_break = tie_break;
+        self
+    }
+    
+    /// Select how the output exponent is derived from the sources
+    pub fn with_expo_selection(mut self, selection: ExpoSelection) -> Self {
+        self.expo_selection = selection;
+        self
+    }
+    
+    /// Select the statistic `max_deviation` checks measure sources against
+    pub fn with_deviation_base(mut self, base: DeviationBase) -> Self {
+        self.deviation_base = base;
+        self
+    }
+    
+    /// Reuse a symbol's last result while its source prices are unchanged, see `aggregate_memoized`
+    pub fn with_memoization(mut self, enabled: bool) -> Self {
+        self.memo = enabled.then(|| Mutex::new(HashMap::new()));
+        self
+    }
+    
+    /// Scale each source's weight in a symbol's confidence-weighted average
+    ///
+    /// Replaces the symbol's previous multipliers; sources left out weigh 1.
+    pub fn set_source_weights(&self, symbol: &str, weights: HashMap<PriceSource, f64>) {
+        self.source_weights.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(symbol.to_string(), weights);
+        
+        // A memoized result was weighted the old way
+        if let Some(memo) = &self.memo {
+            memo.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).remove(symbol);
         }
     }
     
+    /// `confidence_weight` scaled by the source's multiplier for its symbol
+    fn source_weight(&self, price: &PriceData) -> Option<f64> {
+        let multiplier = self.source_weights.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&price.symbol)
+            .and_then(|weights| weights.get(&price.source))
+            .copied()
+            .unwrap_or(1.0);
+        confidence_weight(price).map(|weight| weight * multiplier)
+    }
+    
+    /// Retry failed aggregations once with loosened limits, see `aggregate_two_tier`;
+    /// `None` or 0 fails on the first attempt
+    pub fn with_relaxed_retry(mut self, deviation_multiplier: Option<u64>) -> Self {
+        self.relaxed_retry = deviation_multiplier.filter(|&n| n > 0);
+        self
+    }
+    
     /// Aggregate prices from multiple sources with advanced consensus
     pub fn aggregate_prices(&self, prices: &[PriceData], symbol: &Symbol) -> Result<PriceData> {
+        self.aggregate_prices_traced(prices, symbol).map(|(aggregated, _)| aggregated)
+    }
+    
+    /// Aggregate like `aggregate_prices`, also returning how the price was derived
+    pub fn aggregate_prices_traced(&self, prices: &[PriceData], symbol: &Symbol) -> Result<(PriceData, AggregationTrace)> {
         if prices.len() < self.min_sources {
             anyhow::bail!("Insufficient price sources: {} < {}", prices.len(), self.min_sources);
         }
@@ -30,33 +310,365 @@ impl PriceAggregator {
         debug!("Aggregating {} prices for {}", prices.len(), symbol.name);
         
         // Convert prices to common decimal format
-        let normalized_prices: Vec<f64> = prices.iter()
-            .map(|p| self.normalize_price(p))
-            .collect();
+        let normalized_prices = self.normalized_finite(prices, symbol)?;
+        self.check_positive_median(&normalized_prices, symbol)?;
         
-        // Detect and filter outliers
-        let filtered_prices = self.filter_outliers(&normalized_prices, prices)?;
+        // Detect and filter outliers, against the reference source when it reported
+        let verdicts = match self.reference_verdicts(prices, symbol) {
+            Some(verdicts) => verdicts,
+            None => self.outlier_verdicts(&normalized_prices)?,
+        };
+        let filtered_prices = self.apply_verdicts(prices, &verdicts)?;
         
-        // Calculate consensus price using multiple methods
-        let consensus_price = self.calculate_consensus(&filtered_prices)?;
+        // Calculate consensus price using multiple methods, in fixed point at
+        // an exponent taken from the sources
+        let expo = self.common_expo(prices);
+        let (consensus, components) = self.consensus_components(&filtered_prices, expo, symbol)?;
         
         // Calculate aggregated confidence
         let consensus_confidence = self.calculate_confidence(&filtered_prices);
         
+        let aggregated = self.build_aggregate(prices, symbol, consensus, expo, consensus_confidence)?;
+        
+        // Record each source's fate and its share of the confidence-weighted average
+        let total_weight: f64 = filtered_prices.iter()
+            .filter_map(|price_data| self.source_weight(price_data))
+            .sum();
+        let sources = prices.iter()
+            .zip(&normalized_prices)
+            .zip(verdicts)
+            .map(|((price_data, &price), excluded_reason)| SourceTrace {
+                source: price_data.source.clone(),
+                price,
+                confidence: price_data.confidence_to_decimal(),
+                weight: excluded_reason.is_none()
+                    .then(|| self.source_weight(price_data).map(|w| w / total_weight))
+                    .flatten(),
+                excluded_reason,
+            })
+            .collect();
+        
+        let trace = AggregationTrace {
+            outlier_method: self.outlier_method,
+            sources,
+            components,
+            confidence: consensus_confidence,
+        };
+        
+        Ok((aggregated, trace))
+    }
+    
+    /// Aggregate by walking the symbol's fallback chain until a strategy succeeds
+    ///
+    /// The result is tagged with the strategy that produced it. An empty chain
+    /// behaves like `[Consensus]`. Symbols in strict mode fail outright unless
+    /// every source agrees, see `check_unanimity`.
+    ///
+    /// Symbols configured with a single source skip consensus entirely: that
+    /// source's price is returned as the aggregate unchanged, marked degraded
+    /// since nothing corroborated it.
+    pub fn aggregate_with_fallback(&self, prices: &[PriceData], symbol: &Symbol) -> Result<PriceData> {
+        self.aggregate_with_fallback_traced(prices, symbol).map(|(aggregated, _)| aggregated)
+    }
+    
+    /// Aggregate like `aggregate_with_fallback`, also returning the trace of
+    /// the consensus that produced the price
+    ///
+    /// There is no trace when no consensus ran: the single-source path and the
+    /// median and highest-confidence strategies.
+    pub fn aggregate_with_fallback_traced(&self, prices: &[PriceData], symbol: &Symbol) -> Result<(PriceData, Option<AggregationTrace>)> {
+        // No strategy can salvage sources centred on zero or below
+        self.check_positive_median(&self.normalized_finite(prices, symbol)?, symbol)?;
+        
+        if let ([only], Some([configured])) = (prices, symbol.sources.as_deref()) {
+            if &only.source == configured {
+                debug!("{} has a single source, skipping consensus", symbol.name);
+                return Ok((PriceData {
+                    source: PriceSource::Aggregated,
+                    symbol: symbol.name.clone(),
+                    degraded: true,
+                    ..only.clone()
+                }, None));
+            }
+        }
+        
+        if symbol.strict_consensus {
+            self.check_unanimity(prices, symbol)?;
+        }
+        
+        let mut failures = Vec::new();
+        for &strategy in strategy_chain(symbol) {
+            match self.aggregate_with(strategy, prices, symbol) {
+                Ok((mut aggregated, trace)) => {
+                    if !failures.is_empty() {
+                        warn!("Aggregated {} with fallback {:?} after: {}", symbol.name, strategy, failures.join("; "));
+                    }
+                    aggregated.strategy = Some(strategy);
+                    return Ok((aggregated, trace));
+                },
+                Err(e) => failures.push(format!("{:?}: {}", strategy, e)),
+            }
+        }
+        
+        anyhow::bail!("All aggregation strategies failed for {}: {}", symbol.name, failures.join("; "))
+    }
+    
+    /// `aggregate_with_fallback`, skipping the consensus math when the sources
+    /// report exactly what they did last time for this symbol, under the same
+    /// symbol configuration
+    ///
+    /// Only the latest result per symbol is kept. A reused result carries the
+    /// newest source timestamp so it doesn't look older than its inputs.
+    /// Failures are never remembered. Without memoization enabled this is
+    /// plain `aggregate_with_fallback`.
+    pub fn aggregate_memoized(&self, prices: &[PriceData], symbol: &Symbol) -> Result<PriceData> {
+        self.aggregate_memoized_traced(prices, symbol).map(|(aggregated, _)| aggregated)
+    }
+    
+    /// `aggregate_memoized`, with the trace remembered alongside the result
+    fn aggregate_memoized_traced(&self, prices: &[PriceData], symbol: &Symbol) -> Result<(PriceData, Option<AggregationTrace>)> {
+        let Some(memo) = &self.memo else {
+            return self.aggregate_with_fallback_traced(prices, symbol);
+        };
+        
+        let fingerprint = source_fingerprint(prices, symbol);
+        let latest_timestamp = prices.iter().map(|p| p.timestamp).max().unwrap_or(0);
+        
+        let cached = memo.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&symbol.name)
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| (entry.result.clone(), entry.trace.clone()));
+        if let Some((mut result, trace)) = cached {
+            debug!("Sources unchanged for {}, reusing last aggregate", symbol.name);
+            result.timestamp = latest_timestamp;
+            return Ok((result, trace));
+        }
+        
+        let (result, trace) = self.aggregate_with_fallback_traced(prices, symbol)?;
+        memo.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(symbol.name.clone(), MemoEntry { fingerprint, result: result.clone(), trace: trace.clone() });
+        Ok((result, trace))
+    }
+    
+    /// `aggregate_memoized` with the symbol's own limits, then once more with
+    /// `relaxed_symbol` limits if that fails
+    ///
+    /// A price from the second attempt is marked degraded so consumers can
+    /// tell it apart from one that passed the configured checks, and flagged
+    /// `relaxed` for the caller. Without a relaxed retry configured the first
+    /// error is returned as is.
+    pub fn aggregate_two_tier(&self, prices: &[PriceData], symbol: &Symbol) -> Result<TieredAggregate> {
+        let strict_err = match self.aggregate_memoized_traced(prices, symbol) {
+            Ok((price, trace)) => return Ok(self.tiered(prices, price, trace, false)),
+            Err(e) => e,
+        };
+        let Some(multiplier) = self.relaxed_retry else {
+            return Err(strict_err);
+        };
+        
+        let relaxed = relaxed_symbol(symbol, multiplier);
+        match self.aggregate_with_fallback_traced(prices, &relaxed) {
+            Ok((mut aggregated, trace)) => {
+                warn!("Aggregated {} with relaxed limits ({}x deviation) after: {}", symbol.name, multiplier, strict_err);
+                aggregated.degraded = true;
+                Ok(self.tiered(prices, aggregated, trace, true))
+            },
+            Err(relaxed_err) => Err(strict_err.context(format!("relaxed retry also failed: {}", relaxed_err))),
+        }
+    }
+    
+    /// Package an aggregate with the sources it was actually built from
+    ///
+    /// Consensus names its survivors in the trace and the highest-confidence
+    /// strategy returns one source's price; otherwise every source fed the
+    /// result.
+    fn tiered(&self, prices: &[PriceData], price: PriceData, trace: Option<AggregationTrace>, relaxed: bool) -> TieredAggregate {
+        let contributors = match &trace {
+            Some(trace) => trace.sources.iter()
+                .filter(|s| s.excluded_reason.is_none())
+                .map(|s| s.source.clone())
+                .collect(),
+            None if price.strategy == Some(AggregationStrategy::HighestConfidence) => vec![price.source.clone()],
+            None => prices.iter().map(|p| p.source.clone()).collect(),
+        };
+        TieredAggregate { price, trace, contributors, relaxed }
+    }
+    
+    /// Parameters the engine actually applies to a symbol, defaults resolved
+    pub fn effective_config(&self, symbol: &Symbol, sources: Vec<PriceSource>) -> AggregationConfig {
+        AggregationConfig {
+            symbol: symbol.name.clone(),
+            strategies: strategy_chain(symbol).to_vec(),
+            strict_consensus: symbol.strict_consensus,
+            reference_source: symbol.reference_source.clone(),
+            min_sources: self.min_sources,
+            outlier_method: self.outlier_method,
+            outlier_threshold: self.outlier_method.threshold(),
+            median_tie_break: self.median_tie_break,
+            expo_selection: self.expo_selection,
+            deviation_base: self.deviation_base,
+            max_sources: None, // Applied before fetching, by the manager
+            relaxed_retry_multiplier: self.relaxed_retry,
+            shares: ConsensusShares {
+                median: MEDIAN_SHARE,
+                confidence_weighted: CONFIDENCE_WEIGHTED_SHARE,
+                volume_weighted: VOLUME_WEIGHTED_SHARE,
+            },
+            max_deviation_bp: symbol.max_deviation,
+            max_confidence_bp: symbol.max_confidence,
+            max_staleness_secs: symbol.max_staleness,
+            sources,
+        }
+    }
+    
+    /// Require every source that responded to sit within `max_deviation` of every other
+    ///
+    /// Unlike quorum or the outlier filter, a single divergent source is enough
+    /// to block the price, for consumers that would rather have no price than
+    /// one some source disputes. When the lowest and highest prices are too far
+    /// apart, whichever of the two sits further from the configured deviation
+    /// base is reported as `OracleError::DeviationExceeded`, with the spread as
+    /// its deviation.
+    pub fn check_unanimity(&self, prices: &[PriceData], symbol: &Symbol) -> Result<()> {
+        let values = self.normalized_finite(prices, symbol)?;
+        let by_value = |a: &(usize, &f64), b: &(usize, &f64)| a.1.total_cmp(b.1);
+        let (Some((low, &low_value)), Some((high, &high_value))) = (
+            values.iter().enumerate().min_by(by_value),
+            values.iter().enumerate().max_by(by_value),
+        ) else {
+            return Ok(());
+        };
+        
+        if values.len() < 2 || low_value == high_value {
+            return Ok(());
+        }
+        if low_value <= 0.0 {
+            anyhow::bail!("Strict consensus needs positive prices for {}, got {}", symbol.name, low_value);
+        }
+        
+        let spread_bp = (high_value - low_value) / low_value * 10000.0;
+        if spread_bp <= symbol.max_deviation as f64 {
+            return Ok(());
+        }
+        
+        let base = self.deviation_base;
+        let reference = self.deviation_reference(&values);
+        let culprit = if high_value - reference >= reference - low_value { high } else { low };
+        Err(OracleError::DeviationExceeded {
+            symbol: symbol.name.clone(),
+            price_source: prices[culprit].source.clone(),
+            value: values[culprit],
+            median: self.calculate_median(values.clone()),
+            base,
+            reference,
+            deviation_bp: spread_bp,
+            max_deviation_bp: symbol.max_deviation,
+        }.into())
+    }
+    
+    /// Aggregate with a single strategy, with a trace for the consensus-based ones
+    fn aggregate_with(&self, strategy: AggregationStrategy, prices: &[PriceData], symbol: &Symbol) -> Result<(PriceData, Option<AggregationTrace>)> {
+        match strategy {
+            AggregationStrategy::Consensus => {
+                self.aggregate_prices_traced(prices, symbol).map(|(aggregated, trace)| (aggregated, Some(trace)))
+            },
+            AggregationStrategy::Quorum(required) => {
+                if prices.len() < required {
+                    anyhow::bail!("Quorum not met: {} < {}", prices.len(), required);
+                }
+                self.aggregate_prices_traced(prices, symbol).map(|(aggregated, trace)| (aggregated, Some(trace)))
+            },
+            AggregationStrategy::Median => {
+                if prices.len() < 2 {
+                    anyhow::bail!("Median needs at least 2 sources, got {}", prices.len());
+                }
+                let expo = self.common_expo(prices);
+                let median = self.fixed_median(self.fixed_prices(prices, expo, symbol)?);
+                self.build_aggregate(prices, symbol, median, expo, self.calculate_confidence(prices))
+                    .map(|aggregated| (aggregated, None))
+            },
+            AggregationStrategy::HighestConfidence => {
+                let best = prices.iter()
+                    .filter_map(|p| p.relative_confidence().map(|ratio| (p, ratio)))
+                    .filter(|(_, ratio)| ratio.is_finite())
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(p, _)| p)
+                    .ok_or_else(|| anyhow::anyhow!("No source with a usable confidence"))?;
+                
+                Ok((PriceData {
+                    symbol: symbol.name.clone(),
+                    ..best.clone()
+                }, None))
+            },
+        }
+    }
+    
+    /// Decimal prices, rejecting any non-finite value
+    fn normalized_finite(&self, prices: &[PriceData], symbol: &Symbol) -> Result<Vec<f64>> {
+        let values: Vec<f64> = prices.iter()
+            .map(|p| self.normalize_price(p))
+            .collect();
+        
+        if values.iter().any(|p| !p.is_finite()) {
+            anyhow::bail!("Non-finite normalized price for {}", symbol.name);
+        }
+        Ok(values)
+    }
+    
+    /// Reject the whole set when its median is zero or negative
+    ///
+    /// Deviation checks divide by the median, and a price can't be either, so
+    /// such a set only arises when most sources are broken.
+    fn check_positive_median(&self, values: &[f64], symbol: &Symbol) -> Result<()> {
+        if values.is_empty() {
+            return Ok(());
+        }
+        
+        let median = self.calculate_median(values.to_vec());
+        if median <= 0.0 {
+            return Err(OracleError::NonPositiveMedian {
+                symbol: symbol.name.clone(),
+                median,
+            }.into());
+        }
+        Ok(())
+    }
+    
+    /// Package a fixed-point price at `expo` and a decimal confidence as an aggregated price
+    fn build_aggregate(&self, prices: &[PriceData], symbol: &Symbol, price: i128, expo: i32, confidence: f64) -> Result<PriceData> {
+        if !confidence.is_finite() {
+            anyhow::bail!("Non-finite consensus confidence for {}: {}", symbol.name, confidence);
+        }
+        
         // Get the most recent timestamp
         let latest_timestamp = prices.iter().map(|p| p.timestamp).max().unwrap_or(0);
         
+        // Coarsen rather than saturate when the consensus doesn't fit i64 at `expo`
+        let (mut price, mut expo) = (price, expo);
+        let (fixed_price, fixed_confidence) = loop {
+            match (i64::try_from(price), to_fixed(confidence, expo)) {
+                (Ok(p), Some(c)) => break (p, c.max(0) as u64),
+                _ if expo < 0 => {
+                    price = div_round(price, 10);
+                    expo += 1;
+                },
+                _ => anyhow::bail!("Consensus for {} does not fit a fixed-point price: {}", symbol.name, fixed_to_decimal(price, expo)),
+            }
+        };
+        let price = fixed_to_decimal(price, expo);
+        
         // Create aggregated price data
         let aggregated = PriceData {
-            price: (consensus_price * 10_f64.powi(8)) as i64, // Convert back to integer with 8 decimals
-            confidence: consensus_confidence,
-            expo: -8, // Standard 8 decimal places
+            price: fixed_price,
+            confidence: fixed_confidence,
+            expo,
             timestamp: latest_timestamp,
             source: PriceSource::Aggregated,
             symbol: symbol.name.clone(),
+            ..Default::default()
         };
         
-        debug!("Aggregated price for {}: ${:.2}", symbol.name, consensus_price);
+        debug!("Aggregated price for {}: ${:.2}", symbol.name, price);
         
         Ok(aggregated)
     }
@@ -66,6 +678,90 @@ impl PriceAggregator {
         price_data.price as f64 / 10_f64.powi(-price_data.expo)
     }
     
+    /// Pick the output exponent among the sources per `ExpoSelection`
+    ///
+    /// The finest exponent falls back to the median when the sources are
+    /// inconsistent, i.e. some price or confidence would overflow i64 once
+    /// rescaled to it. `DEFAULT_EXPO` only applies without any sources.
+    fn common_expo(&self, prices: &[PriceData]) -> i32 {
+        let mut expos: Vec<i32> = prices.iter().map(|p| p.expo).collect();
+        if expos.is_empty() {
+            return DEFAULT_EXPO;
+        }
+        expos.sort_unstable();
+        let median = expos[expos.len() / 2];
+        let finest = expos[0];
+        
+        if self.expo_selection == ExpoSelection::Median {
+            return median;
+        }
+        
+        let consistent = prices.iter().all(|p| {
+            rescale(p.price, p.expo, finest).is_some()
+                && i64::try_from(p.confidence).ok()
+                    .and_then(|c| rescale(c, p.expo, finest))
+                    .is_some()
+        });
+        
+        if consistent {
+            finest
+        } else {
+            warn!("Sources have inconsistent exponents, falling back to median expo {}", median);
+            median
+        }
+    }
+    
+    /// Check that all sources agree to within an order of magnitude
+    pub fn is_scale_consistent(&self, prices: &[PriceData]) -> bool {
+        let values: Vec<f64> = prices.iter()
+            .map(|p| self.normalize_price(p))
+            .filter(|v| *v > 0.0 && v.is_finite())
+            .collect();
+        
+        let (Some(min), Some(max)) = (
+            values.iter().cloned().reduce(f64::min),
+            values.iter().cloned().reduce(f64::max),
+        ) else {
+            return true;
+        };
+        
+        max / min <= SCALE_MISMATCH_RATIO
+    }
+    
+    /// Indices of sources whose scale is off by more than an order of magnitude
+    ///
+    /// Sources are judged against `reference` (e.g. the last good aggregate) when
+    /// known, else against the median of three or more sources. Two disagreeing
+    /// sources with no reference can't be told apart, so both are suspect.
+    pub fn scale_suspects(&self, prices: &[PriceData], reference: Option<f64>) -> Vec<usize> {
+        let values: Vec<f64> = prices.iter()
+            .map(|p| self.normalize_price(p))
+            .collect();
+        
+        let anchor = match reference.filter(|r| *r > 0.0 && r.is_finite()) {
+            Some(reference) => reference,
+            None if values.len() >= 3 => self.calculate_median(values.clone()),
+            None => {
+                return if self.is_scale_consistent(prices) {
+                    Vec::new()
+                } else {
+                    (0..values.len()).collect()
+                };
+            }
+        };
+        
+        values.iter()
+            .enumerate()
+            .filter(|(_, &value)| {
+                value <= 0.0
+                    || !value.is_finite()
+                    || value / anchor > SCALE_MISMATCH_RATIO
+                    || anchor / value > SCALE_MISMATCH_RATIO
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+    
     /// Calculate median from a slice of f64 values
     fn calculate_median(&self, mut values: Vec<f64>) -> f64 {
         values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
@@ -73,173 +769,412 @@ impl PriceAggregator {
         if len == 0 {
             0.0
         } else if len % 2 == 0 {
-            (values[len / 2 - 1] + values[len / 2]) / 2.0
+            let (lower, upper) = (values[len / 2 - 1], values[len / 2]);
+            match self.median_tie_break {
+                MedianTieBreak::Average => (lower + upper) / 2.0,
+                MedianTieBreak::Lower => lower,
+                MedianTieBreak::Upper => upper,
+            }
         } else {
             values[len / 2]
         }
     }
 
-    /// Detect and filter statistical outliers
-    fn filter_outliers(&self, prices: &[f64], original_data: &[PriceData]) -> Result<Vec<PriceData>> {
+    /// Prices as i128 mantissas at `expo`
+    fn fixed_prices(&self, prices: &[PriceData], expo: i32, symbol: &Symbol) -> Result<Vec<i128>> {
+        prices.iter()
+            .map(|p| fixed_at(p.price, p.expo, expo)
+                .ok_or_else(|| anyhow::anyhow!("{:?} price for {} does not fit expo {}", p.source, symbol.name, expo)))
+            .collect()
+    }
+    
+    /// Median of fixed-point values, with the configured tie break; 0 when empty
+    fn fixed_median(&self, mut values: Vec<i128>) -> i128 {
+        values.sort_unstable();
+        let len = values.len();
+        if len == 0 {
+            0
+        } else if len.is_multiple_of(2) {
+            let (lower, upper) = (values[len / 2 - 1], values[len / 2]);
+            match self.median_tie_break {
+                MedianTieBreak::Average => lower + div_round(upper - lower, 2),
+                MedianTieBreak::Lower => lower,
+                MedianTieBreak::Upper => upper,
+            }
+        } else {
+            values[len / 2]
+        }
+    }
+    
+    /// Detect statistical outliers: per price, the reason it is one, or `None` to keep it
+    fn outlier_verdicts(&self, prices: &[f64]) -> Result<Vec<Option<String>>> {
         if prices.len() <= 2 {
-            return Ok(original_data.to_vec()); // Can't filter outliers with <= 2 data points
+            return Ok(vec![None; prices.len()]); // Can't filter outliers with <= 2 data points
+        }
+        
+        match self.outlier_method {
+            OutlierMethod::Mad => self.mad_filter(prices),
+            OutlierMethod::Iqr => self.iqr_filter(prices),
+            OutlierMethod::None => Ok(vec![None; prices.len()]),
+        }
+    }
+    
+    /// Exclude sources more than `max_deviation` from the symbol's reference source
+    ///
+    /// Several readings from the reference source are combined with the
+    /// configured deviation base. `None` when no reference is configured or it
+    /// didn't report a usable price, in which case normal outlier filtering applies.
+    fn reference_verdicts(&self, prices: &[PriceData], symbol: &Symbol) -> Option<Vec<Option<String>>> {
+        let reference_source = symbol.reference_source.as_ref()?;
+        let readings: Vec<f64> = prices.iter()
+            .filter(|p| &p.source == reference_source)
+            .map(|p| self.normalize_price(p))
+            .filter(|price| price.is_finite() && *price > 0.0)
+            .collect();
+        let reference = (!readings.is_empty()).then(|| self.deviation_reference(&readings));
+        
+        let Some(reference) = reference else {
+            warn!("Reference source {:?} unavailable for {}, using outlier filter", reference_source, symbol.name);
+            return None;
+        };
+        
+        let verdicts = prices.iter()
+            .map(|price| {
+                if &price.source == reference_source || price.is_within_deviation(reference, symbol.max_deviation) {
+                    return None;
+                }
+                warn!("Excluded {:?} price ${:.2}: beyond {}bp of reference ${:.2}",
+                    price.source, price.to_decimal(), symbol.max_deviation, reference);
+                Some(format!("more than {}bp from reference {:?} at {:.2}", symbol.max_deviation, reference_source, reference))
+            })
+            .collect();
+        
+        Some(verdicts)
+    }
+    
+    /// Keep the prices without an outlier verdict
+    fn apply_verdicts(&self, original_data: &[PriceData], verdicts: &[Option<String>]) -> Result<Vec<PriceData>> {
+        let filtered: Vec<PriceData> = original_data.iter()
+            .zip(verdicts)
+            .filter(|(_, verdict)| verdict.is_none())
+            .map(|(price, _)| price.clone())
+            .collect();
+        
+        if filtered.is_empty() {
+            anyhow::bail!("All prices were filtered as outliers");
         }
         
-        // Calculate median and median absolute deviation (MAD)
-        let median = self.calculate_median(prices.to_vec());
+        Ok(filtered)
+    }
+    
+    /// The configured deviation base over a set of prices
+    fn deviation_reference(&self, values: &[f64]) -> f64 {
+        match self.deviation_base {
+            DeviationBase::Median => self.calculate_median(values.to_vec()),
+            DeviationBase::Mean => values.mean(),
+            DeviationBase::TrimmedMean => trimmed_mean(values, TRIMMED_MEAN_FRACTION),
+        }
+    }
+    
+    /// Modified z-score filter based on the median absolute deviation (MAD)
+    ///
+    /// Deviations are taken from the configured deviation base, the median by default.
+    fn mad_filter(&self, prices: &[f64]) -> Result<Vec<Option<String>>> {
+        // Calculate the base and the median absolute deviation (MAD) from it
+        let median = self.deviation_reference(prices);
+        if !median.is_finite() {
+            anyhow::bail!("Non-finite {} in outlier filter", self.deviation_base);
+        }
         let deviations: Vec<f64> = prices.iter()
             .map(|&p| (p - median).abs())
             .collect();
         let mad = self.calculate_median(deviations);
         
         // Filter outliers using modified z-score method
-        let mut filtered = Vec::new();
-        for (i, &price) in prices.iter().enumerate() {
-            let modified_z_score = if mad > 0.0 {
-                0.6745 * (price - median).abs() / mad
-            } else {
-                0.0
-            };
-            
-            // Keep prices within 2.5 standard deviations (adjustable threshold)
-            if modified_z_score <= 2.5 {
-                filtered.push(original_data[i].clone());
-            } else {
+        let keep = prices.iter()
+            .map(|&price| {
+                // Zero MAD means most values agree exactly; nothing to score against
+                let modified_z_score = if mad > 0.0 && mad.is_finite() {
+                    0.6745 * (price - median).abs() / mad
+                } else {
+                    0.0
+                };
+                debug_assert!(modified_z_score.is_finite());
+                
+                // Keep prices within the z-score threshold
+                if modified_z_score <= MAD_Z_THRESHOLD {
+                    return None;
+                }
                 warn!("Filtered outlier price: ${:.2} (z-score: {:.2})", price, modified_z_score);
-            }
-        }
+                Some(format!("modified z-score {:.2} above {}", modified_z_score, MAD_Z_THRESHOLD))
+            })
+            .collect();
         
-        if filtered.is_empty() {
-            anyhow::bail!("All prices were filtered as outliers");
+        Ok(keep)
+    }
+    
+    /// Tukey fence filter: keep prices within 1.5 IQR of the quartiles
+    ///
+    /// The fences come from quartiles alone, so the deviation base doesn't apply.
+    fn iqr_filter(&self, prices: &[f64]) -> Result<Vec<Option<String>>> {
+        let mut sorted = prices.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        
+        let q1 = quantile(&sorted, 0.25);
+        let q3 = quantile(&sorted, 0.75);
+        if !q1.is_finite() || !q3.is_finite() {
+            anyhow::bail!("Non-finite quartiles in outlier filter");
         }
         
-        Ok(filtered)
+        let iqr = q3 - q1;
+        let lower = q1 - IQR_FENCE_MULTIPLIER * iqr;
+        let upper = q3 + IQR_FENCE_MULTIPLIER * iqr;
+        
+        let keep = prices.iter()
+            .map(|&price| {
+                if price >= lower && price <= upper {
+                    return None;
+                }
+                warn!("Filtered outlier price: ${:.2} (outside IQR fence ${:.2}-${:.2})", price, lower, upper);
+                Some(format!("outside IQR fence {:.2}-{:.2}", lower, upper))
+            })
+            .collect();
+        
+        Ok(keep)
     }
     
     /// Calculate consensus price using multiple statistical methods
-    fn calculate_consensus(&self, prices: &[PriceData]) -> Result<f64> {
-        let values: Vec<f64> = prices.iter()
-            .map(|p| self.normalize_price(p))
-            .collect();
+    /// Blend the median, confidence-weighted and volume-weighted prices
+    ///
+    /// The blend is computed on i128 mantissas at `expo` so large prices keep
+    /// every digit; the returned components are their decimal equivalents.
+    fn consensus_components(&self, prices: &[PriceData], expo: i32, symbol: &Symbol) -> Result<(i128, ConsensusComponents)> {
+        let values = self.fixed_prices(prices, expo, symbol)?;
         
         if values.is_empty() {
             anyhow::bail!("No valid prices for consensus calculation");
         }
         
         // Method 1: Median (most manipulation-resistant)
-        let median_price = self.calculate_median(values.clone());
+        let median_price = self.fixed_median(values.clone());
         
         // Method 2: Confidence-weighted average
-        let weighted_avg = self.confidence_weighted_average(prices)?;
+        let weighted_avg = self.confidence_weighted_average(prices, expo)?;
         
         // Method 3: Volume-weighted average (if volume data available)
-        let volume_weighted = self.volume_weighted_average(prices).unwrap_or(median_price);
+        let volume_weighted = self.volume_weighted_average(&values).unwrap_or(median_price);
         
-        // Combine methods with different weights
-        let consensus = median_price * 0.5 +           // 50% median (manipulation resistant)
-                       weighted_avg * 0.3 +           // 30% confidence weighted
-                       volume_weighted * 0.2;         // 20% volume weighted
+        // Combine methods with different weights, as basis points of the whole
+        let share_bp = |share: f64| (share * 10000.0).round() as i128;
+        let consensus = div_round(
+            median_price * share_bp(MEDIAN_SHARE) +
+                weighted_avg * share_bp(CONFIDENCE_WEIGHTED_SHARE) +
+                volume_weighted * share_bp(VOLUME_WEIGHTED_SHARE),
+            share_bp(MEDIAN_SHARE) + share_bp(CONFIDENCE_WEIGHTED_SHARE) + share_bp(VOLUME_WEIGHTED_SHARE),
+        );
         
+        let components = ConsensusComponents {
+            median: fixed_to_decimal(median_price, expo),
+            confidence_weighted: fixed_to_decimal(weighted_avg, expo),
+            volume_weighted: fixed_to_decimal(volume_weighted, expo),
+            shares: [MEDIAN_SHARE, CONFIDENCE_WEIGHTED_SHARE, VOLUME_WEIGHTED_SHARE],
+            consensus: fixed_to_decimal(consensus, expo),
+        };
         debug!("Consensus methods - Median: {:.2}, Weighted: {:.2}, Volume: {:.2}, Final: {:.2}",
-               median_price, weighted_avg, volume_weighted, consensus);
+               components.median, components.confidence_weighted, components.volume_weighted, components.consensus);
         
-        Ok(consensus)
+        Ok((consensus, components))
     }
     
-    /// Calculate confidence-weighted average
-    fn confidence_weighted_average(&self, prices: &[PriceData]) -> Result<f64> {
-        let mut weighted_sum = 0.0;
-        let mut total_weight = 0.0;
+    /// Calculate confidence-weighted average, as a mantissa at `expo`
+    ///
+    /// Weights are rounded to `WEIGHT_SCALE` so the sum stays in integers.
+    fn confidence_weighted_average(&self, prices: &[PriceData], expo: i32) -> Result<i128> {
+        let mut weighted_sum: i128 = 0;
+        let mut total_weight: i128 = 0;
         
         for price in prices {
-            let normalized_price = self.normalize_price(price);
-            
-            // Weight inversely proportional to confidence interval
-            // Lower confidence interval = higher weight
-            let confidence_ratio = price.confidence as f64 / price.price as f64;
-            let weight = 1.0 / (1.0 + confidence_ratio * 10.0); // Adjust multiplier as needed
+            // A non-positive price has no meaningful confidence ratio
+            let Some(weight) = self.source_weight(price) else {
+                continue;
+            };
+            let weight = (weight * WEIGHT_SCALE).round() as i128;
+            let value = fixed_at(price.price, price.expo, expo)
+                .ok_or_else(|| anyhow::anyhow!("{:?} price does not fit expo {}", price.source, expo))?;
             
-            weighted_sum += normalized_price * weight;
+            weighted_sum = value.checked_mul(weight)
+                .and_then(|term| weighted_sum.checked_add(term))
+                .ok_or_else(|| anyhow::anyhow!("Overflow in confidence-weighted sum"))?;
             total_weight += weight;
         }
         
-        if total_weight == 0.0 {
+        if total_weight == 0 {
             anyhow::bail!("Zero total weight in confidence calculation");
         }
         
-        Ok(weighted_sum / total_weight)
+        Ok(div_round(weighted_sum, total_weight))
     }
     
     /// Calculate volume-weighted average (placeholder for future enhancement)
-    fn volume_weighted_average(&self, prices: &[PriceData]) -> Option<f64> {
+    fn volume_weighted_average(&self, values: &[i128]) -> Option<i128> {
         // TODO: Implement volume weighting when volume data becomes available
         // For now, fall back to simple average
-        if prices.is_empty() {
+        if values.is_empty() {
             return None;
         }
         
-        let sum: f64 = prices.iter().map(|p| self.normalize_price(p)).sum();
-        Some(sum / prices.len() as f64)
+        Some(div_round(values.iter().sum(), values.len() as i128))
     }
     
-    /// Calculate aggregated confidence interval
-    fn calculate_confidence(&self, prices: &[PriceData]) -> u64 {
+    /// Calculate aggregated confidence interval in decimal format
+    fn calculate_confidence(&self, prices: &[PriceData]) -> f64 {
         if prices.is_empty() {
-            return u64::MAX; // Maximum uncertainty if no data
+            return f64::MAX; // Maximum uncertainty if no data
+        }
+        
+        // Ratios are undefined for non-positive prices
+        let ratios: Vec<f64> = prices.iter()
+            .filter_map(|p| p.relative_confidence())
+            .collect();
+        if ratios.is_empty() {
+            return f64::MAX;
         }
         
         // Calculate combined confidence using root mean square
-        let confidence_sum: f64 = prices.iter()
-            .map(|p| {
-                let conf_ratio = p.confidence as f64 / p.price as f64;
-                conf_ratio * conf_ratio
-            })
+        let confidence_sum: f64 = ratios.iter()
+            .map(|conf_ratio| conf_ratio * conf_ratio)
             .sum();
         
-        let rms_confidence = (confidence_sum / prices.len() as f64).sqrt();
-        let combined_price = prices.iter()
+        let rms_confidence = (confidence_sum / ratios.len() as f64).sqrt();
+        let values: Vec<f64> = prices.iter()
             .map(|p| self.normalize_price(p))
-            .sum::<f64>() / prices.len() as f64;
+            .collect();
+        let combined_price = values.iter().sum::<f64>() / values.len() as f64;
         
         // Convert back to absolute confidence value
-        (rms_confidence * combined_price * 10_f64.powi(8)) as u64
+        let source_uncertainty = rms_confidence * combined_price;
+        
+        // Sources that disagree widen the interval even if each is tight on its own
+        let dispersion = if values.len() >= 2 { values.iter().std_dev() } else { 0.0 };
+        
+        source_uncertainty.hypot(dispersion)
+    }
+    
+    /// Calculate rolling volatility statistics from price history
+    pub fn calculate_volatility(&self, history: &[PriceData]) -> VolatilityStats {
+        let mut ordered: Vec<&PriceData> = history.iter().collect();
+        ordered.sort_by_key(|p| p.timestamp);
+        
+        let values: Vec<f64> = ordered.iter()
+            .map(|p| self.normalize_price(p))
+            .collect();
+        
+        let returns: Vec<f64> = values.windows(2)
+            .filter(|w| w[0] != 0.0)
+            .map(|w| (w[1] - w[0]) / w[0])
+            .collect();
+        
+        let has_values = !values.is_empty();
+        
+        VolatilityStats {
+            sample_count: values.len(),
+            mean: has_values.then(|| values.iter().mean()),
+            std_dev: (values.len() >= 2).then(|| values.iter().std_dev()),
+            return_std_dev: (returns.len() >= 2).then(|| returns.iter().std_dev()),
+            high: has_values.then(|| Statistics::max(values.iter())),
+            low: has_values.then(|| Statistics::min(values.iter())),
+        }
+    }
+    
+    /// Bucket price history into OHLC candles, keeping the most recent `limit`
+    pub fn build_candles(&self, history: &[PriceData], interval_secs: i64, limit: usize) -> Vec<Candle> {
+        let mut ordered: Vec<&PriceData> = history.iter().collect();
+        ordered.sort_by_key(|p| p.timestamp);
+        
+        let mut candles: Vec<Candle> = Vec::new();
+        for price_data in ordered {
+            let bucket = price_data.timestamp - price_data.timestamp.rem_euclid(interval_secs);
+            let value = self.normalize_price(price_data);
+            
+            match candles.last_mut() {
+                Some(candle) if candle.t == bucket => {
+                    candle.high = candle.high.max(value);
+                    candle.low = candle.low.min(value);
+                    candle.close = value;
+                },
+                _ => candles.push(Candle {
+                    t: bucket,
+                    open: value,
+                    high: value,
+                    low: value,
+                    close: value,
+                }),
+            }
+        }
+        
+        let skip = candles.len().saturating_sub(limit);
+        candles.split_off(skip)
     }
     
-    /// Detect potential manipulation attempts
-    pub fn detect_manipulation(&self, prices: &[PriceData], historical_avg: f64) -> Vec<ManipulationAlert> {
+    /// Detect potential manipulation attempts against the symbol's rolling baseline
+    ///
+    /// A move only counts when it is both large in relative terms and more
+    /// than `MANIPULATION_Z_THRESHOLD` standard deviations from the baseline
+    /// mean, so ordinary volatility doesn't alert. Tight agreement between
+    /// sources is only suspicious when they moved off the baseline together;
+    /// honest feeds agree every cycle.
+    pub fn detect_manipulation(&self, prices: &[PriceData], baseline: Option<&Baseline>) -> Vec<ManipulationAlert> {
         let mut alerts = Vec::new();
         
+        // Meaningless without a positive baseline
+        let Some(baseline) = baseline.filter(|b| b.mean.is_finite() && b.mean > 0.0 && b.std_dev.is_finite()) else {
+            return alerts;
+        };
+        // Relative deviation from the mean, and its z-score; a flat window makes any move infinite
+        let anomaly = |value: f64| {
+            let distance = (value - baseline.mean).abs();
+            let z_score = if baseline.std_dev > 0.0 { distance / baseline.std_dev } else { f64::INFINITY };
+            (distance / baseline.mean, z_score)
+        };
+        
         let current_values: Vec<f64> = prices.iter()
             .map(|p| self.normalize_price(p))
             .collect();
         
         // Check for flash crash detection
         for (i, &price) in current_values.iter().enumerate() {
-            let deviation = (price - historical_avg).abs() / historical_avg;
+            let (deviation, z_score) = anomaly(price);
+            debug_assert!(deviation.is_finite());
             
-            if deviation > 0.1 { // 10% deviation threshold
+            if deviation > FLASH_CRASH_DEVIATION && z_score > MANIPULATION_Z_THRESHOLD {
                 alerts.push(ManipulationAlert {
                     alert_type: ManipulationType::FlashCrash,
                     source: prices[i].source.clone(),
                     deviation: deviation,
                     price: price,
-                    expected: historical_avg,
+                    expected: baseline.mean,
                 });
             }
         }
         
-        // Check for suspiciously tight clustering (potential coordination)
-        if current_values.len() > 1 {
+        // Check for suspiciously tight clustering away from the baseline (potential coordination)
+        if current_values.len() >= MIN_CLUSTERING_SOURCES {
             let price_variance = current_values.clone().variance();
             let mean_price = current_values.mean();
             
-            if price_variance / (mean_price * mean_price) < 0.0001 { // Very low relative variance
+            if mean_price == 0.0 || !mean_price.is_finite() || !price_variance.is_finite() {
+                return alerts; // Relative variance undefined
+            }
+            
+            let (deviation, z_score) = anomaly(mean_price);
+            let clustered = price_variance / (mean_price * mean_price) < 0.0001; // Very low relative variance
+            if clustered && deviation > MIN_CONSENSUS_MOVE && z_score > MANIPULATION_Z_THRESHOLD {
                 alerts.push(ManipulationAlert {
                     alert_type: ManipulationType::SuspiciousConsensus,
                     source: PriceSource::Aggregated,
-                    deviation: price_variance.sqrt() / mean_price,
+                    deviation,
                     price: mean_price,
-                    expected: historical_avg,
+                    expected: baseline.mean,
                 });
             }
         }
@@ -248,6 +1183,342 @@ impl PriceAggregator {
     }
 }
 
+/// A price from `aggregate_two_tier`
+#[derive(Debug, Clone)]
+pub struct TieredAggregate {
+    pub price: PriceData,
+    pub trace: Option<AggregationTrace>, // Consensus that produced the price, if one did
+    pub contributors: Vec<PriceSource>,  // Sources the price was built from, outliers excluded
+    pub relaxed: bool,                   // Produced by the retry under loosened limits
+}
+
+/// Ring buffer of the last K aggregated prices for a symbol
+///
+/// Feeds `detect_manipulation` a baseline maintained by the service itself
+/// rather than one supplied from outside.
+#[derive(Debug, Clone)]
+pub struct BaselineWindow {
+    capacity: usize,
+    values: VecDeque<f64>,
+}
+
+impl BaselineWindow {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            capacity,
+            values: VecDeque::with_capacity(capacity),
+        }
+    }
+    
+    /// Record an aggregated price, evicting the oldest once full
+    pub fn push(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+    
+    /// Rolling mean and standard deviation, `None` until a price was recorded
+    pub fn baseline(&self) -> Option<Baseline> {
+        if self.values.is_empty() {
+            return None;
+        }
+        Some(Baseline {
+            mean: self.values.iter().mean(),
+            std_dev: if self.values.len() >= 2 { self.values.iter().std_dev() } else { 0.0 },
+            samples: self.values.len(),
+        })
+    }
+}
+
+/// Rolling statistics of recent aggregated prices
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub samples: usize,
+}
+
+/// Strategies a symbol walks through, plain consensus when none are configured
+fn strategy_chain(symbol: &Symbol) -> &[AggregationStrategy] {
+    if symbol.fallback_chain.is_empty() {
+        &[AggregationStrategy::Consensus]
+    } else {
+        &symbol.fallback_chain
+    }
+}
+
+/// Copy of a symbol with the limits a relaxed retry runs under
+///
+/// `max_deviation` is scaled by `multiplier` and quorums fall back to plain
+/// consensus. Strict symbols stay strict, so their sources must still agree
+/// within the scaled limit; other chains end in `HighestConfidence` so a
+/// single usable source still yields a price.
+fn relaxed_symbol(symbol: &Symbol, multiplier: u64) -> Symbol {
+    let mut chain: Vec<AggregationStrategy> = strategy_chain(symbol).iter()
+        .map(|&strategy| match strategy {
+            AggregationStrategy::Quorum(_) => AggregationStrategy::Consensus,
+            other => other,
+        })
+        .collect();
+    chain.dedup();
+    if !symbol.strict_consensus && !chain.contains(&AggregationStrategy::HighestConfidence) {
+        chain.push(AggregationStrategy::HighestConfidence);
+    }
+    
+    Symbol {
+        max_deviation: symbol.max_deviation.saturating_mul(multiplier),
+        fallback_chain: chain,
+        ..symbol.clone()
+    }
+}
+
+/// Convert a price into another quote currency through a cross-rate leg
+///
+/// A `TARGET/QUOTE` leg divides (`invert_leg`), a `QUOTE/TARGET` leg
+/// multiplies. Relative confidences add in quadrature, and the result keeps
+/// the older of the two timestamps so staleness checks stay conservative.
+pub fn cross_price(price: &PriceData, leg: &PriceData, invert_leg: bool, symbol: &str) -> Result<PriceData> {
+    let base = price.to_decimal();
+    let rate = leg.to_decimal();
+    if !(base.is_finite() && base > 0.0 && rate.is_finite() && rate > 0.0) {
+        anyhow::bail!("Cannot convert {} at {} through {} at {}", price.symbol, base, leg.symbol, rate);
+    }
+    
+    let value = if invert_leg { base / rate } else { base * rate };
+    let relative_confidence = (price.confidence_to_decimal() / base).hypot(leg.confidence_to_decimal() / rate);
+    let confidence = value * relative_confidence;
+    
+    let expo = price.expo.min(leg.expo);
+    let scale = 10_f64.powi(-expo);
+    if !((value * scale).is_finite() && value * scale < i64::MAX as f64 && (confidence * scale).is_finite()) {
+        anyhow::bail!("Converted price {} for {} does not fit expo {}", value, symbol, expo);
+    }
+    
+    Ok(PriceData {
+        price: (value * scale).round() as i64,
+        confidence: (confidence * scale).round() as u64,
+        expo,
+        timestamp: price.timestamp.min(leg.timestamp),
+        source: PriceSource::Aggregated,
+        symbol: symbol.to_string(),
+        ..Default::default()
+    })
+}
+
+/// The reciprocal of a price, e.g. USD/BTC from BTC/USD
+///
+/// The relative confidence carries over unchanged. The exponent is chosen so
+/// the inverted mantissa keeps as many significant digits as the original,
+/// and at least `MIN_INVERTED_DIGITS`.
+pub fn invert_price(price: &PriceData, symbol: &str) -> Result<PriceData> {
+    let value = price.to_decimal();
+    if !(value.is_finite() && value > 0.0) {
+        anyhow::bail!("Cannot invert {} at {}", price.symbol, value);
+    }
+    
+    let inverted = 1.0 / value;
+    let confidence = inverted * (price.confidence_to_decimal() / value);
+    
+    // With an n-digit mantissa m, 10^(n - 1 + digits) / m has the wanted digits
+    let magnitude = price.price.unsigned_abs().checked_ilog10().unwrap_or(0) as i32;
+    let digits = (magnitude + 1).max(MIN_INVERTED_DIGITS);
+    let expo = -(digits - 1 + magnitude + price.expo);
+    let scale = 10_f64.powi(-expo);
+    if !((inverted * scale).is_finite() && inverted * scale < i64::MAX as f64 && (confidence * scale).is_finite()) {
+        anyhow::bail!("Inverted price {} for {} does not fit expo {}", inverted, symbol, expo);
+    }
+    
+    Ok(PriceData {
+        price: (inverted * scale).round() as i64,
+        confidence: (confidence * scale).round() as u64,
+        expo,
+        symbol: symbol.to_string(),
+        ..price.clone()
+    })
+}
+
+/// Weight inversely proportional to the relative confidence interval
+///
+/// Lower confidence interval = higher weight; `None` for non-positive prices.
+/// Readings that report their oracle count are scaled by `oracle_count_factor`.
+fn confidence_weight(price: &PriceData) -> Option<f64> {
+    price.relative_confidence()
+        .map(|ratio| 1.0 / (1.0 + ratio * 10.0)) // Adjust multiplier as needed
+        .map(|weight| weight * oracle_count_factor(price))
+}
+
+/// Weight multiplier for the number of oracles behind a reading, 1 when unreported
+///
+/// Grows with the square root so a large round can't drown out the other sources.
+fn oracle_count_factor(price: &PriceData) -> f64 {
+    match price.oracle_count {
+        Some(count) if count > 0 => (count as f64 / REFERENCE_ORACLE_COUNT).sqrt(),
+        _ => 1.0,
+    }
+}
+
+/// Linearly interpolated quantile of an already sorted slice
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let position = q * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Mean after dropping `fraction` of the values, rounded down, from each end
+///
+/// Sets too small to lose a whole value on each side give the plain mean.
+fn trimmed_mean(values: &[f64], fraction: f64) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    
+    let trim = (sorted.len() as f64 * fraction).floor() as usize;
+    sorted[trim..sorted.len() - trim].mean()
+}
+
+/// Scale a decimal value into fixed point at `expo`, `None` when it doesn't fit i64
+fn to_fixed(value: f64, expo: i32) -> Option<i64> {
+    let scaled = (value * 10_f64.powi(-expo)).round();
+    (scaled.abs() < i64::MAX as f64).then_some(scaled as i64)
+}
+
+/// A mantissa at `from_expo` re-expressed at `to_expo` in i128, rounding when
+/// coarsening; `None` when the scale factor itself overflows
+fn fixed_at(value: i64, from_expo: i32, to_expo: i32) -> Option<i128> {
+    let value = value as i128;
+    let shift = from_expo.checked_sub(to_expo)?;
+    let factor = 10_i128.checked_pow(shift.unsigned_abs())?;
+    if shift >= 0 {
+        value.checked_mul(factor)
+    } else {
+        Some(div_round(value, factor))
+    }
+}
+
+/// Integer division rounding half away from zero; `denominator` must be positive
+fn div_round(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    let remainder = (numerator % denominator).abs();
+    if remainder >= denominator - remainder {
+        quotient + numerator.signum()
+    } else {
+        quotient
+    }
+}
+
+/// Decimal value of an i128 mantissa at `expo`
+fn fixed_to_decimal(value: i128, expo: i32) -> f64 {
+    value as f64 / 10_f64.powi(-expo)
+}
+
+/// Rescale a fixed-point value from one exponent to a finer one, `None` on overflow
+fn rescale(value: i64, from_expo: i32, to_expo: i32) -> Option<i64> {
+    let shift = u32::try_from(from_expo.checked_sub(to_expo)?).ok()?;
+    value.checked_mul(10_i64.checked_pow(shift)?)
+}
+
+/// Realized volatility over a window of historical prices
+#[derive(Debug, Clone, Serialize)]
+pub struct VolatilityStats {
+    pub sample_count: usize,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,        // Standard deviation of prices
+    pub return_std_dev: Option<f64>, // Standard deviation of period-over-period returns
+    pub high: Option<f64>,
+    pub low: Option<f64>,
+}
+
+/// Step-by-step derivation of an aggregated price, for debugging disputes
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregationTrace {
+    pub outlier_method: OutlierMethod,
+    pub sources: Vec<SourceTrace>,
+    pub components: ConsensusComponents,
+    pub confidence: f64,
+}
+
+/// One source's input and what the aggregator did with it
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceTrace {
+    pub source: PriceSource,
+    pub price: f64,
+    pub confidence: f64,
+    pub excluded_reason: Option<String>, // Why the outlier filter dropped it
+    pub weight: Option<f64>,             // Normalized share of the confidence-weighted average
+}
+
+/// Intermediate prices blended into the consensus
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusComponents {
+    pub median: f64,
+    pub confidence_weighted: f64,
+    pub volume_weighted: f64,
+    pub shares: [f64; 3], // Median, confidence-weighted, volume-weighted
+    pub consensus: f64,
+}
+
+/// Order-independent hash of the values each source reported, ignoring timestamps,
+/// together with the symbol's configuration so an edited symbol re-aggregates
+fn source_fingerprint(prices: &[PriceData], symbol: &Symbol) -> u64 {
+    let mut inputs: Vec<(String, i64, u64, i32, Option<u32>)> = prices.iter()
+        .map(|p| (format!("{:?}", p.source), p.price, p.confidence, p.expo, p.oracle_count))
+        .collect();
+    inputs.sort();
+    
+    let mut hasher = DefaultHasher::new();
+    inputs.hash(&mut hasher);
+    serde_json::to_string(symbol).unwrap_or_default().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Aggregation parameters in effect for a symbol after defaults are applied
+#[derive(Debug, Clone, Serialize)]
+pub struct AggregationConfig {
+    pub symbol: String,
+    pub strategies: Vec<AggregationStrategy>, // Fallback chain, tried in order
+    pub strict_consensus: bool,
+    pub reference_source: Option<PriceSource>,
+    pub min_sources: usize,
+    pub outlier_method: OutlierMethod,
+    pub outlier_threshold: Option<f64>,       // Z-score for MAD, fence multiplier for IQR
+    pub median_tie_break: MedianTieBreak,
+    pub expo_selection: ExpoSelection,
+    pub deviation_base: DeviationBase,        // Statistic max_deviation is measured from
+    pub max_sources: Option<usize>,           // None when every source takes part
+    pub relaxed_retry_multiplier: Option<u64>, // Deviation multiplier of the retry after a failure; None = no retry
+    pub shares: ConsensusShares,
+    pub max_deviation_bp: u64,
+    pub max_confidence_bp: u64,
+    pub max_staleness_secs: i64,
+    pub sources: Vec<PriceSource>,            // Sources fetched for the symbol
+}
+
+/// Share of each method in the blended consensus price
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsensusShares {
+    pub median: f64,
+    pub confidence_weighted: f64,
+    pub volume_weighted: f64,
+}
+
+/// OHLC candle starting at unix time `t`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Candle {
+    pub t: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+}
+
 /// Types of manipulation that can be detected
 #[derive(Debug, Clone)]
 pub enum ManipulationType {
@@ -280,7 +1551,490 @@ mod tests {
             max_staleness: 300,
             max_confidence: 10000,
             max_deviation: 100,
+            ..Default::default()
+        }
+    }
+    
+    /// BTC/USD reading at expo -8 with a $10 confidence, at timestamp 1000
+    fn btc(price: i64, source: PriceSource) -> PriceData {
+        btc_at(price, 1000, source)
+    }
+    
+    /// `btc` at the given timestamp
+    fn btc_at(price: i64, timestamp: i64, source: PriceSource) -> PriceData {
+        PriceData {
+            price,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp,
+            source,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        }
+    }
+    
+    #[test]
+    fn test_relaxed_retry_marks_degraded() {
+        let mut symbol = create_test_symbol();
+        symbol.strict_consensus = true;
+        
+        let prices = vec![
+            btc(50000_00000000, PriceSource::Pyth),
+            btc(53000_00000000, PriceSource::Switchboard), // 6% away
+        ];
+        
+        // Without a retry the strict failure stands
+        let strict = PriceAggregator::new();
+        assert!(strict.aggregate_two_tier(&prices, &symbol).is_err());
+        
+        // Strict symbols stay strict when relaxed: 600bp apart is beyond 5x 100bp
+        let narrow = PriceAggregator::new().with_relaxed_retry(Some(5));
+        let err = narrow.aggregate_two_tier(&prices, &symbol).unwrap_err();
+        assert!(err.chain().any(|cause| matches!(
+            cause.downcast_ref::<OracleError>(),
+            Some(OracleError::DeviationExceeded { .. })
+        )));
+        
+        // Within 10x 100bp the relaxed attempt publishes, flagged as degraded
+        let aggregator = PriceAggregator::new().with_relaxed_retry(Some(10));
+        let relaxed = aggregator.aggregate_two_tier(&prices, &symbol).unwrap();
+        assert!(relaxed.relaxed);
+        assert!(relaxed.price.degraded);
+        
+        // Prices passing the strict checks are not degraded
+        let agreeing = vec![btc(50000_00000000, PriceSource::Pyth), btc(50010_00000000, PriceSource::Switchboard)];
+        let passed = aggregator.aggregate_two_tier(&agreeing, &symbol).unwrap();
+        assert!(!passed.relaxed);
+        assert!(!passed.price.degraded);
+        
+        // Unmet quorums relax to plain consensus
+        symbol.strict_consensus = false;
+        symbol.fallback_chain = vec![AggregationStrategy::Quorum(3)];
+        assert!(strict.aggregate_two_tier(&agreeing, &symbol).is_err());
+        let quorum = aggregator.aggregate_two_tier(&agreeing, &symbol).unwrap();
+        assert!(quorum.relaxed);
+        assert_eq!(quorum.price.strategy, Some(AggregationStrategy::Consensus));
+        
+        // A single-source symbol is degraded without having been relaxed
+        symbol.fallback_chain = Vec::new();
+        symbol.sources = Some(vec![PriceSource::Pyth]);
+        let single = aggregator.aggregate_two_tier(&agreeing[..1], &symbol).unwrap();
+        assert!(single.price.degraded);
+        assert!(!single.relaxed);
+    }
+    
+    #[test]
+    fn test_single_source_fast_path() {
+        let aggregator = PriceAggregator::new();
+        let mut symbol = create_test_symbol();
+        symbol.sources = Some(vec![PriceSource::Pyth]);
+        
+        let pyth = PriceData {
+            price: 50012_34567890,
+            confidence: 12_34567890,
+            expo: -10,
+            timestamp: 1000,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // Handed back as the aggregate, only flagged as uncorroborated
+        let aggregated = aggregator.aggregate_with_fallback(std::slice::from_ref(&pyth), &symbol).unwrap();
+        assert_eq!(aggregated.price, pyth.price);
+        assert_eq!(aggregated.confidence, pyth.confidence);
+        assert_eq!(aggregated.expo, pyth.expo);
+        assert_eq!(aggregated.timestamp, pyth.timestamp);
+        assert_eq!(aggregated.source, PriceSource::Aggregated);
+        assert!(aggregated.degraded);
+        assert_eq!(aggregated.strategy, None);
+        
+        // A symbol open to every source still runs consensus when one reports
+        symbol.sources = None;
+        let aggregated = aggregator.aggregate_with_fallback(&[pyth], &symbol).unwrap();
+        assert_eq!(aggregated.source, PriceSource::Aggregated);
+        assert!(!aggregated.degraded);
+    }
+    
+    #[test]
+    fn test_non_positive_median_rejected() {
+        let aggregator = PriceAggregator::new();
+        let symbol = create_test_symbol();
+        
+        let median_of = |prices: &[PriceData]| {
+            match aggregator.aggregate_with_fallback(prices, &symbol).unwrap_err().downcast_ref::<OracleError>() {
+                Some(OracleError::NonPositiveMedian { median, .. }) => *median,
+                other => panic!("expected NonPositiveMedian, got {:?}", other),
+            }
+        };
+        
+        let all_zero = vec![
+            btc(0, PriceSource::Pyth),
+            btc(0, PriceSource::Switchboard),
+            btc(0, PriceSource::Internal),
+        ];
+        assert_eq!(median_of(&all_zero), 0.0);
+        
+        let mostly_negative = vec![
+            btc(-50000_00000000, PriceSource::Pyth),
+            btc(-49990_00000000, PriceSource::Switchboard),
+            btc(50000_00000000, PriceSource::Internal),
+        ];
+        assert!(median_of(&mostly_negative) < 0.0);
+        
+        // Consensus itself rejects them too, not just the fallback walk
+        assert!(aggregator.aggregate_prices(&all_zero, &symbol).is_err());
+        
+        // A single bad source among good ones leaves a positive median to work with
+        let mixed = vec![
+            btc(-100_00000000, PriceSource::Pyth),
+            btc(50000_00000000, PriceSource::Switchboard),
+            btc(50010_00000000, PriceSource::Internal),
+        ];
+        assert!(aggregator.check_positive_median(&aggregator.normalized_finite(&mixed, &symbol).unwrap(), &symbol).is_ok());
+    }
+    
+    #[test]
+    fn test_strict_consensus_requires_unanimity() {
+        let aggregator = PriceAggregator::new();
+        let mut symbol = create_test_symbol();
+        
+        let prices = vec![
+            btc(50000_00000000, PriceSource::Pyth),
+            btc(50010_00000000, PriceSource::Switchboard),
+            btc(50005_00000000, PriceSource::Internal),
+            btc(53000_00000000, PriceSource::Internal), // 6% away
+        ];
+        
+        // Blended mode filters the divergent source and publishes
+        assert!(aggregator.aggregate_with_fallback(&prices, &symbol).is_ok());
+        
+        // Strict mode refuses while any source disagrees
+        symbol.strict_consensus = true;
+        let err = aggregator.aggregate_with_fallback(&prices, &symbol).unwrap_err();
+        match err.downcast_ref::<OracleError>() {
+            Some(OracleError::DeviationExceeded { price_source, value, deviation_bp, .. }) => {
+                assert_eq!(*price_source, PriceSource::Internal);
+                assert_eq!(*value, 53000.0);
+                assert!(*deviation_bp > 500.0);
+            },
+            other => panic!("expected DeviationExceeded, got {:?}", other),
+        }
+        
+        // And publishes once the live sources agree
+        assert!(aggregator.aggregate_with_fallback(&prices[..3], &symbol).is_ok());
+    }
+    
+    #[test]
+    fn test_strict_consensus_is_pairwise() {
+        let aggregator = PriceAggregator::new();
+        let symbol = create_test_symbol(); // 100bp
+        
+        // Each within 60bp of the median, but the outer two are 121bp apart
+        let prices = vec![
+            btc(99_40000000, PriceSource::Pyth),
+            btc(100_00000000, PriceSource::Switchboard),
+            btc(100_60000000, PriceSource::Internal),
+        ];
+        let err = aggregator.check_unanimity(&prices, &symbol).unwrap_err();
+        match err.downcast_ref::<OracleError>() {
+            Some(OracleError::DeviationExceeded { median, deviation_bp, .. }) => {
+                assert_eq!(*median, 100.0);
+                assert_eq!(deviation_bp.round(), 121.0);
+            },
+            other => panic!("expected DeviationExceeded, got {:?}", other),
+        }
+        
+        // Pairs within the limit pass
+        assert!(aggregator.check_unanimity(&prices[..2], &symbol).is_ok());
+    }
+    
+    #[test]
+    fn test_strict_consensus_reports_against_deviation_base() {
+        let mut symbol = create_test_symbol();
+        symbol.max_deviation = 190;
+        
+        let prices = vec![
+            btc(100_00000000, PriceSource::Pyth),
+            btc(100_00000000, PriceSource::Pyth),
+            btc(100_00000000, PriceSource::Pyth),
+            btc(103_00000000, PriceSource::Switchboard),
+            btc(103_00000000, PriceSource::Switchboard),
+        ];
+        let reported = |base: DeviationBase| {
+            let aggregator = PriceAggregator::new().with_deviation_base(base);
+            let err = aggregator.check_unanimity(&prices, &symbol).unwrap_err();
+            match err.downcast_ref::<OracleError>() {
+                Some(OracleError::DeviationExceeded { price_source, reference, deviation_bp, .. }) => {
+                    (price_source.clone(), (reference * 100.0).round(), deviation_bp.round())
+                },
+                other => panic!("expected DeviationExceeded, got {:?}", other),
+            }
+        };
+        
+        // The 300bp spread blocks under every base; the base only sets what the
+        // outlying side is reported against
+        assert_eq!(reported(DeviationBase::Median), (PriceSource::Switchboard, 10000.0, 300.0));
+        assert_eq!(reported(DeviationBase::Mean), (PriceSource::Switchboard, 10120.0, 300.0));
+        assert_eq!(reported(DeviationBase::TrimmedMean), (PriceSource::Switchboard, 10100.0, 300.0));
+    }
+    
+    #[test]
+    fn test_deviation_base_changes_outlier_filtering() {
+        let values = [100.0, 100.5, 101.0, 104.0, 110.0];
+        let flagged = |base: DeviationBase| {
+            let aggregator = PriceAggregator::new().with_deviation_base(base);
+            aggregator.outlier_verdicts(&values).unwrap().iter()
+                .enumerate()
+                .filter(|(_, verdict)| verdict.is_some())
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>()
+        };
+        
+        // Median 101, MAD 1: 110 scores 6.07
+        assert_eq!(flagged(DeviationBase::Median), vec![4]);
+        
+        // Mean 103.1 is dragged toward 110, MAD 2.6: 110 scores only 1.79
+        assert!(flagged(DeviationBase::Mean).is_empty());
+        
+        // Trimmed mean 101.83, MAD 1.83: 110 scores 3.0
+        assert_eq!(flagged(DeviationBase::TrimmedMean), vec![4]);
+        
+        // A reference source read twice is judged by the base of its readings
+        let mut symbol = create_test_symbol();
+        symbol.reference_source = Some(PriceSource::Pyth);
+        let prices = vec![
+            btc(100_00000000, PriceSource::Pyth),
+            btc(102_00000000, PriceSource::Pyth),
+            btc(100_50000000, PriceSource::Switchboard),
+        ];
+        let lower = PriceAggregator::new().with_median_tie_break(MedianTieBreak::Lower);
+        assert!(lower.reference_verdicts(&prices, &symbol).unwrap()[2].is_none());
+        let upper = PriceAggregator::new().with_median_tie_break(MedianTieBreak::Upper);
+        assert!(upper.reference_verdicts(&prices, &symbol).unwrap()[2].is_some());
+    }
+    
+    #[test]
+    fn test_deviation_base_parsing() {
+        assert_eq!("median".parse::<DeviationBase>().unwrap(), DeviationBase::Median);
+        assert_eq!("Mean".parse::<DeviationBase>().unwrap(), DeviationBase::Mean);
+        assert_eq!("trimmed_mean".parse::<DeviationBase>().unwrap(), DeviationBase::TrimmedMean);
+        assert!("mode".parse::<DeviationBase>().is_err());
+        assert_eq!(trimmed_mean(&[1.0, 2.0, 3.0, 4.0], TRIMMED_MEAN_FRACTION), 2.5);
+    }
+    
+    #[test]
+    fn test_effective_config_resolves_defaults() {
+        let aggregator = PriceAggregator::new().with_outlier_method(OutlierMethod::Iqr);
+        let mut symbol = create_test_symbol();
+        
+        let config = aggregator.effective_config(&symbol, vec![PriceSource::Pyth]);
+        assert_eq!(config.strategies, vec![AggregationStrategy::Consensus]);
+        assert_eq!(config.outlier_threshold, Some(1.5));
+        assert_eq!(config.max_deviation_bp, 100);
+        assert!((config.shares.median + config.shares.confidence_weighted + config.shares.volume_weighted - 1.0).abs() < 1e-12);
+        
+        symbol.fallback_chain = vec![AggregationStrategy::Quorum(2), AggregationStrategy::Median];
+        let config = aggregator.effective_config(&symbol, vec![PriceSource::Pyth]);
+        assert_eq!(config.strategies, symbol.fallback_chain);
+    }
+    
+    #[test]
+    fn test_invert_price() {
+        let btc_usd = PriceData {
+            price: 50000_00000000,
+            confidence: 25_00000000,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // USD/BTC: 1/50000 with the same 5bp relative confidence
+        let usd_btc = invert_price(&btc_usd, "USD/BTC").unwrap();
+        assert_eq!(usd_btc.symbol, "USD/BTC");
+        assert!((usd_btc.to_decimal() - 0.00002).abs() < 1e-15);
+        assert!((usd_btc.confidence_percentage() - btc_usd.confidence_percentage()).abs() < 1e-9);
+        assert_eq!(usd_btc.timestamp, btc_usd.timestamp);
+        
+        // Inverting back restores the original orientation
+        let round_trip = invert_price(&usd_btc, "BTC/USD").unwrap();
+        assert!((round_trip.to_decimal() - 50000.0).abs() < 1e-6);
+        assert!((round_trip.confidence_to_decimal() - 25.0).abs() < 1e-6);
+        
+        // Short mantissas still get enough digits
+        let two = PriceData { price: 3, confidence: 0, expo: 0, ..btc_usd.clone() };
+        assert!((invert_price(&two, "X").unwrap().to_decimal() - 1.0 / 3.0).abs() < 1e-8);
+        
+        let zero = PriceData { price: 0, ..btc_usd };
+        assert!(invert_price(&zero, "USD/BTC").is_err());
+    }
+    
+    #[test]
+    fn test_cross_price() {
+        let price = |value: i64, confidence: u64, timestamp: i64, symbol: &str| PriceData {
+            price: value,
+            confidence,
+            expo: -8,
+            timestamp,
+            source: PriceSource::Aggregated,
+            symbol: symbol.to_string(),
+            ..Default::default()
+        };
+        let btc_usd = price(60000_00000000, 30_00000000, 1000, "BTC/USD"); // 5bp
+        let eur_usd = price(1_20000000, 60000, 990, "EUR/USD");            // 5bp
+        let usd_eur = price(50000000, 0, 995, "USD/EUR");
+        
+        let via_eur_usd = cross_price(&btc_usd, &eur_usd, true, "BTC/EUR").unwrap();
+        assert_eq!(via_eur_usd.price, 50000_00000000);
+        assert_eq!(via_eur_usd.timestamp, 990);
+        assert_eq!(via_eur_usd.symbol, "BTC/EUR");
+        
+        // Relative confidences combine in quadrature: 5bp and 5bp give ~7.07bp
+        let relative = via_eur_usd.confidence_to_decimal() / via_eur_usd.to_decimal();
+        assert!((relative - 0.0005 * 2f64.sqrt()).abs() < 1e-9);
+        
+        let via_usd_eur = cross_price(&btc_usd, &usd_eur, false, "BTC/EUR").unwrap();
+        assert_eq!(via_usd_eur.price, 30000_00000000);
+        assert_eq!(via_usd_eur.confidence, 15_00000000);
+        
+        let zero = price(0, 0, 1000, "EUR/USD");
+        assert!(cross_price(&btc_usd, &zero, true, "BTC/EUR").is_err());
+    }
+    
+    #[test]
+    fn test_reference_source_filter() {
+        let aggregator = PriceAggregator::new().with_outlier_method(OutlierMethod::None);
+        let mut symbol = create_test_symbol(); // max_deviation 100bp
+        
+        let prices = vec![
+            btc(50000_00000000, PriceSource::Pyth),
+            btc(50200_00000000, PriceSource::Switchboard), // 40bp off
+            btc(52000_00000000, PriceSource::Internal),    // 400bp off
+        ];
+        
+        // Without a reference every price sways the aggregate
+        let (_, trace) = aggregator.aggregate_prices_traced(&prices, &symbol).unwrap();
+        assert!(trace.sources.iter().all(|s| s.excluded_reason.is_none()));
+        
+        symbol.reference_source = Some(PriceSource::Pyth);
+        let (aggregated, trace) = aggregator.aggregate_prices_traced(&prices, &symbol).unwrap();
+        assert!(trace.sources[0].excluded_reason.is_none());
+        assert!(trace.sources[1].excluded_reason.is_none());
+        assert!(trace.sources[2].excluded_reason.as_deref().unwrap().contains("reference"));
+        assert!(aggregated.to_decimal() < 50200.0);
+        
+        // A missing reference falls back to normal outlier filtering
+        let (_, trace) = aggregator.aggregate_prices_traced(&prices[1..], &symbol).unwrap();
+        assert!(trace.sources.iter().all(|s| s.excluded_reason.is_none()));
+    }
+    
+    #[test]
+    fn test_median_tie_break() {
+        let values = vec![50020.0, 50000.0, 50010.0, 50030.0];
+        
+        let average = PriceAggregator::new();
+        assert_eq!(average.calculate_median(values.clone()), 50015.0);
+        
+        let lower = PriceAggregator::new().with_median_tie_break(MedianTieBreak::Lower);
+        assert_eq!(lower.calculate_median(values.clone()), 50010.0);
+        
+        let upper = PriceAggregator::new().with_median_tie_break(MedianTieBreak::Upper);
+        assert_eq!(upper.calculate_median(values.clone()), 50020.0);
+        
+        // Odd counts have a single middle value whatever the policy
+        assert_eq!(upper.calculate_median(values[..3].to_vec()), 50010.0);
+        assert_eq!("LOWER".parse::<MedianTieBreak>().unwrap(), MedianTieBreak::Lower);
+    }
+    
+    #[test]
+    fn test_two_tier_trace_matches_price() {
+        let mut symbol = create_test_symbol();
+        let prices = vec![
+            btc(50000_00000000, PriceSource::Pyth),
+            btc(50010_00000000, PriceSource::Switchboard),
+        ];
+        
+        // Consensus hands back the trace of the aggregation it published
+        let aggregator = PriceAggregator::new().with_memoization(true);
+        let tiered = aggregator.aggregate_two_tier(&prices, &symbol).unwrap();
+        let trace = tiered.trace.expect("consensus leaves a trace");
+        assert!((trace.components.consensus - tiered.price.to_decimal()).abs() < 1e-6);
+        
+        // A memo hit returns the same trace rather than none
+        let reused = aggregator.aggregate_two_tier(&prices, &symbol).unwrap();
+        assert_eq!(reused.trace.unwrap().components.consensus, trace.components.consensus);
+        
+        // An outlier the consensus dropped is not among the contributors
+        let mut with_outlier = prices.clone();
+        with_outlier.push(btc(60000_00000000, PriceSource::Internal));
+        let filtered = PriceAggregator::new().aggregate_two_tier(&with_outlier, &symbol).unwrap();
+        assert_eq!(filtered.contributors, vec![PriceSource::Pyth, PriceSource::Switchboard]);
+        
+        // Strategies that run no consensus have nothing to trace
+        symbol.fallback_chain = vec![AggregationStrategy::HighestConfidence];
+        let best = PriceAggregator::new().aggregate_two_tier(&prices, &symbol).unwrap();
+        assert!(best.trace.is_none());
+        assert_eq!(best.contributors, vec![best.price.source.clone()]);
+    }
+    
+    #[test]
+    fn test_aggregation_memoization() {
+        let aggregator = PriceAggregator::new().with_memoization(true);
+        let mut symbol = create_test_symbol();
+        let first = vec![
+            btc_at(50000_00000000, 1000, PriceSource::Pyth),
+            btc_at(50010_00000000, 1000, PriceSource::Switchboard),
+        ];
+        let computed = aggregator.aggregate_memoized(&first, &symbol).unwrap();
+        assert_eq!(computed.strategy, Some(AggregationStrategy::Consensus));
+        
+        // Same values in another order and later: a hit skips aggregation entirely
+        let quiet = vec![
+            btc_at(50010_00000000, 1005, PriceSource::Switchboard),
+            btc_at(50000_00000000, 1004, PriceSource::Pyth),
+        ];
+        let reused = aggregator.aggregate_memoized(&quiet, &symbol).unwrap();
+        assert_eq!(reused.price, computed.price);
+        assert_eq!(reused.strategy, Some(AggregationStrategy::Consensus));
+        assert_eq!(reused.timestamp, 1005);
+        
+        // A changed strategy chain recomputes even with the same values
+        symbol.fallback_chain = vec![AggregationStrategy::HighestConfidence];
+        let reconfigured = aggregator.aggregate_memoized(&quiet, &symbol).unwrap();
+        assert_eq!(reconfigured.strategy, Some(AggregationStrategy::HighestConfidence));
+        
+        // So does any changed value
+        let moved = vec![
+            btc_at(50020_00000000, 1006, PriceSource::Switchboard),
+            btc_at(50000_00000000, 1006, PriceSource::Pyth),
+        ];
+        let recomputed = aggregator.aggregate_memoized(&moved, &symbol).unwrap();
+        assert_eq!(recomputed.strategy, Some(AggregationStrategy::HighestConfidence));
+        
+        // Off by default
+        let plain = PriceAggregator::new().aggregate_memoized(&quiet, &symbol).unwrap();
+        assert_eq!(plain.strategy, Some(AggregationStrategy::HighestConfidence));
+    }
+    
+    #[test]
+    fn test_baseline_window() {
+        let mut window = BaselineWindow::new(3);
+        assert!(window.baseline().is_none());
+        
+        window.push(10.0);
+        assert_eq!(window.baseline(), Some(Baseline { mean: 10.0, std_dev: 0.0, samples: 1 }));
+        
+        // Oldest entries fall out once the window is full
+        for value in [100.0, 2.0, 4.0, 6.0] {
+            window.push(value);
         }
+        window.push(f64::NAN);
+        let baseline = window.baseline().unwrap();
+        assert_eq!(baseline.samples, 3);
+        assert_eq!(baseline.mean, 4.0);
+        assert_eq!(baseline.std_dev, 2.0);
     }
     
     #[test]
@@ -296,6 +2050,7 @@ mod tests {
                 timestamp: 1000,
                 source: PriceSource::Pyth,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
             PriceData {
                 price: 50050_00000000,
@@ -304,6 +2059,7 @@ mod tests {
                 timestamp: 1001,
                 source: PriceSource::Switchboard,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
         ];
         
@@ -315,6 +2071,554 @@ mod tests {
         assert_eq!(aggregated.source, PriceSource::Aggregated);
     }
     
+    #[test]
+    fn test_confidence_includes_dispersion() {
+        let aggregator = PriceAggregator::new().with_outlier_method(OutlierMethod::None);
+        let symbol = create_test_symbol();
+        let tight = |price: i64, source: PriceSource| PriceData { confidence: 1_00000000, ..btc(price, source) };
+        
+        let agreeing = vec![
+            tight(50000_00000000, PriceSource::Pyth),
+            tight(50000_00000000, PriceSource::Switchboard),
+        ];
+        let divergent = vec![
+            tight(49900_00000000, PriceSource::Pyth),
+            tight(50100_00000000, PriceSource::Switchboard),
+        ];
+        
+        let agreeing = aggregator.aggregate_prices(&agreeing, &symbol).unwrap();
+        let divergent = aggregator.aggregate_prices(&divergent, &symbol).unwrap();
+        
+        // Agreeing sources keep their own ~$1 confidence
+        assert!((agreeing.confidence_to_decimal() - 1.0).abs() < 0.01);
+        // A $200 disagreement dominates
+        assert!(divergent.confidence_to_decimal() > 100.0);
+    }
+    
+    #[test]
+    fn test_fallback_chain() {
+        let aggregator = PriceAggregator::new();
+        let symbol = Symbol {
+            fallback_chain: serde_json::from_str(r#"[{"quorum": 3}, "median", "highest_confidence"]"#).unwrap(),
+            ..create_test_symbol()
+        };
+        assert_eq!(symbol.fallback_chain, vec![
+            AggregationStrategy::Quorum(3),
+            AggregationStrategy::Median,
+            AggregationStrategy::HighestConfidence,
+        ]);
+        
+        let prices = vec![
+            PriceData {
+                price: 50000_00000000,
+                confidence: 50_00000000,
+                expo: -8,
+                timestamp: 1000,
+                source: PriceSource::Pyth,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+            PriceData {
+                price: 50100_00000000,
+                confidence: 5_00000000,
+                expo: -8,
+                timestamp: 1001,
+                source: PriceSource::Switchboard,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+        ];
+        
+        // Two sources miss the quorum, median takes over
+        let aggregated = aggregator.aggregate_with_fallback(&prices, &symbol).unwrap();
+        assert_eq!(aggregated.strategy, Some(AggregationStrategy::Median));
+        assert_eq!(aggregated.to_decimal(), 50050.0);
+        
+        // One source left: the median can't run either
+        let aggregated = aggregator.aggregate_with_fallback(&prices[1..], &symbol).unwrap();
+        assert_eq!(aggregated.strategy, Some(AggregationStrategy::HighestConfidence));
+        assert_eq!(aggregated.source, PriceSource::Switchboard);
+        
+        assert!(aggregator.aggregate_with_fallback(&[], &symbol).is_err());
+        
+        // No chain configured means plain consensus
+        let aggregated = aggregator.aggregate_with_fallback(&prices, &create_test_symbol()).unwrap();
+        assert_eq!(aggregated.strategy, Some(AggregationStrategy::Consensus));
+    }
+    
+    #[test]
+    fn test_oracle_count_weighting() {
+        let aggregator = PriceAggregator::new();
+        let reading = |price: i64, oracle_count: Option<u32>| PriceData {
+            price,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Switchboard,
+            symbol: "BTC/USD".to_string(),
+            oracle_count,
+            ..Default::default()
+        };
+        
+        let well_backed = reading(50000_00000000, Some(12));
+        let thin = reading(50100_00000000, Some(3));
+        assert!(confidence_weight(&well_backed).unwrap() > confidence_weight(&thin).unwrap());
+        
+        // Equal confidence, so only the oracle count pulls the average toward one side
+        let average = aggregator.confidence_weighted_average(&[well_backed.clone(), thin], -8).unwrap();
+        assert!(average < 50050_00000000);
+        
+        // Unreported counts leave the confidence weight unchanged
+        let unreported = reading(50000_00000000, None);
+        assert_eq!(oracle_count_factor(&unreported), 1.0);
+        assert_eq!(oracle_count_factor(&reading(50000_00000000, Some(5))), 1.0);
+    }
+    
+    #[test]
+    fn test_source_weights() {
+        let aggregator = PriceAggregator::new();
+        let prices = [btc(50000_00000000, PriceSource::Pyth), btc(50100_00000000, PriceSource::Switchboard)];
+        
+        // Equal confidence splits the difference
+        let even = aggregator.confidence_weighted_average(&prices, -8).unwrap();
+        assert!((even - 50050_00000000).abs() < 1_000000);
+        
+        // Down-weighting Switchboard pulls the average toward Pyth
+        aggregator.set_source_weights("BTC/USD", [(PriceSource::Switchboard, 0.25)].into_iter().collect());
+        let weighted = aggregator.confidence_weighted_average(&prices, -8).unwrap();
+        assert!((weighted - 50020_00000000).abs() < 1_000000);
+        
+        // Other symbols keep equal weights
+        let other: Vec<PriceData> = prices.iter()
+            .map(|p| PriceData { symbol: "ETH/USD".to_string(), ..p.clone() })
+            .collect();
+        assert!((aggregator.confidence_weighted_average(&other, -8).unwrap() - 50050_00000000).abs() < 1_000000);
+    }
+    
+    #[test]
+    fn test_mixed_expo_aggregation() {
+        let aggregator = PriceAggregator::new();
+        let symbol = create_test_symbol();
+        
+        let prices = vec![
+            PriceData {
+                price: 50000_00000000, // expo -8
+                confidence: 10_00000000,
+                expo: -8,
+                timestamp: 1000,
+                source: PriceSource::Pyth,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+            PriceData {
+                price: 50000_000000, // same price at expo -6
+                confidence: 10_000000,
+                expo: -6,
+                timestamp: 1001,
+                source: PriceSource::Switchboard,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+        ];
+        
+        let aggregated = aggregator.aggregate_prices(&prices, &symbol).unwrap();
+        
+        // Finest shared exponent wins and the value survives the rescale
+        assert_eq!(aggregated.expo, -8);
+        assert_eq!(aggregated.price, 50000_00000000);
+        assert_eq!(aggregated.to_decimal(), 50000.0);
+        
+        // Coarse-only sources keep their coarser exponent
+        let coarse: Vec<PriceData> = prices.iter()
+            .map(|p| PriceData { price: 50000_000000, confidence: 10_000000, expo: -6, ..p.clone() })
+            .collect();
+        let aggregated = aggregator.aggregate_prices(&coarse, &symbol).unwrap();
+        assert_eq!(aggregated.expo, -6);
+        assert_eq!(aggregated.price, 50000_000000);
+    }
+    
+    #[test]
+    fn test_fixed_point_aggregation_is_exact() {
+        let aggregator = PriceAggregator::new();
+        let symbol = create_test_symbol();
+        
+        // 2^53 + 1 has no exact f64, so a float pipeline would publish ...992
+        let price = |source: PriceSource, price: i64, expo: i32| PriceData {
+            price,
+            confidence: 1_00000000,
+            expo,
+            timestamp: 1000,
+            source,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let prices = vec![
+            price(PriceSource::Pyth, 9_007_199_254_740_993, -8),
+            price(PriceSource::Switchboard, 9_007_199_254_740_993, -8),
+        ];
+        let aggregated = aggregator.aggregate_prices(&prices, &symbol).unwrap();
+        assert_eq!(aggregated.price, 9_007_199_254_740_993);
+        
+        // Mixed exponents are combined at the finest one without rounding through a float
+        let mixed = vec![
+            price(PriceSource::Pyth, 90_071_992_547_409_930, -9),
+            price(PriceSource::Switchboard, 9_007_199_254_740_993, -8),
+        ];
+        let aggregated = aggregator.aggregate_prices(&mixed, &symbol).unwrap();
+        assert_eq!(aggregated.expo, -9);
+        assert_eq!(aggregated.price, 90_071_992_547_409_930);
+        
+        assert_eq!(fixed_at(12345, -6, -8), Some(1234500));
+        assert_eq!(fixed_at(12345, -8, -6), Some(123));
+        assert_eq!(fixed_at(-12350, -8, -6), Some(-124));
+        assert_eq!(div_round(5, 2), 3);
+        assert_eq!(div_round(-5, 2), -3);
+    }
+    
+    #[test]
+    fn test_inconsistent_expo_falls_back() {
+        let aggregator = PriceAggregator::new();
+        
+        let prices = vec![
+            PriceData {
+                price: i64::MAX / 10, // Would overflow when rescaled by 10^12
+                confidence: 1,
+                expo: 0,
+                timestamp: 1000,
+                source: PriceSource::Pyth,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+            PriceData {
+                price: 1,
+                confidence: 1,
+                expo: -12,
+                timestamp: 1001,
+                source: PriceSource::Switchboard,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+        ];
+        
+        // The median of [-12, 0] resolves to the coarser middle value
+        assert_eq!(aggregator.common_expo(&prices), 0);
+    }
+    
+    #[test]
+    fn test_coarse_expo_aggregation_does_not_overflow() {
+        let symbol = create_test_symbol();
+        
+        // Worth 2e12, which as an expo -8 mantissa would not fit i64
+        let price = |source: PriceSource, expo: i32| PriceData {
+            price: 2_000_000_000_000 * 10_i64.pow((-expo) as u32),
+            confidence: 10_i64.pow((-expo) as u32) as u64,
+            expo,
+            timestamp: 1000,
+            source,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let prices = vec![price(PriceSource::Pyth, -5), price(PriceSource::Switchboard, -5)];
+        
+        let aggregated = PriceAggregator::new().aggregate_prices(&prices, &symbol).unwrap();
+        assert_eq!(aggregated.expo, -5);
+        assert_eq!(aggregated.price, 2_000_000_000_000_00000);
+        assert_eq!(aggregated.to_decimal(), 2_000_000_000_000.0);
+        
+        // Median selection keeps the majority's exponent over a finer outlier
+        let mut mixed = prices.clone();
+        mixed.push(price(PriceSource::Internal, -6));
+        let median = PriceAggregator::new().with_expo_selection(ExpoSelection::Median);
+        let aggregated = median.aggregate_prices(&mixed, &symbol).unwrap();
+        assert_eq!(aggregated.expo, -5);
+        assert_eq!(aggregated.to_decimal(), 2_000_000_000_000.0);
+        
+        // Even when the chosen exponent can't hold the consensus, the output coarsens instead of saturating
+        let aggregator = PriceAggregator::new();
+        let aggregated = aggregator.build_aggregate(&prices, &symbol, 2 * 10_i128.pow(19), -5, 1.0).unwrap();
+        assert_eq!(aggregated.expo, -4);
+        assert_eq!(aggregated.to_decimal(), 2e14);
+        assert!("median".parse::<ExpoSelection>().is_ok());
+    }
+    
+    #[test]
+    fn test_volatility_calculation() {
+        let aggregator = PriceAggregator::new();
+        
+        // Out of order on purpose, history comes back newest first
+        let history: Vec<PriceData> = [(3, 102_00000000i64), (1, 100_00000000), (2, 101_00000000)]
+            .iter()
+            .map(|&(timestamp, price)| PriceData {
+                price,
+                confidence: 1000000,
+                expo: -8,
+                timestamp,
+                source: PriceSource::Aggregated,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        
+        let stats = aggregator.calculate_volatility(&history);
+        
+        assert_eq!(stats.sample_count, 3);
+        assert_eq!(stats.mean, Some(101.0));
+        assert_eq!(stats.std_dev, Some(1.0));
+        assert_eq!(stats.high, Some(102.0));
+        assert_eq!(stats.low, Some(100.0));
+        assert!(stats.return_std_dev.unwrap() < 0.001);
+        
+        let empty = aggregator.calculate_volatility(&[]);
+        assert_eq!(empty.sample_count, 0);
+        assert!(empty.std_dev.is_none());
+        assert!(empty.high.is_none());
+    }
+    
+    #[test]
+    fn test_build_candles() {
+        let aggregator = PriceAggregator::new();
+        
+        // Newest first, like the cache returns it
+        let history: Vec<PriceData> = [(125, 104i64), (70, 99), (61, 103), (59, 100), (60, 101), (0, 98)]
+            .iter()
+            .map(|&(timestamp, price)| PriceData {
+                price: price * 100000000,
+                confidence: 1000000,
+                expo: -8,
+                timestamp,
+                source: PriceSource::Aggregated,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            })
+            .collect();
+        
+        let candles = aggregator.build_candles(&history, 60, 10);
+        assert_eq!(candles, vec![
+            Candle { t: 0, open: 98.0, high: 100.0, low: 98.0, close: 100.0 },
+            Candle { t: 60, open: 101.0, high: 103.0, low: 99.0, close: 99.0 },
+            Candle { t: 120, open: 104.0, high: 104.0, low: 104.0, close: 104.0 },
+        ]);
+        
+        // Limit keeps the most recent candles
+        let latest = aggregator.build_candles(&history, 60, 2);
+        assert_eq!(latest.iter().map(|c| c.t).collect::<Vec<_>>(), vec![60, 120]);
+        
+        assert!(aggregator.build_candles(&[], 60, 10).is_empty());
+    }
+    
+    #[test]
+    fn test_stablecoin_agreement_not_suspicious() {
+        let aggregator = PriceAggregator::new();
+        let usdc = |source: PriceSource| PriceData {
+            price: 1_00000000,
+            confidence: 10000,
+            expo: -8,
+            timestamp: 1000,
+            source,
+            symbol: "USDC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        let pegged = Baseline { mean: 1.0, std_dev: 0.0001, samples: 10 };
+        
+        // Two feeds both at 1.00 is just a pegged asset
+        let prices = vec![usdc(PriceSource::Pyth), usdc(PriceSource::Switchboard)];
+        assert!(aggregator.detect_manipulation(&prices, Some(&pegged)).is_empty());
+        
+        // So is any number of them agreeing where the price has been all along
+        let prices = vec![usdc(PriceSource::Pyth), usdc(PriceSource::Switchboard), usdc(PriceSource::Internal)];
+        assert!(aggregator.detect_manipulation(&prices, Some(&pegged)).is_empty());
+        
+        // Identical quotes that moved off the baseline together get flagged
+        let depegged = Baseline { mean: 1.05, ..pegged };
+        let alerts = aggregator.detect_manipulation(&prices, Some(&depegged));
+        assert!(matches!(alerts.as_slice(), [ManipulationAlert { alert_type: ManipulationType::SuspiciousConsensus, .. }]));
+    }
+    
+    #[test]
+    fn test_steady_agreement_not_suspicious() {
+        let aggregator = PriceAggregator::new();
+        
+        // Agreeing sources tracking a steady market never alert, cycle after cycle
+        let mut window = BaselineWindow::new(20);
+        for step in 0..50 {
+            let value = 50000_00000000 + (step % 5) * 5_00000000;
+            let prices = vec![
+                btc(value, PriceSource::Pyth),
+                btc(value + 1_00000000, PriceSource::Switchboard),
+                btc(value - 1_00000000, PriceSource::Internal),
+            ];
+            assert!(aggregator.detect_manipulation(&prices, window.baseline().as_ref()).is_empty());
+            window.push(value as f64 / 1e8);
+        }
+        
+        // A move within a volatile window's spread is not a flash crash
+        let volatile = Baseline { mean: 50000.0, std_dev: 3000.0, samples: 20 };
+        let prices = vec![btc(44000_00000000, PriceSource::Pyth)];
+        assert!(aggregator.detect_manipulation(&prices, Some(&volatile)).is_empty());
+        
+        // The same move against a calm window is
+        let calm = Baseline { std_dev: 50.0, ..volatile };
+        let alerts = aggregator.detect_manipulation(&prices, Some(&calm));
+        assert!(matches!(alerts.as_slice(), [ManipulationAlert { alert_type: ManipulationType::FlashCrash, .. }]));
+    }
+    
+    #[test]
+    fn test_manipulation_with_zero_historical_average() {
+        let aggregator = PriceAggregator::new();
+        
+        let prices = vec![
+            PriceData {
+                price: 50000_00000000,
+                confidence: 500_00000,
+                expo: -8,
+                timestamp: 1000,
+                source: PriceSource::Pyth,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+            PriceData {
+                price: 52000_00000000,
+                confidence: 500_00000,
+                expo: -8,
+                timestamp: 1001,
+                source: PriceSource::Switchboard,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            },
+        ];
+        
+        // A zero baseline would previously yield infinite deviations and flash-crash alerts
+        let baseline = |mean: f64| Baseline { mean, std_dev: 0.0, samples: 10 };
+        let alerts = aggregator.detect_manipulation(&prices, Some(&baseline(0.0)));
+        assert!(alerts.is_empty());
+        
+        let alerts = aggregator.detect_manipulation(&prices, Some(&baseline(f64::NAN)));
+        assert!(alerts.is_empty());
+        assert!(aggregator.detect_manipulation(&prices, None).is_empty());
+        
+        // All-zero prices must not divide by a zero mean either
+        let zeros: Vec<PriceData> = prices.iter()
+            .map(|p| PriceData { price: 0, ..p.clone() })
+            .collect();
+        assert!(aggregator.detect_manipulation(&zeros, Some(&baseline(0.0))).is_empty());
+    }
+    
+    /// Outlier filtering as the aggregation pipeline applies it
+    fn filter_outliers(aggregator: &PriceAggregator, prices: &[f64], data: &[PriceData]) -> Result<Vec<PriceData>> {
+        aggregator.apply_verdicts(data, &aggregator.outlier_verdicts(prices)?)
+    }
+    
+    fn prices_from(values: &[f64]) -> Vec<PriceData> {
+        values.iter()
+            .enumerate()
+            .map(|(i, &value)| PriceData {
+                price: (value * 1e8) as i64,
+                confidence: 1000000,
+                expo: -8,
+                timestamp: 1000 + i as i64,
+                source: PriceSource::Pyth,
+                symbol: "BTC/USD".to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+    
+    #[test]
+    fn test_outlier_methods() {
+        // 106 is a MAD outlier (z-score 2.7) but sits exactly on the upper IQR fence
+        let values = vec![100.0, 101.0, 102.0, 103.0, 106.0];
+        let data = prices_from(&values);
+        
+        let mad = PriceAggregator::new().with_outlier_method(OutlierMethod::Mad);
+        let filtered = filter_outliers(&mad, &values, &data).unwrap();
+        assert_eq!(filtered.len(), 4);
+        assert!(filtered.iter().all(|p| p.price < 106_00000000));
+        
+        let iqr = PriceAggregator::new().with_outlier_method(OutlierMethod::Iqr);
+        assert_eq!(filter_outliers(&iqr, &values, &data).unwrap().len(), 5);
+        
+        let none = PriceAggregator::new().with_outlier_method(OutlierMethod::None);
+        assert_eq!(filter_outliers(&none, &values, &data).unwrap().len(), 5);
+        
+        // A gross outlier is dropped by both statistical methods
+        let values = vec![50000.0, 50010.0, 50020.0, 100000.0];
+        let data = prices_from(&values);
+        assert_eq!(filter_outliers(&mad, &values, &data).unwrap().len(), 3);
+        assert_eq!(filter_outliers(&iqr, &values, &data).unwrap().len(), 3);
+        assert_eq!(filter_outliers(&none, &values, &data).unwrap().len(), 4);
+        
+        assert_eq!("IQR".parse::<OutlierMethod>().unwrap(), OutlierMethod::Iqr);
+        assert!("zscore".parse::<OutlierMethod>().is_err());
+    }
+    
+    #[test]
+    fn test_aggregation_trace() {
+        let aggregator = PriceAggregator::new();
+        let symbol = create_test_symbol();
+        let prices = prices_from(&[50000.0, 50010.0, 50020.0, 100000.0]);
+        
+        let (aggregated, trace) = aggregator.aggregate_prices_traced(&prices, &symbol).unwrap();
+        assert_eq!(aggregated, aggregator.aggregate_prices(&prices, &symbol).unwrap());
+        
+        // The gross outlier is reported with a reason and carries no weight
+        assert_eq!(trace.sources.len(), 4);
+        let outlier = &trace.sources[3];
+        assert_eq!(outlier.price, 100000.0);
+        assert!(outlier.excluded_reason.as_deref().unwrap().contains("z-score"));
+        assert!(outlier.weight.is_none());
+        
+        let kept_weight: f64 = trace.sources.iter().filter_map(|s| s.weight).sum();
+        assert!((kept_weight - 1.0).abs() < 1e-9);
+        
+        assert_eq!(trace.components.median, 50010.0);
+        assert_eq!(trace.components.shares.iter().sum::<f64>(), 1.0);
+        assert!((trace.components.consensus - aggregated.to_decimal()).abs() < 1e-6);
+    }
+    
+    #[test]
+    fn test_scale_mismatch_detection() {
+        let aggregator = PriceAggregator::new();
+        
+        let pyth = PriceData {
+            price: 50000_00000000,
+            confidence: 500_00000,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        // Switchboard mantissa read with expo -8 instead of -6: 100x too small
+        let switchboard = PriceData {
+            price: 50000_000000,
+            confidence: 5_000000,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Switchboard,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        let prices = vec![pyth.clone(), switchboard.clone()];
+        assert!(!aggregator.is_scale_consistent(&prices));
+        
+        // The last good aggregate pins the blame on Switchboard
+        assert_eq!(aggregator.scale_suspects(&prices, Some(50100.0)), vec![1]);
+        
+        // Without a reference neither of two sources can be trusted
+        assert_eq!(aggregator.scale_suspects(&prices, None), vec![0, 1]);
+        
+        // With three sources the median settles it
+        let prices = vec![pyth.clone(), switchboard, PriceData { price: 50010_00000000, ..pyth.clone() }];
+        assert_eq!(aggregator.scale_suspects(&prices, None), vec![1]);
+        
+        // Ordinary deviations are not scale problems
+        let prices = vec![pyth.clone(), PriceData { price: 52000_00000000, ..pyth }];
+        assert!(aggregator.is_scale_consistent(&prices));
+        assert!(aggregator.scale_suspects(&prices, None).is_empty());
+    }
+    
     #[test]
     fn test_outlier_detection() {
         let aggregator = PriceAggregator::new();
@@ -329,6 +2633,7 @@ mod tests {
                 timestamp: 1000,
                 source: PriceSource::Pyth,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
             PriceData {
                 price: 50010_00000000,
@@ -337,6 +2642,7 @@ mod tests {
                 timestamp: 1001,
                 source: PriceSource::Switchboard,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
             PriceData {
                 price: 50020_00000000,
@@ -345,6 +2651,7 @@ mod tests {
                 timestamp: 1002,
                 source: PriceSource::Pyth,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
             PriceData {
                 price: 100000_00000000, // Outlier
@@ -353,10 +2660,11 @@ mod tests {
                 timestamp: 1003,
                 source: PriceSource::Switchboard,
                 symbol: "BTC/USD".to_string(),
+                ..Default::default()
             },
         ];
         
-        let filtered = aggregator.filter_outliers(&prices, &original_data).unwrap();
+        let filtered = filter_outliers(&aggregator, &prices, &original_data).unwrap();
         
         // Should filter out the outlier
         assert_eq!(filtered.len(), 3);
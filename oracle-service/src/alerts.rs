@@ -0,0 +1,183 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
+
+/// Alerts waiting for delivery before new ones are dropped
+const QUEUE_CAPACITY: usize = 256;
+
+/// Delivery attempts per alert, with doubling backoff in between
+const MAX_ATTEMPTS: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Consecutive failed deliveries that open the circuit, and how long it stays open
+const BREAKER_THRESHOLD: u32 = 5;
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// A health alert as forwarded to external tooling
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub oracle: String,
+    pub status: String,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+/// Forwards health alerts to an outbound webhook
+///
+/// Delivery happens on a background task behind a bounded queue, so a slow
+/// or failing webhook never holds up the code raising the alert. While the
+/// webhook keeps failing, a circuit breaker drops alerts instead of retrying.
+pub struct WebhookSink {
+    queue: mpsc::Sender<Alert>,
+}
+
+impl WebhookSink {
+    /// Start the delivery task for `url`
+    pub fn spawn(url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        let (queue, receiver) = mpsc::channel(QUEUE_CAPACITY);
+        
+        info!("Forwarding health alerts to webhook");
+        tokio::spawn(deliver_alerts(http, url.to_string(), receiver));
+        
+        Ok(Self { queue })
+    }
+    
+    /// Queue an alert for delivery, dropping it if the queue is full
+    pub fn notify(&self, alert: Alert) {
+        if let Err(e) = self.queue.try_send(alert) {
+            warn!("Dropping health alert for webhook: {}", e);
+        }
+    }
+}
+
+async fn deliver_alerts(http: reqwest::Client, url: String, mut receiver: mpsc::Receiver<Alert>) {
+    let mut breaker = CircuitBreaker::new(BREAKER_THRESHOLD, BREAKER_COOLDOWN);
+    
+    while let Some(alert) = receiver.recv().await {
+        if !breaker.allows(Instant::now()) {
+            warn!("Alert webhook circuit open, dropping alert for {}", alert.oracle);
+            continue;
+        }
+        
+        match post_with_retry(&http, &url, &webhook_payload(&alert)).await {
+            Ok(()) => breaker.record_success(),
+            Err(e) => {
+                error!("Failed to deliver alert for {} to webhook: {}", alert.oracle, e);
+                breaker.record_failure(Instant::now());
+            },
+        }
+    }
+}
+
+async fn post_with_retry(http: &reqwest::Client, url: &str, payload: &serde_json::Value) -> Result<()> {
+    let mut attempt = 1;
+    loop {
+        let result = http.post(url)
+            .json(payload)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        
+        match result {
+            Ok(_) => return Ok(()),
+            Err(e) if attempt >= MAX_ATTEMPTS => return Err(e.into()),
+            Err(e) => {
+                warn!("Alert webhook attempt {}/{} failed: {}", attempt, MAX_ATTEMPTS, e);
+                tokio::time::sleep(RETRY_BACKOFF * 2u32.pow(attempt - 1)).await;
+                attempt += 1;
+            },
+        }
+    }
+}
+
+/// Slack-compatible body: `text` for chat tools, the alert fields for everything else
+fn webhook_payload(alert: &Alert) -> serde_json::Value {
+    serde_json::json!({
+        "text": format!("[{}] {}: {}", alert.status, alert.oracle, alert.message),
+        "oracle": alert.oracle,
+        "status": alert.status,
+        "message": alert.message,
+        "timestamp": alert.timestamp,
+    })
+}
+
+/// Opens after `threshold` consecutive failures and lets a single trial
+/// request through once `cooldown` has passed
+#[derive(Debug)]
+struct CircuitBreaker {
+    threshold: u32,
+    cooldown: Duration,
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn new(threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            threshold,
+            cooldown,
+            consecutive_failures: 0,
+            open_until: None,
+        }
+    }
+    
+    fn allows(&self, now: Instant) -> bool {
+        self.open_until.is_none_or(|until| now >= until)
+    }
+    
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.open_until = None;
+    }
+    
+    fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= self.threshold {
+            self.open_until = Some(now + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_circuit_breaker() {
+        let mut breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        
+        breaker.record_failure(start);
+        assert!(breaker.allows(start));
+        
+        // Threshold reached: closed to traffic until the cooldown passes
+        breaker.record_failure(start);
+        assert!(!breaker.allows(start + Duration::from_secs(59)));
+        assert!(breaker.allows(start + Duration::from_secs(60)));
+        
+        // A failed trial reopens it, a success resets it
+        breaker.record_failure(start + Duration::from_secs(60));
+        assert!(!breaker.allows(start + Duration::from_secs(61)));
+        breaker.record_success();
+        assert!(breaker.allows(start + Duration::from_secs(61)));
+    }
+    
+    #[test]
+    fn test_webhook_payload_shape() {
+        let payload = webhook_payload(&Alert {
+            oracle: "BTC/USD".to_string(),
+            status: "hard_failed".to_string(),
+            message: "Fetch loop crashed 6 times, giving up".to_string(),
+            timestamp: 1640995200,
+        });
+        
+        assert_eq!(payload["text"], "[hard_failed] BTC/USD: Fetch loop crashed 6 times, giving up");
+        assert_eq!(payload["oracle"], "BTC/USD");
+        assert_eq!(payload["timestamp"], 1640995200);
+    }
+}
\ No newline at end of file
@@ -1,18 +1,24 @@
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    body::Body,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
+use futures_util::{stream, StreamExt, TryStreamExt};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::{Duration, Instant}};
 use tower_http::cors::CorsLayer;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 
 use crate::{
-    manager::OracleManager,
-    types::{PriceResponse, HealthResponse, OracleHealthStatus, CacheHealthStatus},
+    aggregator::{invert_price, AggregationConfig, AggregationTrace, Baseline, Candle, VolatilityStats},
+    manager::{validate_adhoc_symbol, AllPrices, ConversionLeg, OracleManager, SourceDiff, SourcePriority, TracedCycle},
+    metrics::{FetchStats, Histogram},
+    rate_limit::RateLimiter,
+    types::{PriceData, PriceResponse, PriceSource, PushMode, HealthResponse, HealthSnapshot, OracleHealthStatus, CacheHealthStatus, OracleError, Symbol},
     // cache::PriceCache, // Unused for now
 };
 
@@ -20,6 +26,79 @@ use crate::{
 #[derive(Clone)]
 pub struct ApiState {
     pub oracle_manager: Arc<OracleManager>,
+    pub admin_api_key: Option<Arc<str>>,
+}
+
+/// Header carrying the key for /admin routes
+const ADMIN_KEY_HEADER: &str = "x-api-key";
+
+/// Header reporting how long a price read took, in milliseconds
+const ELAPSED_HEADER: &str = "x-elapsed-ms";
+
+/// Query parameter toggling the response envelope for one request
+const ENVELOPE_PARAM: &str = "envelope";
+
+/// A response body wrapped with metadata identifying the server that produced it
+#[derive(Debug, Serialize)]
+pub struct Envelope<T> {
+    pub data: T,
+    pub meta: ResponseMeta,
+}
+
+/// Server details attached to enveloped responses
+#[derive(Debug, Clone, Serialize)]
+pub struct ResponseMeta {
+    pub server_time: i64, // Unix milliseconds when the response was built
+    pub version: &'static str,
+    pub instance_id: Option<String>,
+}
+
+tokio::task_local! {
+    /// Instance id for the envelope of the response being handled; unset when it goes out bare
+    static ENVELOPE_INSTANCE: Option<Option<String>>;
+}
+
+/// JSON response body, sent in an `Envelope` when the request gets one
+///
+/// Handlers return this in place of `Json` so the envelope is built from the
+/// typed body instead of re-parsing the bytes it serialized to.
+#[derive(Debug)]
+pub struct ApiJson<T>(pub T);
+
+impl<T: Serialize> IntoResponse for ApiJson<T> {
+    fn into_response(self) -> Response {
+        match ENVELOPE_INSTANCE.try_with(Clone::clone).ok().flatten() {
+            Some(instance_id) => Json(Envelope { data: self.0, meta: ResponseMeta::now(instance_id) }).into_response(),
+            None => Json(self.0).into_response(),
+        }
+    }
+}
+
+impl ResponseMeta {
+    fn now(instance_id: Option<String>) -> Self {
+        Self {
+            server_time: chrono::Utc::now().timestamp_millis(),
+            version: env!("CARGO_PKG_VERSION"),
+            instance_id,
+        }
+    }
+}
+
+/// Query parameters for a single price
+#[derive(Debug, Deserialize)]
+pub struct PriceQuery {
+    pub max_confidence_bp: Option<u64>,
+    pub max_source_age_secs: Option<i64>, // Reject aggregates built from a source older than this
+    #[serde(default)]
+    pub explain: bool,
+    #[serde(default)]
+    pub string_prices: bool,
+    pub quote: Option<String>, // Convert into this quote currency through a configured cross
+    pub deadline_ms: Option<u64>, // Budget for a live fetch, overriding PRICE_DEADLINE_MS; 0 = none
+    pub source: Option<String>,   // Return this source's own price instead of the aggregate
+    #[serde(default)]
+    pub invert: bool,             // Return the reciprocal, QUOTE/BASE
+    pub base: Option<String>,     // Orient the price so this asset is the one priced, inverting if needed
 }
 
 /// Query parameters for price history
@@ -27,8 +106,75 @@ pub struct ApiState {
 pub struct HistoryQuery {
     pub limit: Option<usize>,
     pub since: Option<i64>,
+    pub format: Option<String>, // "csv" for a streamed CSV export, same as `Accept: text/csv`
+}
+
+/// Query parameters for health history
+#[derive(Debug, Deserialize)]
+pub struct HealthHistoryQuery {
+    pub limit: Option<usize>, // Newest snapshots returned, defaults to every one kept
 }
 
+/// Query parameters for recent consensus traces
+#[derive(Debug, Deserialize)]
+pub struct TraceQuery {
+    pub limit: Option<usize>, // Newest traces returned, defaults to every one kept
+}
+
+/// Query parameters for muting a source's alerts
+#[derive(Debug, Deserialize)]
+pub struct MuteQuery {
+    pub ttl_secs: Option<u64>, // How long the mute lasts, defaults to DEFAULT_MUTE_TTL_SECS
+}
+
+/// Mute length when the request doesn't give one
+const DEFAULT_MUTE_TTL_SECS: u64 = 3600;
+
+/// Column header of the CSV history export
+const HISTORY_CSV_HEADER: &str = "timestamp,price,confidence,source\n";
+
+/// Query parameters for a live feed address check
+#[derive(Debug, Deserialize)]
+pub struct ValidateFeedQuery {
+    pub source: String,  // pyth or switchboard
+    pub address: String, // Candidate price feed or aggregator account
+}
+
+/// Query parameters for the all-prices listing
+#[derive(Debug, Deserialize)]
+pub struct AllPricesQuery {
+    #[serde(default)]
+    pub include_errors: bool,
+    #[serde(default)]
+    pub string_prices: bool,
+}
+
+/// Query parameters for volatility
+#[derive(Debug, Deserialize)]
+pub struct VolatilityQuery {
+    pub window_secs: Option<i64>,
+}
+
+/// Query parameters for OHLC candles
+#[derive(Debug, Deserialize)]
+pub struct OhlcQuery {
+    pub interval: Option<String>,
+    pub limit: Option<usize>,
+}
+
+/// Supported candle intervals and their length in seconds
+const CANDLE_INTERVALS: [(&str, i64); 6] = [
+    ("1m", 60),
+    ("5m", 300),
+    ("15m", 900),
+    ("1h", 3600),
+    ("4h", 14400),
+    ("1d", 86400),
+];
+
+/// Maximum number of candles returned per request
+const MAX_CANDLES: usize = 500;
+
 /// Request body for batch price queries
 #[derive(Debug, Deserialize)]
 pub struct BatchPriceRequest {
@@ -36,47 +182,691 @@ pub struct BatchPriceRequest {
 }
 
 /// Build the REST API router
-pub fn create_router(state: ApiState) -> Router {
-    Router::new()
+pub fn create_router(state: ApiState, push_mode: PushMode) -> Router {
+    let mut router = Router::new()
         .route("/health", get(health_check))
         .route("/oracle/price/:symbol", get(get_price))
+        .route("/oracle/price/:symbol/ohlc", get(get_ohlc))
+        .route("/oracle/price/:symbol/confidence-band", get(get_confidence_band))
+        .route("/oracle/price/:symbol/raw", get(get_raw_price))
         .route("/oracle/prices", get(get_all_prices))
         .route("/oracle/prices/batch", post(get_batch_prices))
         .route("/oracle/history/:symbol", get(get_price_history))
         .route("/oracle/sources/:symbol", get(get_source_prices))
+        .route("/oracle/diff/:symbol", get(get_source_diff))
+        .route("/oracle/volatility/:symbol", get(get_volatility))
+        .route("/oracle/config/:symbol/aggregation", get(get_aggregation_config))
+        .route("/oracle/trace/:symbol", get(get_traces))
         .route("/oracle/health", get(get_oracle_health))
+        .route("/oracle/health/:symbol/history", get(get_health_history))
         .route("/oracle/stats", get(get_oracle_stats))
+        .route("/oracle/metrics/deviation", get(get_deviation_histograms))
+        .route("/oracle/metrics/fetch", get(get_fetch_stats));
+    
+    // Ad-hoc lookups hit arbitrary accounts, so they are opt-in and rate limited
+    let adhoc_rate_limit = state.oracle_manager.settings().adhoc_rate_limit_per_min;
+    if adhoc_rate_limit > 0 {
+        let limiter = Arc::new(RateLimiter::per_minute(adhoc_rate_limit));
+        router = router.route(
+            "/oracle/price/adhoc",
+            post(get_adhoc_price).route_layer(middleware::from_fn_with_state(limiter, enforce_rate_limit)),
+        );
+    }
+    
+    let mut admin = Router::new()
+        .route("/admin/refresh/:symbol", post(refresh_symbol))
+        .route("/admin/drain", post(start_drain))
+        .route("/admin/source/:source/disable", post(disable_source))
+        .route("/admin/source/:source/enable", post(enable_source))
+        .route("/admin/source/:source/mute", post(mute_source))
+        .route("/admin/source/:source/unmute", post(unmute_source));
+    
+    // External schedulers drive fetches through the API instead of internal loops
+    if push_mode == PushMode::External {
+        admin = admin.route("/admin/fetch/:symbol", post(trigger_fetch));
+    }
+    
+    // Feed validation reads arbitrary accounts, so it sits behind the admin key too;
+    // a budget of 0 leaves it off, as for ad-hoc lookups
+    let validate_feed_rate_limit = state.oracle_manager.settings().validate_feed_rate_limit_per_min;
+    if validate_feed_rate_limit > 0 {
+        let limiter = Arc::new(RateLimiter::per_minute(validate_feed_rate_limit));
+        admin = admin.route(
+            "/oracle/validate-feed",
+            get(validate_feed).route_layer(middleware::from_fn_with_state(limiter, enforce_rate_limit)),
+        );
+    }
+    
+    router = router.merge(
+        admin.route_layer(middleware::from_fn_with_state(state.clone(), require_admin_key))
+    );
+    
+    router
+        .layer(middleware::from_fn_with_state(state.clone(), wrap_in_envelope))
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
 
+/// Envelope the handler's `ApiJson` body when configured or asked for with `?envelope=true`
+///
+/// Errors are wrapped like any other body; non-JSON responses such as the
+/// CSV export pass through untouched.
+async fn wrap_in_envelope(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let settings = state.oracle_manager.settings();
+    let instance_id = envelope_requested(request.uri().query(), settings.response_envelope)
+        .then(|| settings.instance_id.clone());
+    ENVELOPE_INSTANCE.scope(instance_id, next.run(request)).await
+}
+
+/// Whether a request gets the envelope: `?envelope=` wins, otherwise the configured default
+fn envelope_requested(query: Option<&str>, default: bool) -> bool {
+    query.into_iter()
+        .flat_map(|query| query.split('&'))
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, "true"))))
+        .filter(|(key, _)| *key == ENVELOPE_PARAM)
+        .map(|(_, value)| value == "true" || value == "1")
+        .next_back()
+        .unwrap_or(default)
+}
+
+/// Reject /admin requests that don't carry the configured API key
+async fn require_admin_key(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if is_authorized(request.headers(), state.admin_api_key.as_deref()) {
+        return next.run(request).await;
+    }
+    
+    (
+        StatusCode::UNAUTHORIZED,
+        ApiJson(serde_json::json!({
+            "error": "Unauthorized",
+            "message": format!("missing or invalid {} header", ADMIN_KEY_HEADER)
+        }))
+    ).into_response()
+}
+
+/// Turn requests away with 429 once the limiter's budget is spent
+async fn enforce_rate_limit(
+    State(limiter): State<Arc<RateLimiter>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limiter.try_acquire() {
+        return next.run(request).await;
+    }
+    
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        ApiJson(serde_json::json!({
+            "error": "Too many requests",
+            "message": "rate limit exceeded, retry later"
+        }))
+    ).into_response()
+}
+
+/// Whether the request headers carry the expected admin key
+///
+/// With no key configured nothing is authorized.
+fn is_authorized(headers: &HeaderMap, expected: Option<&str>) -> bool {
+    let Some(expected) = expected else {
+        return false;
+    };
+    headers.get(ADMIN_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()))
+}
+
+/// Compare secrets without short-circuiting on the first differing byte
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
 /// Health check endpoint
-pub async fn health_check() -> Result<Json<serde_json::Value>, StatusCode> {
-    Ok(Json(serde_json::json!({
+pub async fn health_check() -> Result<ApiJson<serde_json::Value>, StatusCode> {
+    Ok(ApiJson(serde_json::json!({
         "status": "healthy",
         "service": "oracle-integration",
         "timestamp": chrono::Utc::now().timestamp()
     })))
 }
 
-/// Get current price for a specific symbol
+/// Get current price for a specific symbol, with the time taken in `x-elapsed-ms`
 pub async fn get_price(
     State(state): State<ApiState>,
     Path(symbol): Path<String>,
-) -> Result<Json<PriceResponse>, (StatusCode, Json<serde_json::Value>)> {
+    Query(query): Query<PriceQuery>,
+) -> Response {
+    let started = Instant::now();
+    let mut response = price_response(&state, &symbol, query).await.into_response();
+    response.headers_mut().insert(ELAPSED_HEADER, HeaderValue::from(started.elapsed().as_millis() as u64));
+    response
+}
+
+async fn price_response(
+    state: &ApiState,
+    symbol: &str,
+    query: PriceQuery,
+) -> Result<ApiJson<ExplainedPriceResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching price for symbol: {}", symbol);
     
-    match state.oracle_manager.get_current_price(&symbol).await {
-        Ok(price_data) => {
-            let response = PriceResponse::from_price_data(&price_data);
-            Ok(Json(response))
+    let source = match query.source.as_deref().map(str::parse::<PriceSource>).transpose() {
+        Ok(source) => source,
+        Err(e) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                ApiJson(serde_json::json!({
+                    "error": "Unknown source",
+                    "symbol": symbol,
+                    "message": e.to_string()
+                }))
+            ));
+        }
+    };
+    
+    let invert = match price_orientation(state, symbol, &query) {
+        Ok(invert) => invert,
+        Err(message) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                ApiJson(serde_json::json!({
+                    "error": "Invalid orientation",
+                    "symbol": symbol,
+                    "message": message
+                }))
+            ));
+        }
+    };
+    
+    let result = match (&source, &query.quote) {
+        (Some(source), _) => state.oracle_manager.get_source_price(symbol, source).await
+            .map(|price_data| (price_data, None, None)),
+        (None, Some(quote)) => state.oracle_manager.get_converted_price(symbol, quote).await
+            .map(|converted| (converted.price, None, Some(converted.path))),
+        (None, None) if query.explain => state.oracle_manager.explain_price(symbol).await
+            .map(|(price_data, trace)| (price_data, Some(trace), None)),
+        (None, None) => {
+            let deadline = price_deadline(query.deadline_ms, state.oracle_manager.settings().price_deadline_ms);
+            state.oracle_manager.get_current_price_within(symbol, deadline).await
+                .map(|price_data| (price_data, None, None))
+        },
+    };
+    
+    match result {
+        Ok((price_data, explanation, conversion)) => {
+            // Consumers decide how wide a confidence interval they'll accept
+            if let Some(max_bp) = query.max_confidence_bp {
+                if confidence_exceeds(&price_data, max_bp) {
+                    return Err((
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        ApiJson(serde_json::json!({
+                            "error": "Confidence too wide",
+                            "symbol": symbol,
+                            "message": format!("confidence {:.2}bp exceeds max {}bp",
+                                price_data.confidence_percentage() * 100.0, max_bp)
+                        }))
+                    ));
+                }
+            }
+            
+            if let Some(max_age) = query.max_source_age_secs {
+                if price_data.max_source_age_secs.is_some_and(|age| age > max_age) {
+                    return Err((
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        ApiJson(serde_json::json!({
+                            "error": "Sources too old",
+                            "symbol": symbol,
+                            "message": format!("oldest source is {}s old, max {}s",
+                                price_data.max_source_age_secs.unwrap_or_default(), max_age)
+                        }))
+                    ));
+                }
+            }
+            
+            let price_data = if invert {
+                let inverted_symbol = state.oracle_manager.symbol_config(symbol)
+                    .ok()
+                    .and_then(|config| config.base_quote())
+                    .map(|(base, quote)| format!("{}/{}", quote, base))
+                    .unwrap_or_else(|| format!("1/{}", price_data.symbol));
+                invert_price(&price_data, &inverted_symbol)
+                    .map_err(|e| price_error_response(symbol, &e))?
+            } else {
+                price_data
+            };
+            
+            Ok(ApiJson(ExplainedPriceResponse {
+                price: PriceResponse::from_price_data_as(&price_data, query.string_prices),
+                explanation,
+                conversion,
+            }))
         },
         Err(e) => {
             error!("Failed to get price for {}: {}", symbol, e);
+            Err(price_error_response(symbol, &e))
+        }
+    }
+}
+
+/// Whether the requested orientation is the reciprocal of the symbol's own
+///
+/// `?base=` names the asset to price and wins over `?invert=`. Neither can be
+/// combined with a `?quote=` conversion, which already picks the pair. An
+/// inverted price can't be explained either, as the trace's medians and
+/// weighted averages only hold in the symbol's own orientation.
+fn price_orientation(state: &ApiState, symbol: &str, query: &PriceQuery) -> Result<bool, String> {
+    if query.quote.is_some() && (query.invert || query.base.is_some()) {
+        return Err("invert and base cannot be combined with quote".to_string());
+    }
+    
+    let invert = match &query.base {
+        Some(base) => state.oracle_manager.symbol_config(symbol)
+            .map_err(|e| e.to_string())?
+            .inverted_for_base(base)
+            .map_err(|e| e.to_string())?,
+        None => query.invert,
+    };
+    if invert && query.explain {
+        return Err("explain cannot be combined with an inverted price".to_string());
+    }
+    Ok(invert)
+}
+
+/// Live-fetch budget for a price read: the request's, else the configured one; 0 means none
+fn price_deadline(requested_ms: Option<u64>, configured_ms: u64) -> Option<Duration> {
+    let ms = requested_ms.unwrap_or(configured_ms);
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// Map a failed price lookup to a response, with the offending values when sources diverged
+fn price_error_response(symbol: &str, e: &anyhow::Error) -> (StatusCode, ApiJson<serde_json::Value>) {
+    if let Some(OracleError::DeadlineExceeded { deadline_ms, .. }) = e.downcast_ref::<OracleError>() {
+        return (
+            StatusCode::GATEWAY_TIMEOUT,
+            ApiJson(serde_json::json!({
+                "error": "Deadline exceeded",
+                "symbol": symbol,
+                "message": e.to_string(),
+                "deadline_ms": deadline_ms
+            }))
+        );
+    }
+    
+    if let Some(OracleError::DeviationExceeded { price_source, value, median, base, reference, deviation_bp, max_deviation_bp, .. }) =
+        e.downcast_ref::<OracleError>()
+    {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            ApiJson(serde_json::json!({
+                "error": "Deviation exceeded",
+                "symbol": symbol,
+                "message": e.to_string(),
+                "source": price_source,
+                "value": value,
+                "median": median,
+                "deviation_base": base,
+                "reference": reference,
+                "deviation_bp": deviation_bp,
+                "max_deviation_bp": max_deviation_bp
+            }))
+        );
+    }
+    
+    (
+        StatusCode::NOT_FOUND,
+        ApiJson(serde_json::json!({
+            "error": "Price not available",
+            "symbol": symbol,
+            "message": e.to_string()
+        }))
+    )
+}
+
+/// Fetch and aggregate a symbol described in the request body, without configuring it
+pub async fn get_adhoc_price(
+    State(state): State<ApiState>,
+    Json(symbol): Json<Symbol>,
+) -> Result<ApiJson<PriceResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching ad-hoc price for symbol: {}", symbol.name);
+    
+    if let Err(e) = validate_adhoc_symbol(&symbol) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            ApiJson(serde_json::json!({
+                "error": "Invalid symbol",
+                "symbol": symbol.name,
+                "message": e.to_string()
+            }))
+        ));
+    }
+    
+    match state.oracle_manager.get_adhoc_price(&symbol).await {
+        Ok(price_data) => Ok(ApiJson(PriceResponse::from_price_data(&price_data))),
+        Err(e) => {
+            error!("Failed to get ad-hoc price for {}: {}", symbol.name, e);
+            Err(price_error_response(&symbol.name, &e))
+        }
+    }
+}
+
+/// Check a candidate feed address live and return what it parses to
+pub async fn validate_feed(
+    State(state): State<ApiState>,
+    Query(query): Query<ValidateFeedQuery>,
+) -> Result<ApiJson<FeedValidationResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Validating {} feed {}", query.source, query.address);
+    
+    let source = match query.source.parse::<PriceSource>() {
+        Ok(source @ (PriceSource::Pyth | PriceSource::Switchboard)) => source,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                ApiJson(serde_json::json!({
+                    "error": "Unsupported source",
+                    "source": query.source,
+                    "message": "source must be pyth or switchboard"
+                }))
+            ));
+        }
+    };
+    
+    match state.oracle_manager.validate_feed(&source, &query.address).await {
+        Ok(price_data) => Ok(ApiJson(FeedValidationResponse::new(
+            source,
+            query.address,
+            &price_data,
+            chrono::Utc::now().timestamp(),
+        ))),
+        Err(e) => {
+            warn!("Feed {} failed validation: {}", query.address, e);
             Err((
-                StatusCode::NOT_FOUND,
-                Json(serde_json::json!({
-                    "error": "Price not available",
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ApiJson(serde_json::json!({
+                    "error": "Invalid feed",
+                    "valid": false,
+                    "source": source,
+                    "address": query.address,
+                    "message": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// Get the `price ± confidence` band around a symbol's current aggregate
+pub async fn get_confidence_band(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<ConfidenceBandResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching confidence band for symbol: {}", symbol);
+    
+    match state.oracle_manager.get_current_price(&symbol).await {
+        Ok(price_data) => Ok(ApiJson(ConfidenceBandResponse::from_price_data(&price_data))),
+        Err(e) => {
+            error!("Failed to get confidence band for {}: {}", symbol, e);
+            Err(price_error_response(&symbol, &e))
+        }
+    }
+}
+
+/// Get a symbol's current aggregate as the fixed-point integers it is stored as
+pub async fn get_raw_price(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<RawPriceResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching raw price for symbol: {}", symbol);
+    
+    match state.oracle_manager.get_current_price(&symbol).await {
+        Ok(price_data) => Ok(ApiJson(RawPriceResponse::from_price_data(&price_data))),
+        Err(e) => {
+            error!("Failed to get raw price for {}: {}", symbol, e);
+            Err(price_error_response(&symbol, &e))
+        }
+    }
+}
+
+/// Get OHLC candles for a symbol built from its price history
+pub async fn get_ohlc(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<OhlcQuery>,
+) -> Result<ApiJson<Vec<Candle>>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching OHLC candles for symbol: {}", symbol);
+    
+    let interval = query.interval.unwrap_or_else(|| "1m".to_string());
+    let Some(interval_secs) = parse_candle_interval(&interval) else {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            ApiJson(serde_json::json!({
+                "error": "Unsupported interval",
+                "symbol": symbol,
+                "message": format!("interval must be one of: {}",
+                    CANDLE_INTERVALS.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", "))
+            }))
+        ));
+    };
+    let max = state.oracle_manager.settings().max_history_records;
+    let limit = history_limit(&symbol, query.limit, 100, max)?.clamp(1, MAX_CANDLES);
+    
+    match state.oracle_manager.get_candles(&symbol, interval_secs, limit).await {
+        Ok(candles) => Ok(ApiJson(candles)),
+        Err(e) => {
+            error!("Failed to build candles for {}: {}", symbol, e);
+            Err(history_error_response(&state, &symbol, "Candles not available", &e))
+        }
+    }
+}
+
+/// Look up an allowlisted candle interval
+fn parse_candle_interval(interval: &str) -> Option<i64> {
+    CANDLE_INTERVALS.iter()
+        .find(|(name, _)| *name == interval)
+        .map(|&(_, secs)| secs)
+}
+
+/// Whether a price's confidence, in basis points of price, is above `max_bp`
+fn confidence_exceeds(price_data: &PriceData, max_bp: u64) -> bool {
+    price_data.confidence_percentage() * 100.0 > max_bp as f64
+}
+
+/// Evict a symbol's cached price and replace it with a fresh fetch
+pub async fn refresh_symbol(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<PriceResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Refresh requested for symbol: {}", symbol);
+    
+    if let Err(e) = state.oracle_manager.symbol_config(&symbol) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            ApiJson(serde_json::json!({
+                "error": "Symbol not configured",
+                "symbol": symbol,
+                "message": e.to_string()
+            }))
+        ));
+    }
+    
+    match state.oracle_manager.refresh_symbol(&symbol).await {
+        Ok(price_data) => Ok(ApiJson(PriceResponse::from_price_data(&price_data))),
+        Err(e) => {
+            error!("Refresh failed for {}: {}", symbol, e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                ApiJson(serde_json::json!({
+                    "error": "Refresh failed",
+                    "symbol": symbol,
+                    "message": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// Stop accepting WebSocket clients so the replica can be shut down cleanly
+pub async fn start_drain(
+    State(state): State<ApiState>,
+) -> ApiJson<serde_json::Value> {
+    state.oracle_manager.start_drain();
+    
+    ApiJson(serde_json::json!({
+        "draining": true,
+        "websocket_connections": state.oracle_manager.broadcast_sender().receiver_count(),
+    }))
+}
+
+/// Stop fetching a source on every replica, e.g. when it is compromised
+pub async fn disable_source(
+    State(state): State<ApiState>,
+    Path(source): Path<String>,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    set_source_disabled(&state, &source, true).await
+}
+
+/// Resume fetching a source previously switched off
+pub async fn enable_source(
+    State(state): State<ApiState>,
+    Path(source): Path<String>,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    set_source_disabled(&state, &source, false).await
+}
+
+async fn set_source_disabled(
+    state: &ApiState,
+    source: &str,
+    disabled: bool,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Kill-switch for source {}: disabled={}", source, disabled);
+    
+    let parsed: PriceSource = source.parse().map_err(|e: anyhow::Error| (
+        StatusCode::BAD_REQUEST,
+        ApiJson(serde_json::json!({
+            "error": "Unknown source",
+            "source": source,
+            "message": e.to_string()
+        }))
+    ))?;
+    
+    match state.oracle_manager.set_source_disabled(parsed.clone(), disabled).await {
+        Ok(disabled_sources) => Ok(ApiJson(serde_json::json!({
+            "source": parsed,
+            "disabled": disabled,
+            "disabled_sources": disabled_sources,
+        }))),
+        Err(e) => {
+            error!("Failed to persist kill-switch for {}: {}", source, e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                ApiJson(serde_json::json!({
+                    "error": "Kill-switch not persisted",
+                    "source": source,
+                    "message": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// Silence outlier and manipulation alerts for a source, e.g. during planned maintenance
+pub async fn mute_source(
+    State(state): State<ApiState>,
+    Path(source): Path<String>,
+    Query(params): Query<MuteQuery>,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    let ttl_secs = params.ttl_secs.unwrap_or(DEFAULT_MUTE_TTL_SECS);
+    if ttl_secs == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            ApiJson(serde_json::json!({
+                "error": "Invalid TTL",
+                "source": source,
+                "message": "ttl_secs must be greater than 0"
+            }))
+        ));
+    }
+    
+    set_source_muted(&state, &source, Some(ttl_secs)).await
+}
+
+/// Restore alerts for a muted source before its TTL runs out
+pub async fn unmute_source(
+    State(state): State<ApiState>,
+    Path(source): Path<String>,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    set_source_muted(&state, &source, None).await
+}
+
+async fn set_source_muted(
+    state: &ApiState,
+    source: &str,
+    ttl_secs: Option<u64>,
+) -> Result<ApiJson<serde_json::Value>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Alert mute for source {}: ttl={:?}", source, ttl_secs);
+    
+    let parsed: PriceSource = source.parse().map_err(|e: anyhow::Error| (
+        StatusCode::BAD_REQUEST,
+        ApiJson(serde_json::json!({
+            "error": "Unknown source",
+            "source": source,
+            "message": e.to_string()
+        }))
+    ))?;
+    
+    match state.oracle_manager.set_source_muted(parsed.clone(), ttl_secs).await {
+        Ok(muted_sources) => Ok(ApiJson(serde_json::json!({
+            "source": parsed,
+            "muted_until": muted_sources.get(&parsed),
+            "muted_sources": muted_sources,
+        }))),
+        Err(e) => {
+            error!("Failed to persist alert mute for {}: {}", source, e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                ApiJson(serde_json::json!({
+                    "error": "Mute not persisted",
+                    "source": source,
+                    "message": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// Run one fetch cycle for a symbol on behalf of an external scheduler
+pub async fn trigger_fetch(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<PriceResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Triggered fetch for symbol: {}", symbol);
+    
+    if let Err(e) = state.oracle_manager.symbol_config(&symbol) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            ApiJson(serde_json::json!({
+                "error": "Symbol not configured",
+                "symbol": symbol,
+                "message": e.to_string()
+            }))
+        ));
+    }
+    
+    match state.oracle_manager.fetch_symbol_once(&symbol).await {
+        Ok(price_data) => Ok(ApiJson(PriceResponse::from_price_data(&price_data))),
+        Err(e) => {
+            error!("Triggered fetch failed for {}: {}", symbol, e);
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                ApiJson(serde_json::json!({
+                    "error": "Fetch failed",
                     "symbol": symbol,
                     "message": e.to_string()
                 }))
@@ -88,26 +878,20 @@ pub async fn get_price(
 /// Get current prices for all configured symbols
 pub async fn get_all_prices(
     State(state): State<ApiState>,
-) -> Result<Json<HashMap<String, PriceResponse>>, (StatusCode, Json<serde_json::Value>)> {
+    Query(query): Query<AllPricesQuery>,
+) -> Result<ApiJson<AllPricesResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching all prices");
     
-    let prices = state.oracle_manager.get_all_prices().await;
-    
-    let response: HashMap<String, PriceResponse> = prices
-        .iter()
-        .map(|(symbol, price_data)| {
-            (symbol.clone(), PriceResponse::from_price_data(price_data))
-        })
-        .collect();
+    let all_prices = state.oracle_manager.get_all_prices_detailed().await;
     
-    Ok(Json(response))
+    Ok(ApiJson(AllPricesResponse::from_all_prices(&all_prices, query.include_errors, query.string_prices)))
 }
 
 /// Get prices for multiple symbols in batch
 pub async fn get_batch_prices(
     State(state): State<ApiState>,
     Json(request): Json<BatchPriceRequest>,
-) -> Result<Json<HashMap<String, Option<PriceResponse>>>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<ApiJson<HashMap<String, Option<PriceResponse>>>, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching batch prices for {} symbols", request.symbols.len());
     
     let mut response = HashMap::new();
@@ -123,31 +907,158 @@ pub async fn get_batch_prices(
         }
     }
     
-    Ok(Json(response))
+    Ok(ApiJson(response))
 }
 
 /// Get price history for a symbol
 pub async fn get_price_history(
-    State(_state): State<ApiState>,
+    State(state): State<ApiState>,
     Path(symbol): Path<String>,
     Query(query): Query<HistoryQuery>,
-) -> Result<Json<Vec<PriceResponse>>, (StatusCode, Json<serde_json::Value>)> {
+    headers: HeaderMap,
+) -> Result<Response, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching price history for symbol: {}", symbol);
     
-    let _limit = query.limit.unwrap_or(100).min(1000); // Cap at 1000 entries
+    let limit = history_limit(&symbol, query.limit, 100, state.oracle_manager.settings().max_history_records)?;
+    
+    if wants_csv(query.format.as_deref(), &headers) {
+        return stream_history_csv(&state, &symbol, limit, query.since);
+    }
+    
+    match state.oracle_manager.get_price_history(&symbol, limit, query.since).await {
+        Ok(history) => {
+            let response: Vec<PriceResponse> = history.iter()
+                .map(PriceResponse::from_price_data)
+                .collect();
+            Ok(ApiJson(response).into_response())
+        },
+        Err(e) => {
+            error!("Failed to read price history for {}: {}", symbol, e);
+            Err(history_error_response(&state, &symbol, "History not available", &e))
+        }
+    }
+}
+
+/// Records a history request may return: its `limit`, else `default`, never above `max`
+///
+/// An explicit limit above `max` is a 400 naming the max rather than being
+/// silently cut down, so callers paging through a range notice.
+fn history_limit(
+    symbol: &str,
+    requested: Option<usize>,
+    default: usize,
+    max: usize,
+) -> Result<usize, (StatusCode, ApiJson<serde_json::Value>)> {
+    match requested {
+        Some(limit) if limit > max => Err((
+            StatusCode::BAD_REQUEST,
+            ApiJson(serde_json::json!({
+                "error": "Limit too large",
+                "symbol": symbol,
+                "message": format!("limit {} exceeds the maximum of {} records", limit, max),
+                "max": max
+            }))
+        )),
+        Some(limit) => Ok(limit),
+        None => Ok(default.min(max)),
+    }
+}
+
+/// Map a failed history read to a response
+///
+/// A symbol with no history yet is not an error, it reads as an empty list.
+/// Only an unconfigured symbol is a 404; anything else means the store failed.
+fn history_error_response(
+    state: &ApiState,
+    symbol: &str,
+    error: &str,
+    e: &anyhow::Error,
+) -> (StatusCode, ApiJson<serde_json::Value>) {
+    let configured = state.oracle_manager.symbol_config(symbol).is_ok();
+    (
+        history_error_status(configured),
+        ApiJson(serde_json::json!({
+            "error": error,
+            "symbol": symbol,
+            "message": e.to_string()
+        }))
+    )
+}
+
+fn history_error_status(configured: bool) -> StatusCode {
+    if configured {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// Whether the caller asked for CSV via `?format=csv` or the Accept header
+fn wants_csv(format: Option<&str>, headers: &HeaderMap) -> bool {
+    if let Some(format) = format {
+        return format.eq_ignore_ascii_case("csv");
+    }
+    headers.get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/csv"))
+}
+
+/// Stream stored history as CSV, forwarding each page of rows as it is read
+fn stream_history_csv(
+    state: &ApiState,
+    symbol: &str,
+    limit: usize,
+    since: Option<i64>,
+) -> Result<Response, (StatusCode, ApiJson<serde_json::Value>)> {
+    let pages = state.oracle_manager.price_history_pages(symbol, limit, since)
+        .map_err(|e| history_error_response(state, symbol, "Symbol not configured", &e))?;
+    
+    let rows = pages.map_ok(|page| page.iter().map(history_csv_row).collect::<String>());
+    let body = stream::once(async { Ok::<_, anyhow::Error>(HISTORY_CSV_HEADER.to_string()) }).chain(rows);
+    
+    Ok((
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        Body::from_stream(body),
+    ).into_response())
+}
+
+/// One CSV row, with exact decimal price and confidence
+fn history_csv_row(price_data: &PriceData) -> String {
+    format!("{},{},{},{:?}\n",
+        price_data.timestamp,
+        price_data.to_decimal_string(),
+        price_data.confidence_to_decimal_string(),
+        price_data.source)
+}
+
+/// Get rolling volatility for a symbol from its price history
+pub async fn get_volatility(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<VolatilityQuery>,
+) -> Result<ApiJson<VolatilityResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching volatility for symbol: {}", symbol);
     
-    // This would typically come from a database
-    // For now, we'll return a placeholder response
-    let response = vec![];
+    let window_secs = query.window_secs.unwrap_or(3600).clamp(1, 86400); // Cap at 24h
     
-    Ok(Json(response))
+    match state.oracle_manager.get_volatility(&symbol, window_secs).await {
+        Ok(stats) => Ok(ApiJson(VolatilityResponse {
+            symbol,
+            window_secs,
+            stats,
+        })),
+        Err(e) => {
+            error!("Failed to compute volatility for {}: {}", symbol, e);
+            Err(history_error_response(&state, &symbol, "Volatility not available", &e))
+        }
+    }
 }
 
 /// Get individual source prices for a symbol (before aggregation)
 pub async fn get_source_prices(
     State(_state): State<ApiState>,
     Path(symbol): Path<String>,
-) -> Result<Json<SourcePricesResponse>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<ApiJson<SourcePricesResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching source prices for symbol: {}", symbol);
     
     // This would fetch individual oracle prices
@@ -158,20 +1069,91 @@ pub async fn get_source_prices(
         aggregated: None,
     };
     
-    Ok(Json(response))
+    Ok(ApiJson(response))
+}
+
+/// Compare each source's current price against the aggregate
+pub async fn get_source_diff(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<SourceDiffResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching source diff for symbol: {}", symbol);
+    
+    match state.oracle_manager.get_source_diff(&symbol).await {
+        Ok(diff) => Ok(ApiJson(SourceDiffResponse::from_diff(&diff))),
+        Err(e) => {
+            error!("Failed to diff sources for {}: {}", symbol, e);
+            Err((
+                StatusCode::NOT_FOUND,
+                ApiJson(serde_json::json!({
+                    "error": "Symbol not available",
+                    "symbol": symbol,
+                    "message": e.to_string()
+                }))
+            ))
+        }
+    }
+}
+
+/// Get the aggregation parameters in effect for a symbol
+pub async fn get_aggregation_config(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+) -> Result<ApiJson<AggregationConfig>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching aggregation config for symbol: {}", symbol);
+    
+    match state.oracle_manager.aggregation_config(&symbol) {
+        Ok(config) => Ok(ApiJson(config)),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            ApiJson(serde_json::json!({
+                "error": "Symbol not configured",
+                "symbol": symbol,
+                "message": e.to_string()
+            }))
+        )),
+    }
+}
+
+/// Consensus traces of the symbol's recent fetch cycles, newest first
+///
+/// Unlike `?explain` this shows sources that are only occasionally filtered.
+pub async fn get_traces(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<TraceQuery>,
+) -> Result<ApiJson<Vec<TracedCycle>>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching consensus traces for symbol: {}", symbol);
+    
+    match state.oracle_manager.get_traces(&symbol, query.limit.unwrap_or(usize::MAX)).await {
+        Ok(traces) => Ok(ApiJson(traces)),
+        Err(e) => Err((
+            StatusCode::NOT_FOUND,
+            ApiJson(serde_json::json!({
+                "error": "Symbol not configured",
+                "symbol": symbol,
+                "message": e.to_string()
+            }))
+        )),
+    }
 }
 
 /// Get oracle health status
 pub async fn get_oracle_health(
     State(state): State<ApiState>,
-) -> Result<Json<HealthResponse>, (StatusCode, Json<serde_json::Value>)> {
+) -> Result<ApiJson<HealthResponse>, (StatusCode, ApiJson<serde_json::Value>)> {
     info!("Fetching oracle health status");
     
     let health_status = state.oracle_manager.get_health_status().await;
+    let baselines = state.oracle_manager.get_baselines().await;
     
     let oracles: HashMap<String, OracleHealthStatus> = health_status
         .iter()
-        .map(|(symbol, health)| (symbol.clone(), health.into()))
+        .map(|(symbol, health)| {
+            let mut status = OracleHealthStatus::from(health);
+            status.baseline = baselines.get(symbol).copied();
+            (symbol.clone(), status)
+        })
         .collect();
     
     let overall_healthy = oracles.values().all(|status| status.is_healthy);
@@ -187,13 +1169,31 @@ pub async fn get_oracle_health(
         uptime: 0, // This would be calculated from service start time
     };
     
-    Ok(Json(response))
+    Ok(ApiJson(response))
+}
+
+/// Recent health snapshots for a symbol, newest first, to chart gradual degradation
+pub async fn get_health_history(
+    State(state): State<ApiState>,
+    Path(symbol): Path<String>,
+    Query(query): Query<HealthHistoryQuery>,
+) -> Result<ApiJson<Vec<HealthSnapshot>>, (StatusCode, ApiJson<serde_json::Value>)> {
+    info!("Fetching health history for symbol: {}", symbol);
+    
+    let limit = history_limit(&symbol, query.limit, usize::MAX, state.oracle_manager.settings().max_history_records)?;
+    match state.oracle_manager.get_health_history(&symbol, limit).await {
+        Ok(history) => Ok(ApiJson(history)),
+        Err(e) => {
+            error!("Failed to read health history for {}: {}", symbol, e);
+            Err(history_error_response(&state, &symbol, "Health history not available", &e))
+        }
+    }
 }
 
 /// Get oracle statistics and metrics
 pub async fn get_oracle_stats(
     State(_state): State<ApiState>,
-) -> Result<Json<OracleStatsResponse>, StatusCode> {
+) -> Result<ApiJson<OracleStatsResponse>, StatusCode> {
     info!("Fetching oracle statistics");
     
     // This would collect various metrics
@@ -206,7 +1206,55 @@ pub async fn get_oracle_stats(
         error_rate: 0.0,
     };
     
-    Ok(Json(response))
+    Ok(ApiJson(response))
+}
+
+/// Get the distribution of source-vs-aggregate deviations, by source
+pub async fn get_deviation_histograms(
+    State(state): State<ApiState>,
+) -> ApiJson<HashMap<String, Histogram>> {
+    info!("Fetching deviation histograms");
+    
+    ApiJson(state.oracle_manager.metrics().deviation_histograms().await)
+}
+
+/// Get fetch outcomes and latency, by symbol label
+pub async fn get_fetch_stats(
+    State(state): State<ApiState>,
+) -> ApiJson<HashMap<String, FetchStats>> {
+    info!("Fetching fetch cycle statistics");
+    
+    ApiJson(state.oracle_manager.metrics().fetch_stats().await)
+}
+
+/// A feed account that parsed and passed validation
+#[derive(Debug, Serialize)]
+pub struct FeedValidationResponse {
+    pub valid: bool,
+    pub source: PriceSource,
+    pub address: String,
+    pub price: f64,
+    pub confidence: f64,
+    pub confidence_bp: Option<f64>,
+    pub expo: i32,
+    pub timestamp: i64,
+    pub age_secs: i64,
+}
+
+impl FeedValidationResponse {
+    fn new(source: PriceSource, address: String, price_data: &PriceData, now: i64) -> Self {
+        Self {
+            valid: true,
+            source,
+            address,
+            price: price_data.to_decimal(),
+            confidence: price_data.confidence_to_decimal(),
+            confidence_bp: price_data.relative_confidence().map(|ratio| ratio * 10000.0),
+            expo: price_data.expo,
+            timestamp: price_data.timestamp,
+            age_secs: (now - price_data.timestamp).max(0),
+        }
+    }
 }
 
 /// Response structure for source prices
@@ -217,6 +1265,158 @@ pub struct SourcePricesResponse {
     pub aggregated: Option<PriceResponse>,
 }
 
+/// Price with the explicit band its confidence interval spans
+#[derive(Debug, Serialize)]
+pub struct ConfidenceBandResponse {
+    pub symbol: String,
+    pub price: f64,
+    pub lower: f64, // price - confidence
+    pub upper: f64, // price + confidence
+    pub confidence: f64,
+    pub timestamp: i64,
+}
+
+impl ConfidenceBandResponse {
+    pub fn from_price_data(price_data: &PriceData) -> Self {
+        let (lower, upper) = price_data.confidence_band();
+        Self {
+            symbol: price_data.symbol.clone(),
+            price: price_data.to_decimal(),
+            lower,
+            upper,
+            confidence: price_data.confidence_to_decimal(),
+            timestamp: price_data.timestamp,
+        }
+    }
+}
+
+/// Price as its fixed-point mantissa and exponent, for exact integer math
+///
+/// The value is `price * 10^expo`; `confidence` shares the same exponent.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawPriceResponse {
+    pub symbol: String,
+    pub price: i64,
+    pub confidence: u64,
+    pub expo: i32,
+    pub timestamp: i64,
+    pub source: PriceSource,
+}
+
+impl RawPriceResponse {
+    pub fn from_price_data(price_data: &PriceData) -> Self {
+        Self {
+            symbol: price_data.symbol.clone(),
+            price: price_data.price,
+            confidence: price_data.confidence,
+            expo: price_data.expo,
+            timestamp: price_data.timestamp,
+            source: price_data.source.clone(),
+        }
+    }
+}
+
+/// Response structure for volatility
+#[derive(Debug, Serialize)]
+pub struct VolatilityResponse {
+    pub symbol: String,
+    pub window_secs: i64,
+    #[serde(flatten)]
+    pub stats: VolatilityStats,
+}
+
+/// Price response, with the derivation attached when `?explain=true` and the
+/// conversion path when `?quote=` is set
+#[derive(Debug, Serialize)]
+pub struct ExplainedPriceResponse {
+    #[serde(flatten)]
+    pub price: PriceResponse,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<AggregationTrace>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversion: Option<Vec<ConversionLeg>>, // Legs used when `?quote=` converted the price
+}
+
+/// Response structure for all prices
+///
+/// Prices stay keyed by symbol at the top level; `errors` is only present
+/// when the caller asked for it.
+#[derive(Debug, Serialize)]
+pub struct AllPricesResponse {
+    #[serde(flatten)]
+    pub prices: HashMap<String, PriceResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errors: Option<HashMap<String, String>>,
+}
+
+impl AllPricesResponse {
+    pub fn from_all_prices(all_prices: &AllPrices, include_errors: bool, string_prices: bool) -> Self {
+        Self {
+            prices: all_prices.prices.iter()
+                .map(|(symbol, price_data)| (symbol.clone(), PriceResponse::from_price_data_as(price_data, string_prices)))
+                .collect(),
+            errors: include_errors.then(|| all_prices.errors.clone()),
+        }
+    }
+}
+
+/// Response structure for source disagreement
+#[derive(Debug, Serialize)]
+pub struct SourceDiffResponse {
+    pub symbol: String,
+    pub aggregated: Option<PriceResponse>,
+    pub aggregation_error: Option<String>,
+    pub sources: Vec<SourceDiffEntry>,
+    pub baseline: Option<Baseline>, // Rolling mean/std the manipulation detector compares against
+    pub source_priority: Vec<SourcePriority>, // Health ranking, best first; empty unless ranking is on
+}
+
+/// One source's reading and its distance from the aggregate
+#[derive(Debug, Serialize)]
+pub struct SourceDiffEntry {
+    pub source: PriceSource,
+    pub price: Option<PriceResponse>,
+    pub deviation_bp: Option<f64>, // Deviation from the aggregate in basis points
+    pub error: Option<String>,
+}
+
+impl SourceDiffResponse {
+    pub fn from_diff(diff: &SourceDiff) -> Self {
+        let aggregate_price = diff.aggregated.as_ref().ok().map(|p| p.to_decimal());
+        
+        let sources = diff.readings.iter()
+            .map(|(source, result)| match result {
+                Ok(price_data) => {
+                    let price = price_data.to_decimal();
+                    SourceDiffEntry {
+                        source: source.clone(),
+                        price: Some(PriceResponse::from_price_data(price_data)),
+                        deviation_bp: aggregate_price
+                            .filter(|aggregate| *aggregate != 0.0)
+                            .map(|aggregate| (price - aggregate) / aggregate * 10000.0),
+                        error: None,
+                    }
+                },
+                Err(e) => SourceDiffEntry {
+                    source: source.clone(),
+                    price: None,
+                    deviation_bp: None,
+                    error: Some(e.clone()),
+                },
+            })
+            .collect();
+        
+        Self {
+            symbol: diff.symbol.clone(),
+            aggregated: diff.aggregated.as_ref().ok().map(PriceResponse::from_price_data),
+            aggregation_error: diff.aggregated.as_ref().err().cloned(),
+            sources,
+            baseline: diff.baseline,
+            source_priority: diff.priority.clone(),
+        }
+    }
+}
+
 /// Response structure for oracle statistics
 #[derive(Debug, Serialize)]
 pub struct OracleStatsResponse {
@@ -233,12 +1433,15 @@ pub async fn start_server(
     host: &str,
     port: u16,
     oracle_manager: Arc<OracleManager>,
+    push_mode: PushMode,
+    admin_api_key: Option<String>,
 ) -> anyhow::Result<()> {
     let state = ApiState {
         oracle_manager,
+        admin_api_key: admin_api_key.map(Arc::from),
     };
     
-    let app = create_router(state);
+    let app = create_router(state, push_mode);
     let addr = format!("{}:{}", host, port);
     
     info!("Starting REST API server on {}", addr);
@@ -251,8 +1454,9 @@ pub async fn start_server(
 
 #[cfg(test)]
 mod tests {
-    // Tests commented out - require proper mock setup for OracleManager
-    // use super::*;
+    use super::*;
+    
+    // Router tests commented out - require proper mock setup for OracleManager
     // use axum::{
     //     body::Body,
     //     http::{Request, Method},
@@ -281,4 +1485,287 @@ mod tests {
         
         // assert_eq!(response.status(), StatusCode::OK);
     }
+    
+    #[test]
+    fn test_source_diff_deviation() {
+        let price = |price: i64, source: PriceSource| PriceData {
+            price,
+            confidence: 1_00000000,
+            expo: -8,
+            timestamp: 1000,
+            source,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        let diff = SourceDiff {
+            symbol: "BTC/USD".to_string(),
+            readings: vec![
+                (PriceSource::Pyth, Ok(price(50100_00000000, PriceSource::Pyth))),
+                (PriceSource::Switchboard, Err("Stale Switchboard data".to_string())),
+            ],
+            aggregated: Ok(price(50000_00000000, PriceSource::Aggregated)),
+            baseline: None,
+            priority: Vec::new(),
+        };
+        
+        let response = SourceDiffResponse::from_diff(&diff);
+        
+        assert!(response.aggregated.is_some());
+        assert_eq!(response.sources.len(), 2);
+        
+        // 100 / 50000 = 20 bp above the aggregate
+        let pyth = &response.sources[0];
+        assert!((pyth.deviation_bp.unwrap() - 20.0).abs() < 1e-9);
+        assert!(pyth.error.is_none());
+        
+        // Failed sources carry their error instead of a deviation
+        let switchboard = &response.sources[1];
+        assert!(switchboard.price.is_none());
+        assert!(switchboard.deviation_bp.is_none());
+        assert_eq!(switchboard.error.as_deref(), Some("Stale Switchboard data"));
+    }
+    
+    #[test]
+    fn test_raw_price_keeps_fixed_point_fields() {
+        let price_data = PriceData {
+            price: 50012_34567891,
+            confidence: 1_23456789,
+            expo: -8,
+            timestamp: 1700000000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        let json = serde_json::to_string(&RawPriceResponse::from_price_data(&price_data)).unwrap();
+        let raw: RawPriceResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(raw.symbol, price_data.symbol);
+        assert_eq!(raw.price, price_data.price);
+        assert_eq!(raw.confidence, price_data.confidence);
+        assert_eq!(raw.expo, price_data.expo);
+        assert_eq!(raw.timestamp, price_data.timestamp);
+        assert_eq!(raw.source, price_data.source);
+        
+        // Integers on the wire, not decimals
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["price"], serde_json::json!(5001234567891i64));
+        assert!(value["price"].is_i64());
+    }
+    
+    #[test]
+    fn test_all_prices_errors_opt_in() {
+        let mut all_prices = AllPrices::default();
+        all_prices.prices.insert("BTC/USD".to_string(), PriceData {
+            price: 50000_00000000,
+            confidence: 1_00000000,
+            expo: -8,
+            timestamp: 1700000000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        });
+        all_prices.errors.insert("ETH/USD".to_string(), "All sources failed".to_string());
+        
+        let plain = serde_json::to_value(AllPricesResponse::from_all_prices(&all_prices, false, false)).unwrap();
+        assert!(plain.get("BTC/USD").is_some());
+        assert!(plain.get("errors").is_none());
+        
+        let detailed = serde_json::to_value(AllPricesResponse::from_all_prices(&all_prices, true, false)).unwrap();
+        assert!(detailed.get("BTC/USD").is_some());
+        assert_eq!(detailed["errors"]["ETH/USD"], "All sources failed");
+    }
+    
+    #[test]
+    fn test_deviation_error_detail() {
+        let e: anyhow::Error = OracleError::DeviationExceeded {
+            symbol: "BTC/USD".to_string(),
+            price_source: PriceSource::Switchboard,
+            value: 53000.0,
+            median: 50000.0,
+            base: crate::aggregator::DeviationBase::Median,
+            reference: 50000.0,
+            deviation_bp: 600.0,
+            max_deviation_bp: 100,
+        }.into();
+        
+        let (status, ApiJson(body)) = price_error_response("BTC/USD", &e);
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["source"], "Switchboard");
+        assert_eq!(body["value"], 53000.0);
+        assert_eq!(body["median"], 50000.0);
+        assert_eq!(body["reference"], 50000.0);
+        assert_eq!(body["deviation_bp"], 600.0);
+        
+        let (status, _) = price_error_response("BTC/USD", &anyhow::anyhow!("No price sources available"));
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+    
+    #[test]
+    fn test_envelope_requested() {
+        assert!(!envelope_requested(None, false));
+        assert!(envelope_requested(None, true));
+        assert!(envelope_requested(Some("envelope=true"), false));
+        assert!(envelope_requested(Some("explain=true&envelope=1"), false));
+        assert!(envelope_requested(Some("envelope"), false));
+        
+        // Per-request opt-out of an always-on envelope
+        assert!(!envelope_requested(Some("envelope=false"), true));
+        assert!(envelope_requested(Some("explain=true"), true));
+        
+        let envelope = Envelope {
+            data: serde_json::json!({"price": 50000.0}),
+            meta: ResponseMeta::now(Some("replica-1".to_string())),
+        };
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["data"]["price"], 50000.0);
+        assert_eq!(json["meta"]["instance_id"], "replica-1");
+        assert_eq!(json["meta"]["version"], env!("CARGO_PKG_VERSION"));
+    }
+    
+    #[tokio::test]
+    async fn test_api_json_envelope() {
+        let body = |response: Response| async move {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            serde_json::from_slice::<serde_json::Value>(&bytes).unwrap()
+        };
+        let reply = || ApiJson(serde_json::json!({"price": 50000.0}));
+        
+        // Bare outside an enveloped request
+        assert_eq!(body(reply().into_response()).await, serde_json::json!({"price": 50000.0}));
+        assert_eq!(body(ENVELOPE_INSTANCE.scope(None, async { reply().into_response() }).await).await["price"], 50000.0);
+        
+        // Errors are wrapped like any other body
+        let wrapped = ENVELOPE_INSTANCE.scope(Some(Some("replica-1".to_string())), async {
+            (StatusCode::NOT_FOUND, reply()).into_response()
+        }).await;
+        assert_eq!(wrapped.status(), StatusCode::NOT_FOUND);
+        let json = body(wrapped).await;
+        assert_eq!(json["data"]["price"], 50000.0);
+        assert_eq!(json["meta"]["instance_id"], "replica-1");
+    }
+    
+    #[test]
+    fn test_price_deadline() {
+        assert_eq!(price_deadline(None, 0), None);
+        assert_eq!(price_deadline(None, 250), Some(Duration::from_millis(250)));
+        
+        // The request overrides config, and 0 lifts the configured budget
+        assert_eq!(price_deadline(Some(100), 250), Some(Duration::from_millis(100)));
+        assert_eq!(price_deadline(Some(0), 250), None);
+        
+        let e: anyhow::Error = OracleError::DeadlineExceeded {
+            symbol: "BTC/USD".to_string(),
+            deadline_ms: 100,
+        }.into();
+        let (status, ApiJson(body)) = price_error_response("BTC/USD", &e);
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert_eq!(body["deadline_ms"], 100);
+    }
+    
+    #[test]
+    fn test_history_csv_format() {
+        let row = history_csv_row(&PriceData {
+            price: 50012_34567890,
+            confidence: 5_00000000,
+            expo: -8,
+            timestamp: 1640995200,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(row, "1640995200,50012.34567890,5.00000000,Aggregated\n");
+        assert_eq!(HISTORY_CSV_HEADER.split(',').count(), row.split(',').count());
+        
+        let mut headers = HeaderMap::new();
+        assert!(!wants_csv(None, &headers));
+        assert!(wants_csv(Some("CSV"), &headers));
+        headers.insert(header::ACCEPT, "text/csv".parse().unwrap());
+        assert!(wants_csv(None, &headers));
+        assert!(!wants_csv(Some("json"), &headers));
+    }
+    
+    #[test]
+    fn test_max_confidence_threshold() {
+        // $5 on $50,000 is 1bp
+        let price_data = PriceData {
+            price: 50000_00000000,
+            confidence: 5_00000000,
+            expo: -8,
+            timestamp: 1700000000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        assert!(confidence_exceeds(&price_data, 0));
+        assert!(!confidence_exceeds(&price_data, 1));
+        assert!(!confidence_exceeds(&price_data, 50));
+    }
+    
+    #[test]
+    fn test_feed_validation_response() {
+        let price_data = PriceData {
+            price: 50000_00000000,
+            confidence: 25_00000000,
+            expo: -8,
+            timestamp: 1640995190,
+            source: PriceSource::Pyth,
+            symbol: String::new(),
+            ..Default::default()
+        };
+        let response = FeedValidationResponse::new(
+            PriceSource::Pyth,
+            "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU".to_string(),
+            &price_data,
+            1640995200,
+        );
+        
+        assert!(response.valid);
+        assert_eq!(response.price, 50000.0);
+        assert_eq!(response.confidence_bp, Some(5.0));
+        assert_eq!(response.age_secs, 10);
+    }
+    
+    #[test]
+    fn test_admin_key_check() {
+        let mut headers = HeaderMap::new();
+        assert!(!is_authorized(&headers, Some("secret")));
+        
+        headers.insert(ADMIN_KEY_HEADER, "wrong".parse().unwrap());
+        assert!(!is_authorized(&headers, Some("secret")));
+        
+        headers.insert(ADMIN_KEY_HEADER, "secret".parse().unwrap());
+        assert!(is_authorized(&headers, Some("secret")));
+        
+        // No configured key locks the admin routes
+        assert!(!is_authorized(&headers, None));
+    }
+    
+    #[test]
+    fn test_history_limit_enforced() {
+        assert_eq!(history_limit("BTC/USD", None, 100, 1000).unwrap(), 100);
+        assert_eq!(history_limit("BTC/USD", Some(1000), 100, 1000).unwrap(), 1000);
+        
+        // Defaults are cut down to the cap, explicit limits over it are rejected
+        assert_eq!(history_limit("BTC/USD", None, usize::MAX, 1000).unwrap(), 1000);
+        let (status, ApiJson(body)) = history_limit("BTC/USD", Some(1001), 100, 1000).unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["max"], 1000);
+    }
+    
+    #[test]
+    fn test_history_error_status() {
+        // Reserved for real failures; a fresh symbol's empty history is a 200
+        assert_eq!(history_error_status(true), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(history_error_status(false), StatusCode::NOT_FOUND);
+    }
+    
+    #[test]
+    fn test_candle_interval_allowlist() {
+        assert_eq!(parse_candle_interval("1m"), Some(60));
+        assert_eq!(parse_candle_interval("4h"), Some(14400));
+        assert_eq!(parse_candle_interval("2m"), None);
+        assert_eq!(parse_candle_interval("60"), None);
+    }
 }
\ No newline at end of file
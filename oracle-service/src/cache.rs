@@ -1,42 +1,118 @@
 use anyhow::Result;
+use futures_util::stream::StreamExt;
 use redis::{Client, AsyncCommands};
 use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
-use tracing::{debug, error};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
 
-use crate::types::PriceData;
+use crate::types::{HealthSnapshot, OracleError, PriceData, PriceSource};
+
+/// Set of sources switched off fleet-wide, shared by every replica
+const DISABLED_SOURCES_KEY: &str = "disabled_sources";
+
+/// Hash of source to the unix time its alert mute expires, shared by every replica
+const MUTED_SOURCES_KEY: &str = "muted_sources";
 
 /// Redis-based price caching for ultra-fast price queries
 pub struct PriceCache {
     client: Client,
-    connection_pool: redis::aio::ConnectionManager,
+    connection_pool: Vec<redis::aio::ConnectionManager>,
+    next_connection: AtomicUsize,
     cache_ttl: u64, // Time-to-live in seconds
+    history_enabled: bool, // Keep the history:{symbol} sorted sets
+    history_sample_interval: u64, // Seconds each history entry covers; 0 = one entry per write
+    pending_writes: Arc<AtomicUsize>,
 }
 
 impl PriceCache {
-    pub async fn new(redis_url: &str) -> Result<Self> {
-        let client = Client::open(redis_url)?;
-        let connection_pool = client.get_connection_manager().await?;
+    /// Connect the pool, failing with `OracleError::ConfigError` for a malformed
+    /// URL and `OracleError::CacheError` when Redis can't be reached
+    pub async fn new(redis_url: &str, pool_size: u32) -> Result<Self> {
+        // The URL is left out of errors since it may carry a password
+        let client = Client::open(redis_url)
+            .map_err(|e| OracleError::ConfigError(format!("Invalid Redis URL: {}", e)))?;
+        
+        // Each manager is a single multiplexed connection that reconnects on its own;
+        // several of them keep one slow command from stalling everything else
+        let pool_size = pool_size.max(1) as usize;
+        let mut connection_pool = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            connection_pool.push(client.get_connection_manager().await
+                .map_err(|e| OracleError::CacheError(format!("Redis unreachable: {}", e)))?);
+        }
+        debug!("Opened Redis pool with {} connections", pool_size);
         
         Ok(Self {
             client,
             connection_pool,
+            next_connection: AtomicUsize::new(0),
             cache_ttl: 300, // 5 minutes default TTL
+            history_enabled: true,
+            history_sample_interval: 0,
+            pending_writes: Arc::new(AtomicUsize::new(0)),
         })
     }
     
+    /// Turn the Redis-backed price history on or off
+    ///
+    /// With history off `set_price` only writes the latest price and history
+    /// reads come back empty, for deployments keeping history elsewhere.
+    pub fn with_history(mut self, enabled: bool) -> Self {
+        self.history_enabled = enabled;
+        self
+    }
+    
+    /// Keep at most one history entry per `interval_secs` window, the latest
+    /// write in the window replacing earlier ones; 0 records every write
+    ///
+    /// The `price:` key is unaffected and still updates on every write.
+    pub fn with_history_sample_interval(mut self, interval_secs: u64) -> Self {
+        self.history_sample_interval = interval_secs;
+        self
+    }
+    
+    /// Next connection from the pool, handed out round-robin
+    fn connection(&self) -> redis::aio::ConnectionManager {
+        let index = self.next_connection.fetch_add(1, Ordering::Relaxed) % self.connection_pool.len();
+        self.connection_pool[index].clone()
+    }
+    
+    /// Wait for in-flight writes to finish, returning false if the timeout hit first
+    pub async fn flush(&self, timeout: Duration) -> bool {
+        let drained = wait_for_drain(&self.pending_writes, timeout).await;
+        if !drained {
+            warn!("Timed out with {} cache writes still pending", self.pending_writes.load(Ordering::SeqCst));
+        }
+        drained
+    }
+    
     /// Set price in cache with automatic expiration
     pub async fn set_price(&self, symbol: &str, price_data: &PriceData) -> Result<()> {
-        let mut conn = self.connection_pool.clone();
+        let _pending = PendingWrite::new(&self.pending_writes);
+        let mut conn = self.connection();
         let key = format!("price:{}", symbol);
-        let value = serde_json::to_string(price_data)?;
+        let value = encode_price(price_data)?;
         
         // Set with TTL
         conn.set_ex::<_, _, ()>(&key, &value, self.cache_ttl).await?;
         
+        if !self.history_enabled {
+            debug!("Cached price for {} at ${}", symbol, self.format_price(price_data));
+            return Ok(());
+        }
+        
         // Also set in a sorted set for price history (optional)
         let history_key = format!("history:{}", symbol);
         let score = price_data.timestamp as f64;
+        
+        // Coalesce into the sample window: drop what the window already holds
+        if let Some((start, end)) = history_window(price_data.timestamp, self.history_sample_interval) {
+            conn.zrembyscore::<_, _, _, ()>(&history_key, start, end).await?;
+        }
         conn.zadd::<_, _, _, ()>(&history_key, &value, score).await?;
         
         // Keep only last 1000 entries in history
@@ -48,14 +124,14 @@ impl PriceCache {
     
     /// Get price from cache
     pub async fn get_price(&self, symbol: &str) -> Result<Option<PriceData>> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         let key = format!("price:{}", symbol);
         
         let value: Option<String> = conn.get(&key).await?;
         
         match value {
             Some(json_str) => {
-                let price_data: PriceData = serde_json::from_str(&json_str)?;
+                let price_data = decode_price(&json_str)?;
                 debug!("Retrieved cached price for {}: ${}", symbol, self.format_price(&price_data));
                 Ok(Some(price_data))
             },
@@ -68,7 +144,11 @@ impl PriceCache {
     
     /// Get price history for a symbol
     pub async fn get_price_history(&self, symbol: &str, limit: usize) -> Result<Vec<PriceData>> {
-        let mut conn = self.connection_pool.clone();
+        if !self.history_enabled {
+            return Ok(Vec::new());
+        }
+        
+        let mut conn = self.connection();
         let history_key = format!("history:{}", symbol);
         
         // Get most recent entries
@@ -76,7 +156,7 @@ impl PriceCache {
         
         let mut history = Vec::new();
         for value in values {
-            if let Ok(price_data) = serde_json::from_str::<PriceData>(&value) {
+            if let Ok(price_data) = decode_price(&value) {
                 history.push(price_data);
             }
         }
@@ -84,16 +164,42 @@ impl PriceCache {
         Ok(history)
     }
     
+    /// Get one page of history, newest first, optionally only entries at or after `since`
+    pub async fn get_price_history_page(
+        &self,
+        symbol: &str,
+        since: Option<i64>,
+        offset: usize,
+        count: usize,
+    ) -> Result<Vec<PriceData>> {
+        if !self.history_enabled {
+            return Ok(Vec::new());
+        }
+        
+        let mut conn = self.connection();
+        let history_key = format!("history:{}", symbol);
+        let min = since.map_or("-inf".to_string(), |since| since.to_string());
+        
+        let values: Vec<String> = conn.zrevrangebyscore_limit(
+            &history_key, "+inf", min, offset as isize, count as isize
+        ).await?;
+        
+        Ok(values.iter()
+            .filter_map(|value| decode_price(value).ok())
+            .collect())
+    }
+    
     /// Set multiple prices in a batch operation
     pub async fn set_multiple_prices(&self, prices: &[(String, PriceData)]) -> Result<()> {
-        let mut conn = self.connection_pool.clone();
+        let _pending = PendingWrite::new(&self.pending_writes);
+        let mut conn = self.connection();
         
         // Use pipeline for batch operations
         let mut pipe = redis::pipe();
         
         for (symbol, price_data) in prices {
             let key = format!("price:{}", symbol);
-            let value = serde_json::to_string(price_data)?;
+            let value = encode_price(price_data)?;
             pipe.set_ex(&key, &value, self.cache_ttl);
         }
         
@@ -105,7 +211,7 @@ impl PriceCache {
     
     /// Get multiple prices in a batch operation
     pub async fn get_multiple_prices(&self, symbols: &[String]) -> Result<Vec<Option<PriceData>>> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         
         let keys: Vec<String> = symbols.iter()
             .map(|symbol| format!("price:{}", symbol))
@@ -117,7 +223,7 @@ impl PriceCache {
         for value in values {
             match value {
                 Some(json_str) => {
-                    match serde_json::from_str::<PriceData>(&json_str) {
+                    match decode_price(&json_str) {
                         Ok(price_data) => results.push(Some(price_data)),
                         Err(_) => results.push(None),
                     }
@@ -131,9 +237,9 @@ impl PriceCache {
     
     /// Publish price update to subscribers
     pub async fn publish_price_update(&self, symbol: &str, price_data: &PriceData) -> Result<()> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         let channel = format!("price_updates:{}", symbol);
-        let message = serde_json::to_string(price_data)?;
+        let message = encode_price(price_data)?;
         
         let subscriber_count: i32 = conn.publish(&channel, &message).await?;
         
@@ -157,9 +263,177 @@ impl PriceCache {
         Ok(pubsub)
     }
     
+    /// Spawn a managed subscription to price updates that survives Redis restarts
+    ///
+    /// The task reconnects with exponential backoff and re-subscribes to the same
+    /// channels, emitting `PubSubEvent::Resubscribed` after each recovery so
+    /// consumers know updates may have been missed. It exits once the receiver
+    /// is dropped.
+    pub fn spawn_price_subscriber(&self, symbols: Vec<String>) -> mpsc::Receiver<PubSubEvent> {
+        let (tx, rx) = mpsc::channel(1000);
+        let client = self.client.clone();
+        let channels: Vec<String> = symbols.iter()
+            .map(|symbol| format!("price_updates:{}", symbol))
+            .collect();
+        
+        tokio::spawn(async move {
+            let mut attempt: u32 = 0;
+            let mut connected_before = false;
+            
+            while !tx.is_closed() {
+                let mut pubsub = match client.get_async_connection().await {
+                    Ok(conn) => conn.into_pubsub(),
+                    Err(e) => {
+                        attempt += 1;
+                        warn!("Redis pubsub connect failed (attempt {}): {}", attempt, e);
+                        tokio::time::sleep(reconnect_backoff(attempt)).await;
+                        continue;
+                    }
+                };
+                
+                let mut subscribed = true;
+                for channel in &channels {
+                    if let Err(e) = pubsub.subscribe(channel).await {
+                        warn!("Redis subscribe to {} failed: {}", channel, e);
+                        subscribed = false;
+                        break;
+                    }
+                }
+                if !subscribed {
+                    attempt += 1;
+                    tokio::time::sleep(reconnect_backoff(attempt)).await;
+                    continue;
+                }
+                
+                attempt = 0;
+                if connected_before {
+                    info!("Re-subscribed to {} price update channels", channels.len());
+                    if tx.send(PubSubEvent::Resubscribed).await.is_err() {
+                        break;
+                    }
+                }
+                connected_before = true;
+                
+                let mut messages = pubsub.on_message();
+                while let Some(msg) = messages.next().await {
+                    let payload: String = match msg.get_payload() {
+                        Ok(payload) => payload,
+                        Err(e) => {
+                            warn!("Unreadable pubsub payload on {}: {}", msg.get_channel_name(), e);
+                            continue;
+                        }
+                    };
+                    
+                    match decode_price(&payload) {
+                        Ok(price_data) => {
+                            if tx.send(PubSubEvent::Price(price_data)).await.is_err() {
+                                return;
+                            }
+                        },
+                        Err(e) => warn!("Invalid price update on {}: {}", msg.get_channel_name(), e),
+                    }
+                }
+                
+                // Stream ended, so the connection is gone
+                error!("Redis pubsub connection lost, reconnecting");
+                attempt += 1;
+                tokio::time::sleep(reconnect_backoff(attempt)).await;
+            }
+        });
+        
+        rx
+    }
+    
+    /// Append a health snapshot to the symbol's history, keeping the newest `max_len`
+    pub async fn push_health_snapshot(&self, symbol: &str, snapshot: &HealthSnapshot, max_len: usize) -> Result<()> {
+        let mut conn = self.connection();
+        let key = format!("health_history:{}", symbol);
+        let value = serde_json::to_string(snapshot)?;
+        
+        conn.lpush::<_, _, ()>(&key, &value).await?;
+        conn.ltrim::<_, ()>(&key, 0, max_len.max(1) as isize - 1).await?;
+        Ok(())
+    }
+    
+    /// Most recent health snapshots for a symbol, newest first
+    pub async fn get_health_history(&self, symbol: &str, limit: usize) -> Result<Vec<HealthSnapshot>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+        
+        let mut conn = self.connection();
+        let key = format!("health_history:{}", symbol);
+        let values: Vec<String> = conn.lrange(&key, 0, limit as isize - 1).await?;
+        
+        Ok(values.iter()
+            .filter_map(|value| serde_json::from_str(value).ok())
+            .collect())
+    }
+    
+    /// Switch a source off or back on for every replica
+    pub async fn set_source_disabled(&self, source: &PriceSource, disabled: bool) -> Result<()> {
+        let mut conn = self.connection();
+        let member = format!("{:?}", source);
+        
+        if disabled {
+            conn.sadd::<_, _, ()>(DISABLED_SOURCES_KEY, &member).await?;
+        } else {
+            conn.srem::<_, _, ()>(DISABLED_SOURCES_KEY, &member).await?;
+        }
+        
+        debug!("Source {} disabled: {}", member, disabled);
+        Ok(())
+    }
+    
+    /// Sources currently switched off, ignoring entries this build doesn't know
+    pub async fn get_disabled_sources(&self) -> Result<HashSet<PriceSource>> {
+        let mut conn = self.connection();
+        let members: Vec<String> = conn.smembers(DISABLED_SOURCES_KEY).await?;
+        
+        Ok(members.iter()
+            .filter_map(|member| match member.parse() {
+                Ok(source) => Some(source),
+                Err(e) => {
+                    warn!("Ignoring disabled source entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+    
+    /// Mute alerts for a source until `until` (unix seconds) on every replica, or lift the mute
+    pub async fn set_source_muted(&self, source: &PriceSource, until: Option<i64>) -> Result<()> {
+        let mut conn = self.connection();
+        let field = format!("{:?}", source);
+        
+        match until {
+            Some(until) => conn.hset::<_, _, _, ()>(MUTED_SOURCES_KEY, &field, until).await?,
+            None => conn.hdel::<_, _, ()>(MUTED_SOURCES_KEY, &field).await?,
+        }
+        
+        debug!("Source {} muted until: {:?}", field, until);
+        Ok(())
+    }
+    
+    /// Alert mutes with their expiry, including expired ones not yet removed
+    pub async fn get_muted_sources(&self) -> Result<HashMap<PriceSource, i64>> {
+        let mut conn = self.connection();
+        let entries: HashMap<String, i64> = conn.hgetall(MUTED_SOURCES_KEY).await?;
+        
+        Ok(entries.into_iter()
+            .filter_map(|(field, until)| match field.parse() {
+                Ok(source) => Some((source, until)),
+                Err(e) => {
+                    warn!("Ignoring muted source entry: {}", e);
+                    None
+                }
+            })
+            .collect())
+    }
+    
     /// Get cache statistics
     pub async fn get_stats(&self) -> Result<CacheStats> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         
         // Get basic Redis stats  
         let info: String = redis::cmd("INFO").arg("memory").query_async(&mut conn).await?;
@@ -178,33 +452,37 @@ impl PriceCache {
         })
     }
     
-    /// Clear cache for a specific symbol
+    /// Evict a symbol's cached price; its price and health history are kept
     pub async fn clear_symbol(&self, symbol: &str) -> Result<()> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         
         let price_key = format!("price:{}", symbol);
-        let history_key = format!("history:{}", symbol);
-        
-        conn.del::<_, ()>(&[price_key, history_key]).await?;
+        conn.del::<_, ()>(&price_key).await?;
         
-        debug!("Cleared cache for symbol: {}", symbol);
+        debug!("Cleared cached price for symbol: {}", symbol);
         Ok(())
     }
     
     /// Clear all cached data
     pub async fn clear_all(&self) -> Result<()> {
-        let mut conn = self.connection_pool.clone();
+        let mut conn = self.connection();
         redis::cmd("FLUSHDB").query_async::<_, ()>(&mut conn).await?;
         
         debug!("Cleared all cached data");
         Ok(())
     }
     
+    /// Round-trip a PING, as a startup check that Redis accepts our commands
+    pub async fn ping(&self) -> Result<(), OracleError> {
+        let mut conn = self.connection();
+        redis::cmd("PING").query_async::<_, String>(&mut conn).await
+            .map_err(|e| OracleError::CacheError(format!("Redis unreachable: {}", e)))?;
+        Ok(())
+    }
+    
     /// Health check for Redis connection
     pub async fn health_check(&self) -> bool {
-        let mut conn = match self.connection_pool.clone() {
-            conn => conn,
-        };
+        let mut conn = self.connection();
         
         match redis::cmd("PING").query_async::<_, String>(&mut conn).await {
             Ok(_) => {
@@ -237,6 +515,110 @@ impl PriceCache {
     }
 }
 
+/// Schema version tagged onto every cached price entry
+///
+/// v1: bare `PriceData` JSON with no tag
+/// v2: `v` tag added, plus the optional aggregation `strategy`
+pub const CACHE_SCHEMA_VERSION: u64 = 2;
+
+/// Serialize a price with the current schema version tag
+fn encode_price(price_data: &PriceData) -> Result<String> {
+    let mut value = serde_json::to_value(price_data)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.insert("v".to_string(), CACHE_SCHEMA_VERSION.into());
+    }
+    Ok(serde_json::to_string(&value)?)
+}
+
+/// Deserialize a cached price written by this or an older release
+///
+/// Entries from newer releases are read best-effort, since unknown fields
+/// are ignored, so a rolling deploy never poisons either side.
+fn decode_price(raw: &str) -> Result<PriceData> {
+    let mut value: serde_json::Value = serde_json::from_str(raw)?;
+    let version = value.get("v").and_then(|v| v.as_u64()).unwrap_or(1);
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("v");
+    }
+    
+    if version > CACHE_SCHEMA_VERSION {
+        warn!("Cached price has newer schema v{} (expected v{}), reading best-effort", version, CACHE_SCHEMA_VERSION);
+    }
+    
+    // Step older entries forward one version at a time
+    let mut current = version;
+    while current < CACHE_SCHEMA_VERSION {
+        value = match current {
+            1 => upgrade_v1(value),
+            _ => value,
+        };
+        current += 1;
+    }
+    
+    let price_data: PriceData = serde_json::from_value(value)?;
+    if version < CACHE_SCHEMA_VERSION {
+        debug!("Upgraded cached {} price from schema v{} to v{}", price_data.symbol, version, CACHE_SCHEMA_VERSION);
+    }
+    Ok(price_data)
+}
+
+/// v1 entries predate aggregation strategies
+fn upgrade_v1(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(fields) = value.as_object_mut() {
+        fields.entry("strategy").or_insert(serde_json::Value::Null);
+    }
+    value
+}
+
+/// Event delivered by a managed price-update subscription
+#[derive(Debug, Clone)]
+pub enum PubSubEvent {
+    Price(PriceData),
+    Resubscribed, // Connection was re-established; updates in between were missed
+}
+
+/// Inclusive score range of the history sample window holding `timestamp`,
+/// or `None` when sampling is off
+fn history_window(timestamp: i64, interval_secs: u64) -> Option<(i64, i64)> {
+    let interval = i64::try_from(interval_secs).ok().filter(|&interval| interval > 0)?;
+    let start = timestamp - timestamp.rem_euclid(interval);
+    Some((start, start + interval - 1))
+}
+
+/// Delay before reconnect attempt `attempt`, doubling from 100ms up to 30s
+fn reconnect_backoff(attempt: u32) -> Duration {
+    let millis = 100u64.saturating_mul(1u64 << attempt.saturating_sub(1).min(16));
+    Duration::from_millis(millis.min(30_000))
+}
+
+/// Counts an in-flight cache write for as long as the guard lives
+struct PendingWrite(Arc<AtomicUsize>);
+
+impl PendingWrite {
+    fn new(counter: &Arc<AtomicUsize>) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter.clone())
+    }
+}
+
+impl Drop for PendingWrite {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Poll until the pending counter reaches zero or the timeout elapses
+async fn wait_for_drain(pending: &AtomicUsize, timeout: Duration) -> bool {
+    let deadline = tokio::time::Instant::now() + timeout;
+    while pending.load(Ordering::SeqCst) > 0 {
+        if tokio::time::Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    true
+}
+
 /// Cache statistics structure
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CacheStats {
@@ -263,7 +645,7 @@ mod tests {
     
     async fn setup_test_cache() -> PriceCache {
         // Use Redis test instance or mock
-        PriceCache::new("redis://127.0.0.1:6379/1").await
+        PriceCache::new("redis://127.0.0.1:6379/1", 2).await
             .expect("Failed to connect to test Redis")
     }
     
@@ -275,9 +657,21 @@ mod tests {
             timestamp: chrono::Utc::now().timestamp(),
             source: PriceSource::Pyth,
             symbol: "BTC/USD".to_string(),
+            ..Default::default()
         }
     }
     
+    #[test]
+    fn test_history_window() {
+        assert_eq!(history_window(1700000123, 0), None);
+        assert_eq!(history_window(1700000123, 1), Some((1700000123, 1700000123)));
+        
+        // Every timestamp in a minute shares one window
+        assert_eq!(history_window(1700000100, 60), Some((1700000100, 1700000159)));
+        assert_eq!(history_window(1700000159, 60), Some((1700000100, 1700000159)));
+        assert_eq!(history_window(1700000160, 60), Some((1700000160, 1700000219)));
+    }
+    
     #[tokio::test]
     async fn test_set_and_get_price() {
         let cache = setup_test_cache().await;
@@ -294,6 +688,81 @@ mod tests {
         assert_eq!(retrieved_data.symbol, price_data.symbol);
     }
     
+    #[tokio::test]
+    async fn test_clear_symbol_keeps_history() {
+        let cache = setup_test_cache().await;
+        let price_data = PriceData { symbol: "CLEAR/USD".to_string(), ..create_test_price_data() };
+        
+        cache.set_price("CLEAR/USD", &price_data).await.unwrap();
+        cache.clear_symbol("CLEAR/USD").await.unwrap();
+        
+        assert!(cache.get_price("CLEAR/USD").await.unwrap().is_none());
+        let history = cache.get_price_history("CLEAR/USD", 10).await.unwrap();
+        assert!(history.iter().any(|entry| entry.timestamp == price_data.timestamp));
+    }
+    
+    #[tokio::test]
+    async fn test_history_disabled_still_caches_price() {
+        let cache = setup_test_cache().await.with_history(false);
+        let price_data = PriceData { symbol: "NOHIST/USD".to_string(), ..create_test_price_data() };
+        
+        let mut conn = cache.connection();
+        conn.del::<_, ()>("history:NOHIST/USD").await.unwrap();
+        cache.set_price("NOHIST/USD", &price_data).await.unwrap();
+        
+        // The latest price is cached, but nothing lands in the history set
+        assert_eq!(cache.get_price("NOHIST/USD").await.unwrap().map(|p| p.price), Some(price_data.price));
+        let entries: usize = conn.zcard("history:NOHIST/USD").await.unwrap();
+        assert_eq!(entries, 0);
+    }
+    
+    #[tokio::test]
+    async fn test_flush_waits_for_pending_writes() {
+        let pending = Arc::new(AtomicUsize::new(0));
+        assert!(wait_for_drain(&pending, Duration::from_millis(50)).await);
+        
+        let guard = PendingWrite::new(&pending);
+        assert!(!wait_for_drain(&pending, Duration::from_millis(50)).await);
+        
+        // Write completes while we're draining
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert!(wait_for_drain(&pending, Duration::from_secs(1)).await);
+        release.await.unwrap();
+    }
+    
+    #[test]
+    fn test_decode_v1_entry() {
+        // Written before entries carried a version or strategy
+        let v1 = r#"{"price":5000000000000,"confidence":50000000,"expo":-8,"timestamp":1700000000,"source":"Pyth","symbol":"BTC/USD"}"#;
+        
+        let price_data = decode_price(v1).unwrap();
+        assert_eq!(price_data.price, 5000000000000);
+        assert_eq!(price_data.symbol, "BTC/USD");
+        assert_eq!(price_data.strategy, None);
+        
+        // Current entries round-trip and carry the tag
+        let encoded = encode_price(&price_data).unwrap();
+        let tagged: serde_json::Value = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(tagged["v"], CACHE_SCHEMA_VERSION);
+        assert_eq!(decode_price(&encoded).unwrap(), price_data);
+        
+        // A newer replica's extra fields don't break older readers
+        let v3 = r#"{"v":3,"price":5000000000000,"confidence":50000000,"expo":-8,"timestamp":1700000000,"source":"Pyth","symbol":"BTC/USD","slot":12345}"#;
+        assert_eq!(decode_price(v3).unwrap(), price_data);
+    }
+    
+    #[test]
+    fn test_reconnect_backoff() {
+        assert_eq!(reconnect_backoff(1), Duration::from_millis(100));
+        assert_eq!(reconnect_backoff(2), Duration::from_millis(200));
+        assert_eq!(reconnect_backoff(5), Duration::from_millis(1600));
+        assert_eq!(reconnect_backoff(20), Duration::from_secs(30));
+        assert_eq!(reconnect_backoff(u32::MAX), Duration::from_secs(30));
+    }
+    
     #[tokio::test]
     async fn test_price_freshness() {
         let fresh_price = PriceData {
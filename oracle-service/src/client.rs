@@ -0,0 +1,310 @@
+use anyhow::Result;
+use futures_util::{sink::SinkExt, stream::StreamExt};
+use reqwest::{StatusCode, Url};
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tracing::{debug, warn};
+
+use crate::types::{PriceResponse, WsMessage};
+
+/// Attempts after the first before a request is given up
+const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// First delay between attempts, doubled per retry
+const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(200);
+
+/// Messages buffered for a subscriber that isn't keeping up
+const SUBSCRIPTION_BUFFER: usize = 256;
+
+/// Typed client for the oracle service's REST and WebSocket APIs
+///
+/// Requests share one HTTP connection pool, so keep a single client around
+/// rather than building one per call. Connection errors, 5xx and 429
+/// responses are retried with exponential backoff; other errors are not.
+pub struct OracleServiceClient {
+    http: reqwest::Client,
+    base_url: Url,
+    ws_url: Url,
+    max_retries: u32,
+    retry_backoff: Duration,
+}
+
+impl OracleServiceClient {
+    /// Client for a service whose REST API is at `base_url` and WebSocket server at `ws_url`
+    pub fn new(base_url: &str, ws_url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .pool_idle_timeout(Duration::from_secs(90))
+            .tcp_keepalive(Duration::from_secs(30))
+            .build()?;
+        
+        Ok(Self {
+            http,
+            base_url: Url::parse(base_url)?,
+            ws_url: Url::parse(ws_url)?,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_backoff: DEFAULT_RETRY_BACKOFF,
+        })
+    }
+    
+    /// Retry failed requests up to `max_retries` times, waiting `backoff` before the first retry
+    pub fn with_retries(mut self, max_retries: u32, backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.retry_backoff = backoff;
+        self
+    }
+    
+    /// Current price for a symbol, e.g. "BTC/USD"
+    pub async fn get_price(&self, symbol: &str) -> Result<PriceResponse> {
+        self.get_json(&["oracle", "price", symbol]).await
+    }
+    
+    /// Latest price of every configured symbol that has one, keyed by symbol
+    pub async fn get_all_prices(&self) -> Result<HashMap<String, PriceResponse>> {
+        self.get_json(&["oracle", "prices"]).await
+    }
+    
+    /// Stream price updates for `symbols` over the WebSocket API
+    ///
+    /// The initial connection is retried like a REST request. The receiver
+    /// closes when the server drops the connection; subscribe again to resume.
+    pub async fn subscribe(&self, symbols: &[String]) -> Result<mpsc::Receiver<WsMessage>> {
+        let url = endpoint(&self.ws_url, &["ws"])?;
+        let subscribe = serde_json::to_string(&WsMessage::Subscribe {
+            symbols: symbols.to_vec(),
+            channels: Vec::new(),
+            throttle_ms: HashMap::new(),
+        })?;
+        
+        let mut attempt = 0;
+        let (mut socket, _) = loop {
+            match connect_async(url.as_str()).await {
+                Ok(connected) => break connected,
+                Err(e) if attempt < self.max_retries => {
+                    warn!("WebSocket connect to {} failed, retrying: {}", url, e);
+                    tokio::time::sleep(self.retry_delay(attempt)).await;
+                    attempt += 1;
+                },
+                Err(e) => return Err(e.into()),
+            }
+        };
+        socket.send(Message::Text(subscribe)).await?;
+        
+        let (sender, receiver) = mpsc::channel(SUBSCRIPTION_BUFFER);
+        tokio::spawn(async move {
+            while let Some(message) = socket.next().await {
+                let text = match message {
+                    Ok(Message::Text(text)) => text,
+                    Ok(Message::Close(_)) | Err(_) => break,
+                    Ok(_) => continue,
+                };
+                
+                match serde_json::from_str::<WsMessage>(&text) {
+                    Ok(message) => {
+                        if sender.send(message).await.is_err() {
+                            break; // Subscriber went away
+                        }
+                    },
+                    Err(e) => warn!("Ignoring unparseable WebSocket message: {}", e),
+                }
+            }
+            debug!("WebSocket subscription closed");
+        });
+        
+        Ok(receiver)
+    }
+    
+    /// GET a JSON endpoint, retrying transient failures
+    async fn get_json<T: DeserializeOwned>(&self, segments: &[&str]) -> Result<T> {
+        let url = endpoint(&self.base_url, segments)?;
+        
+        let mut attempt = 0;
+        loop {
+            // Ask for the bare body even if the server wraps responses by default
+            let result = self.http.get(url.clone())
+                .query(&[("envelope", "false")])
+                .send()
+                .await;
+            
+            let retryable = match result {
+                Ok(response) if response.status().is_success() => return Ok(response.json().await?),
+                Ok(response) => {
+                    let status = response.status();
+                    let body = response.text().await.unwrap_or_default();
+                    let error = anyhow::anyhow!("{} returned {}: {}", url, status, error_message(&body));
+                    if !is_retryable(status) {
+                        return Err(error);
+                    }
+                    error
+                },
+                Err(e) => e.into(),
+            };
+            
+            if attempt >= self.max_retries {
+                return Err(retryable);
+            }
+            warn!("Request to {} failed, retrying: {}", url, retryable);
+            tokio::time::sleep(self.retry_delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+    
+    fn retry_delay(&self, attempt: u32) -> Duration {
+        self.retry_backoff.saturating_mul(2u32.saturating_pow(attempt))
+    }
+}
+
+/// `base` with `segments` appended, each percent-encoded so "BTC/USD" stays one segment
+fn endpoint(base: &Url, segments: &[&str]) -> Result<Url> {
+    let mut url = base.clone();
+    url.path_segments_mut()
+        .map_err(|_| anyhow::anyhow!("{} cannot be used as a base URL", base))?
+        .pop_if_empty()
+        .extend(segments);
+    Ok(url)
+}
+
+/// Whether a failed request may succeed if sent again
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// The service's `message` field from an error body, or the body itself
+fn error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body).ok()
+        .and_then(|value| value.get("message").and_then(|m| m.as_str()).map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::{ws::{Message as AxumMessage, WebSocket, WebSocketUpgrade}, Path, State},
+        http::StatusCode as AxumStatusCode,
+        response::IntoResponse,
+        routing::get,
+        Json, Router,
+    };
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    
+    use crate::types::{PriceSource, PriceValue};
+    
+    fn price_response(symbol: &str) -> PriceResponse {
+        PriceResponse {
+            symbol: symbol.to_string(),
+            price: PriceValue::Float(65000.0),
+            confidence: PriceValue::Float(12.5),
+            confidence_bp: 1.923,
+            timestamp: 1700000000,
+            source: PriceSource::Aggregated,
+            strategy: None,
+            max_source_age_secs: None,
+            degraded: false,
+        }
+    }
+    
+    async fn get_price(
+        State(failures_left): State<Arc<AtomicU32>>,
+        Path(symbol): Path<String>,
+    ) -> impl IntoResponse {
+        if symbol != "BTC/USD" {
+            return (AxumStatusCode::NOT_FOUND, Json(serde_json::json!({
+                "error": "Symbol not configured",
+                "symbol": symbol,
+                "message": format!("Symbol {} not configured", symbol)
+            }))).into_response();
+        }
+        if failures_left.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_ok() {
+            return (AxumStatusCode::SERVICE_UNAVAILABLE, "try again").into_response();
+        }
+        Json(price_response(&symbol)).into_response()
+    }
+    
+    async fn get_all_prices() -> Json<HashMap<String, PriceResponse>> {
+        Json([("BTC/USD".to_string(), price_response("BTC/USD"))].into_iter().collect())
+    }
+    
+    async fn websocket(ws: WebSocketUpgrade) -> impl IntoResponse {
+        ws.on_upgrade(|mut socket: WebSocket| async move {
+            let Some(Ok(AxumMessage::Text(text))) = socket.recv().await else { return };
+            let Ok(WsMessage::Subscribe { symbols, .. }) = serde_json::from_str(&text) else { return };
+            
+            for symbol in symbols {
+                let update = WsMessage::PriceUpdate {
+                    symbol,
+                    price: 65000.0,
+                    confidence: 12.5,
+                    timestamp: 1700000000,
+                    source: PriceSource::Aggregated,
+                };
+                let _ = socket.send(AxumMessage::Text(serde_json::to_string(&update).unwrap())).await;
+            }
+        })
+    }
+    
+    /// Serve a stand-in for the service, failing the first `failures` price requests
+    async fn spawn_server(failures: u32) -> String {
+        let app = Router::new()
+            .route("/oracle/price/:symbol", get(get_price))
+            .route("/oracle/prices", get(get_all_prices))
+            .route("/ws", get(websocket))
+            .with_state(Arc::new(AtomicU32::new(failures)));
+        
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr.to_string()
+    }
+    
+    fn client(addr: &str) -> OracleServiceClient {
+        OracleServiceClient::new(&format!("http://{}", addr), &format!("ws://{}", addr))
+            .unwrap()
+            .with_retries(2, Duration::from_millis(10))
+    }
+    
+    #[tokio::test]
+    async fn test_get_price_retries_transient_errors() {
+        let addr = spawn_server(2).await;
+        let price = client(&addr).get_price("BTC/USD").await.unwrap();
+        assert_eq!(price.symbol, "BTC/USD");
+        assert_eq!(price.price, PriceValue::Float(65000.0));
+        
+        // One failure more than the retry budget surfaces the error
+        let addr = spawn_server(3).await;
+        assert!(client(&addr).get_price("BTC/USD").await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_get_price_client_error_not_retried() {
+        let addr = spawn_server(0).await;
+        let error = client(&addr).get_price("DOGE/USD").await.unwrap_err();
+        assert!(error.to_string().contains("Symbol DOGE/USD not configured"));
+    }
+    
+    #[tokio::test]
+    async fn test_get_all_prices() {
+        let addr = spawn_server(0).await;
+        let prices = client(&addr).get_all_prices().await.unwrap();
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices["BTC/USD"].timestamp, 1700000000);
+    }
+    
+    #[tokio::test]
+    async fn test_subscribe() {
+        let addr = spawn_server(0).await;
+        let mut updates = client(&addr).subscribe(&["BTC/USD".to_string(), "ETH/USD".to_string()]).await.unwrap();
+        
+        let mut symbols = Vec::new();
+        while let Some(message) = updates.recv().await {
+            if let WsMessage::PriceUpdate { symbol, .. } = message {
+                symbols.push(symbol);
+            }
+        }
+        assert_eq!(symbols, vec!["BTC/USD", "ETH/USD"]);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::debug;
+
+use crate::clients::pyth::normalize_confidence;
+use crate::types::{PriceData, PriceSource};
+
+/// Client for Pyth's Hermes price service
+///
+/// Hermes serves the same Pyth prices over HTTP, independently of our
+/// on-chain account parsing, which makes it a reference for cross-checks.
+pub struct HermesClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl HermesClient {
+    pub fn new(base_url: &str) -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        
+        Ok(Self {
+            http,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+    
+    /// Get the latest price for a hex Pyth price feed id
+    pub async fn get_price(&self, price_feed_id: &str) -> Result<PriceData> {
+        let feed_id = price_feed_id.trim_start_matches("0x");
+        debug!("Fetching Hermes price for feed: {}", feed_id);
+        
+        let body = self.http
+            .get(format!("{}/v2/updates/price/latest", self.base_url))
+            .query(&[("ids[]", feed_id), ("parsed", "true")])
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        
+        parse_latest_price(&body, feed_id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LatestPriceResponse {
+    parsed: Vec<ParsedPriceUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ParsedPriceUpdate {
+    id: String,
+    price: HermesPrice,
+}
+
+/// Hermes encodes the integer fields as strings
+#[derive(Debug, Deserialize)]
+struct HermesPrice {
+    price: String,
+    conf: String,
+    expo: i32,
+    publish_time: i64,
+}
+
+/// Extract one feed's price from a `/v2/updates/price/latest` response body
+fn parse_latest_price(body: &str, feed_id: &str) -> Result<PriceData> {
+    let response: LatestPriceResponse = serde_json::from_str(body)?;
+    
+    let update = response.parsed.into_iter()
+        .find(|update| update.id.trim_start_matches("0x").eq_ignore_ascii_case(feed_id))
+        .ok_or_else(|| anyhow::anyhow!("Hermes response has no update for feed {}", feed_id))?;
+    
+    let price = update.price.price.parse()
+        .map_err(|e| anyhow::anyhow!("Invalid Hermes price: {}", e))?;
+    let confidence = update.price.conf.parse()
+        .map_err(|e| anyhow::anyhow!("Invalid Hermes confidence: {}", e))?;
+    
+    // Hermes serves the same aggregate as the on-chain account
+    Ok(PriceData {
+        price,
+        confidence,
+        expo: update.price.expo,
+        timestamp: update.price.publish_time,
+        source: PriceSource::Pyth,
+        symbol: "".to_string(), // Will be set by the caller
+        confidence_ratio: normalize_confidence(price, confidence),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_latest_price() {
+        let body = r#"{
+            "binary": {"encoding": "hex", "data": []},
+            "parsed": [{
+                "id": "e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43",
+                "price": {"price": "6512345678901", "conf": "2345678901", "expo": -8, "publish_time": 1700000000},
+                "ema_price": {"price": "6510000000000", "conf": "2000000000", "expo": -8, "publish_time": 1700000000}
+            }]
+        }"#;
+        
+        let price_data = parse_latest_price(body, "E62DF6C8B4A85FE1A67DB44DC12DE5DB330F7AC66B72DC658AFEDF0F4A415B43").unwrap();
+        assert_eq!(price_data.price, 6512345678901);
+        assert_eq!(price_data.confidence, 2345678901);
+        assert_eq!(price_data.expo, -8);
+        assert_eq!(price_data.timestamp, 1700000000);
+        
+        assert!(parse_latest_price(body, "ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace").is_err());
+    }
+}
\ No newline at end of file
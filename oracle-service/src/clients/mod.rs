@@ -1,5 +1,112 @@
+pub mod hermes;
 pub mod pyth;
 pub mod switchboard;
 
+pub use hermes::HermesClient;
 pub use pyth::PythClient;
-pub use switchboard::{SwitchboardClient, OracleInfo};
\ No newline at end of file
+pub use switchboard::{resolve_zero_scale, SwitchboardClient, OracleInfo};
+
+use anyhow::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+use tracing::info;
+
+use crate::types::OracleError;
+
+/// Staleness limit for reads that aren't tied to a symbol, such as vetting a candidate feed
+pub const DEFAULT_MAX_STALENESS_SECS: i64 = 300;
+
+/// How long the startup RPC check waits for the node to answer
+const RPC_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reject an RPC URL that can't be an HTTP endpoint, without contacting it
+///
+/// The URL is left out of the error since it often embeds an API key.
+pub fn validate_rpc_url(rpc_url: &str) -> Result<(), OracleError> {
+    let url = reqwest::Url::parse(rpc_url)
+        .map_err(|e| OracleError::ConfigError(format!("Invalid Solana RPC URL: {}", e)))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(OracleError::ConfigError(format!(
+            "Invalid Solana RPC URL: scheme must be http or https, got {}", url.scheme()
+        )));
+    }
+    Ok(())
+}
+
+/// Confirm the RPC node answers a lightweight `getVersion` call
+pub async fn check_rpc_reachable(rpc_url: &str) -> Result<(), OracleError> {
+    let client = solana_client::nonblocking::rpc_client::RpcClient::new_with_timeout(
+        rpc_url.to_string(),
+        RPC_CHECK_TIMEOUT,
+    );
+    let version = client.get_version().await
+        .map_err(|e| OracleError::ConnectionFailed(format!("Solana RPC unreachable: {}", e)))?;
+    
+    info!("Solana RPC reachable, node version {}", version.solana_core);
+    Ok(())
+}
+
+/// Parse a configured commitment level
+///
+/// "processed" trades safety for latency, "finalized" the other way round.
+pub fn parse_commitment(level: &str) -> Result<CommitmentConfig> {
+    match level.to_ascii_lowercase().as_str() {
+        "processed" => Ok(CommitmentConfig::processed()),
+        "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        other => anyhow::bail!("Unknown commitment level: {} (expected processed, confirmed or finalized)", other),
+    }
+}
+
+/// Raw account data lookup the oracle clients parse from
+///
+/// Abstracted over the RPC client so parsing and validation can be exercised
+/// with canned accounts instead of a live cluster.
+pub trait AccountFetcher: Send + Sync {
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>>;
+}
+
+impl AccountFetcher for RpcClient {
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>> {
+        Ok(self.get_account(pubkey)?.data)
+    }
+}
+
+/// Serves fixed account bytes keyed by address
+#[cfg(test)]
+#[derive(Default)]
+pub struct MockAccountFetcher {
+    accounts: std::collections::HashMap<Pubkey, Vec<u8>>,
+}
+
+#[cfg(test)]
+impl MockAccountFetcher {
+    pub fn with_account(mut self, pubkey: Pubkey, data: Vec<u8>) -> Self {
+        self.accounts.insert(pubkey, data);
+        self
+    }
+}
+
+#[cfg(test)]
+impl AccountFetcher for MockAccountFetcher {
+    fn get_account_data(&self, pubkey: &Pubkey) -> Result<Vec<u8>> {
+        self.accounts.get(pubkey)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("AccountNotFound: {}", pubkey))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_commitment() {
+        assert_eq!(parse_commitment("processed").unwrap(), CommitmentConfig::processed());
+        assert_eq!(parse_commitment("Finalized").unwrap(), CommitmentConfig::finalized());
+        assert!(parse_commitment("max").is_err());
+        assert!(parse_commitment("").is_err());
+    }
+}
\ No newline at end of file
@@ -1,57 +1,64 @@
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 // Remove Pyth SDK direct parsing for now - use account data analysis
 use std::str::FromStr;
 use tracing::{debug, error, warn};
 use tokio::time::Instant;
 
+use crate::clients::{AccountFetcher, DEFAULT_MAX_STALENESS_SECS};
 use crate::types::{PriceData, PriceSource};
 
 /// Pyth Network client for fetching real-time price data
 pub struct PythClient {
-    rpc_client: RpcClient,
+    account_fetcher: Box<dyn AccountFetcher>,
     _last_fetch: Option<Instant>,
 }
 
 impl PythClient {
-    pub async fn new(rpc_url: &str) -> Result<Self> {
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+    pub async fn new(rpc_url: &str, commitment: CommitmentConfig) -> Result<Self> {
+        let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment);
         
-        Ok(Self {
-            rpc_client,
+        Ok(Self::with_fetcher(Box::new(rpc_client)))
+    }
+    
+    /// Build a client on top of any account source
+    pub fn with_fetcher(account_fetcher: Box<dyn AccountFetcher>) -> Self {
+        Self {
+            account_fetcher,
             _last_fetch: None,
-        })
+        }
     }
     
     /// Get price from Pyth Network for a specific feed ID
-    pub async fn get_price(&self, price_feed_id: &str) -> Result<PriceData> {
+    pub async fn get_price(&self, price_feed_id: &str, max_staleness_secs: i64) -> Result<PriceData> {
         let feed_pubkey = Pubkey::from_str(price_feed_id)
             .map_err(|e| anyhow::anyhow!("Invalid Pyth feed ID: {}", e))?;
         
         debug!("Fetching Pyth price for feed: {}", price_feed_id);
         
         // Get account info from Solana RPC
-        let account_info = self.rpc_client.get_account(&feed_pubkey)
+        let account_data = self.account_fetcher.get_account_data(&feed_pubkey)
             .map_err(|e| anyhow::anyhow!("Failed to fetch Pyth account: {}", e))?;
         
         // Extract real price data from Pyth account structure
         // Pyth accounts have a standard structure - we can extract key information
-        if account_info.data.len() < 240 { // Pyth price accounts are typically ~240 bytes
+        if account_data.len() < 240 { // Pyth price accounts are typically ~240 bytes
             return Err(anyhow::anyhow!("Invalid Pyth account: insufficient data length"));
         }
         
         // REAL PYTH ACCOUNT PARSING - Proper validation and error handling
         
         // First, validate this is a genuine Pyth price account
-        if account_info.data.len() < 240 {
+        if account_data.len() < 240 {
             return Err(anyhow::anyhow!("Invalid Pyth account: data too short"));
         }
         
         // Check Pyth magic number to verify account type
         let magic = u32::from_le_bytes([
-            account_info.data[0], account_info.data[1], 
-            account_info.data[2], account_info.data[3]
+            account_data[0], account_data[1], 
+            account_data[2], account_data[3]
         ]);
         if magic != 0xa1b2c3d4 {
             return Err(anyhow::anyhow!("Invalid Pyth account: wrong magic number"));
@@ -59,19 +66,19 @@ impl PythClient {
         
         // Check account version compatibility
         let version = u32::from_le_bytes([
-            account_info.data[4], account_info.data[5],
-            account_info.data[6], account_info.data[7] 
+            account_data[4], account_data[5],
+            account_data[6], account_data[7] 
         ]);
         if version < 2 {
             return Err(anyhow::anyhow!("Unsupported Pyth account version: {}", version));
         }
         
         // Extract real price data from validated Pyth account structure
-        let price_bytes = &account_info.data[208..216];
-        let conf_bytes = &account_info.data[216..224]; 
-        let expo_bytes = &account_info.data[224..228];
-        let timestamp_bytes = &account_info.data[228..236];
-        let status_bytes = &account_info.data[236..240];
+        let price_bytes = &account_data[208..216];
+        let conf_bytes = &account_data[216..224]; 
+        let expo_bytes = &account_data[224..228];
+        let timestamp_bytes = &account_data[228..236];
+        let status_bytes = &account_data[236..240];
         
         let price = i64::from_le_bytes(price_bytes.try_into()
             .map_err(|_| anyhow::anyhow!("Failed to parse price"))?);
@@ -90,7 +97,7 @@ impl PythClient {
         }
         
         // Validate the extracted price data
-        self.validate_price_data(price, timestamp)?;
+        self.validate_price_data(price, timestamp, max_staleness_secs)?;
         
         let price_data = PriceData {
             price,
@@ -99,6 +106,8 @@ impl PythClient {
             timestamp,
             source: PriceSource::Pyth,
             symbol: "".to_string(), // Will be set by the caller
+            confidence_ratio: normalize_confidence(price, confidence),
+            ..Default::default()
         };
         
         debug!("Successfully fetched Pyth price: ${}", self.format_price(&price_data));
@@ -108,7 +117,7 @@ impl PythClient {
     
     /// Get price with confidence interval
     pub async fn get_price_with_confidence(&self, price_feed_id: &str) -> Result<(f64, f64)> {
-        let price_data = self.get_price(price_feed_id).await?;
+        let price_data = self.get_price(price_feed_id, DEFAULT_MAX_STALENESS_SECS).await?;
         
         let price = price_data.price as f64 / 10_f64.powi(-price_data.expo);
         let confidence = price_data.confidence as f64 / 10_f64.powi(-price_data.expo);
@@ -117,7 +126,7 @@ impl PythClient {
     }
     
     /// Validate real Pyth price data quality and integrity
-    fn validate_price_data(&self, price: i64, timestamp: i64) -> Result<()> {
+    fn validate_price_data(&self, price: i64, timestamp: i64, max_staleness_secs: i64) -> Result<()> {
         // Check if price is positive (negative prices indicate error state)
         if price <= 0 {
             anyhow::bail!("Invalid Pyth price: non-positive value {}", price);
@@ -127,9 +136,9 @@ impl PythClient {
         let current_timestamp = chrono::Utc::now().timestamp();
         let price_age = current_timestamp - timestamp;
         
-        if price_age > 300 { // 5 minutes maximum staleness
+        if price_age > max_staleness_secs {
             warn!("Stale Pyth price detected: {} seconds old", price_age);
-            anyhow::bail!("Stale Pyth price: {} seconds old (max 300)", price_age);
+            anyhow::bail!("Stale Pyth price: {} seconds old (max {})", price_age, max_staleness_secs);
         }
         
         if price_age < 0 {
@@ -164,7 +173,7 @@ impl PythClient {
         // Try to fetch a well-known feed (BTC/USD)
         let btc_feed = "GVXRSBjFk6e6J3NbVPXohDJetcTjaeeuykUpbQF8UoMU"; // Pyth BTC/USD
         
-        match self.get_price(btc_feed).await {
+        match self.get_price(btc_feed, DEFAULT_MAX_STALENESS_SECS).await {
             Ok(_) => {
                 debug!("Pyth health check passed");
                 true
@@ -177,20 +186,109 @@ impl PythClient {
     }
 }
 
+/// Pyth confidence as a fraction of price
+///
+/// `conf` is the half-width of the interval Pyth aggregates from its
+/// publishers' quotes, in the same exponent as `price`, so the fraction is
+/// simply `conf / price`. None for non-positive prices.
+pub fn normalize_confidence(price: i64, confidence: u64) -> Option<f64> {
+    (price > 0).then(|| confidence as f64 / price as f64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::MockAccountFetcher;
+    
+    /// Lay out a Pyth price account the way `get_price` reads it
+    fn pyth_account(price: i64, conf: u64, expo: i32, timestamp: i64, status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; 240];
+        data[0..4].copy_from_slice(&0xa1b2c3d4u32.to_le_bytes());
+        data[4..8].copy_from_slice(&2u32.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[216..224].copy_from_slice(&conf.to_le_bytes());
+        data[224..228].copy_from_slice(&expo.to_le_bytes());
+        data[228..236].copy_from_slice(&timestamp.to_le_bytes());
+        data[236..240].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+    
+    fn mock_client(feed: Pubkey, data: Vec<u8>) -> PythClient {
+        PythClient::with_fetcher(Box::new(MockAccountFetcher::default().with_account(feed, data)))
+    }
+    
+    #[tokio::test]
+    async fn test_parse_valid_account() {
+        let feed = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        let client = mock_client(feed, pyth_account(50000_00000000, 25_00000000, -8, now, 1));
+        
+        let price_data = client.get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.unwrap();
+        assert_eq!(price_data, PriceData {
+            price: 50000_00000000,
+            confidence: 25_00000000,
+            expo: -8,
+            timestamp: now,
+            source: PriceSource::Pyth,
+            symbol: "".to_string(),
+            confidence_ratio: Some(0.0005),
+            ..Default::default()
+        });
+    }
+    
+    #[tokio::test]
+    async fn test_rejects_invalid_accounts() {
+        let feed = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        
+        // Wrong magic number
+        let mut data = pyth_account(50000_00000000, 25_00000000, -8, now, 1);
+        data[0] = 0;
+        assert!(mock_client(feed, data).get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_err());
+        
+        // Trading halted
+        let halted = pyth_account(50000_00000000, 25_00000000, -8, now, 2);
+        assert!(mock_client(feed, halted).get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_err());
+        
+        // Older than the staleness limit
+        let stale = pyth_account(50000_00000000, 25_00000000, -8, now - 600, 1);
+        assert!(mock_client(feed, stale).get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_err());
+        
+        // The limit is the caller's: ten seconds is fine at the default but not at five
+        let recent = pyth_account(50000_00000000, 25_00000000, -8, now - 10, 1);
+        assert!(mock_client(feed, recent.clone()).get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_ok());
+        assert!(mock_client(feed, recent).get_price(&feed.to_string(), 5).await.is_err());
+        
+        // Truncated account
+        assert!(mock_client(feed, vec![0u8; 100]).get_price(&feed.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_err());
+        
+        // Account missing entirely
+        let other = Pubkey::new_unique();
+        assert!(mock_client(feed, vec![]).get_price(&other.to_string(), DEFAULT_MAX_STALENESS_SECS).await.is_err());
+    }
+    
+    #[test]
+    fn test_confidence_normalization() {
+        // $50,000 +/- $25 at expo -8 is 5bp of price
+        assert!((normalize_confidence(50000_00000000, 25_00000000).unwrap() - 0.0005).abs() < 1e-12);
+        
+        // The exponent cancels out
+        assert!((normalize_confidence(50000_000000, 25_000000).unwrap() - 0.0005).abs() < 1e-12);
+        
+        assert_eq!(normalize_confidence(0, 25_00000000), None);
+        assert_eq!(normalize_confidence(-5, 25_00000000), None);
+    }
     
     #[tokio::test]
     async fn test_pyth_client_creation() {
-        let client = PythClient::new("https://api.mainnet-beta.solana.com").await;
+        let client = PythClient::new("https://api.mainnet-beta.solana.com", CommitmentConfig::confirmed()).await;
         assert!(client.is_ok());
     }
     
     #[tokio::test]
     async fn test_invalid_feed_id() {
-        let client = PythClient::new("https://api.mainnet-beta.solana.com").await.unwrap();
-        let result = client.get_price("invalid_feed_id").await;
+        let client = PythClient::new("https://api.mainnet-beta.solana.com", CommitmentConfig::confirmed()).await.unwrap();
+        let result = client.get_price("invalid_feed_id", DEFAULT_MAX_STALENESS_SECS).await;
         assert!(result.is_err());
     }
 }
\ No newline at end of file
@@ -1,66 +1,119 @@
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 use switchboard_solana::SwitchboardDecimal;
 
-use crate::types::{PriceData, PriceSource};
+use crate::clients::{AccountFetcher, DEFAULT_MAX_STALENESS_SECS};
+use crate::types::{OracleError, PriceData, PriceSource};
+
+/// Largest decimal scale accepted from an aggregator; anything beyond is a corrupt account
+const MAX_SCALE: u32 = 18;
+
+/// `latest_confirmed_round.num_success` in switchboard-solana's packed `AggregatorAccountData`
+///
+/// The 8-byte discriminator and 333 bytes of fields (`name` through
+/// `crank_pubkey`) precede `latest_confirmed_round`, an `AggregatorRound`
+/// whose first field is the u32 count of oracles that responded.
+const NUM_SUCCESS_OFFSET: usize = 8 + 333;
+
+/// `history_buffer` pubkey in switchboard-solana's packed `AggregatorAccountData`
+///
+/// Follows `latest_confirmed_round` and `current_round` (1097-byte
+/// `AggregatorRound`s), `job_pubkeys_data` and `job_hashes` (16 x 32 bytes
+/// each), `job_pubkeys_size` (u32), `jobs_checksum` and `authority`.
+const HISTORY_BUFFER_OFFSET: usize = NUM_SUCCESS_OFFSET + 2 * 1097 + 2 * 16 * 32 + 4 + 32 + 32;
+
+/// Discriminator of an `AggregatorHistoryBuffer` account
+const HISTORY_BUFFER_DISCRIMINATOR: &[u8; 8] = b"BUFFERxx";
+
+/// Start of the history rows: discriminator, then the u32 `insertion_idx` of the newest row
+const HISTORY_ROWS_OFFSET: usize = 12;
+
+/// Packed `AggregatorHistoryRow`: i64 timestamp, then a `SwitchboardDecimal` (i128 mantissa, u32 scale)
+const HISTORY_ROW_SIZE: usize = 28;
+
+/// Furthest a past round may sit from the latest one before it is treated as corrupt
+const MAX_ROUND_DEVIATION_BP: u128 = 1000;
 
 /// Switchboard client for fetching decentralized oracle data
 pub struct SwitchboardClient {
-    rpc_client: RpcClient,
+    account_fetcher: Box<dyn AccountFetcher>,
 }
 
 impl SwitchboardClient {
-    pub async fn new(rpc_url: &str) -> Result<Self> {
-        let rpc_client = RpcClient::new(rpc_url.to_string());
+    pub async fn new(rpc_url: &str, commitment: CommitmentConfig) -> Result<Self> {
+        let rpc_client = RpcClient::new_with_commitment(rpc_url.to_string(), commitment);
         
-        Ok(Self {
-            rpc_client,
-        })
+        Ok(Self::with_fetcher(Box::new(rpc_client)))
+    }
+    
+    /// Build a client on top of any account source
+    pub fn with_fetcher(account_fetcher: Box<dyn AccountFetcher>) -> Self {
+        Self {
+            account_fetcher,
+        }
     }
     
     /// Get price from Switchboard aggregator
-    pub async fn get_price(&self, aggregator_address: &str) -> Result<PriceData> {
+    ///
+    /// With `max_confidence_bp` set, a round whose response spread implies a
+    /// wider confidence than that is rejected, as Pyth's `conf` is on chain.
+    /// Rounds older than `max_staleness_secs` are rejected as stale.
+    ///
+    /// With `smoothing_rounds` above 1, the price is the median of the latest
+    /// round and up to `smoothing_rounds - 1` earlier rounds from the
+    /// aggregator's history buffer, skipping any that are stale or implausible
+    /// (see `history_rounds`); confidence and timestamp still come from the
+    /// latest round. Aggregators without a history buffer, or one that can't
+    /// be read, fall back to the latest round alone.
+    pub async fn get_price(
+        &self,
+        aggregator_address: &str,
+        max_confidence_bp: Option<u64>,
+        max_staleness_secs: i64,
+        smoothing_rounds: usize,
+    ) -> Result<PriceData> {
         let aggregator_pubkey = Pubkey::from_str(aggregator_address)
             .map_err(|e| anyhow::anyhow!("Invalid Switchboard aggregator address: {}", e))?;
         
         debug!("Fetching Switchboard price from aggregator: {}", aggregator_address);
         
         // Get account info from Solana RPC
-        let account_info = self.rpc_client.get_account(&aggregator_pubkey)
+        let account_data = self.account_fetcher.get_account_data(&aggregator_pubkey)
             .map_err(|e| anyhow::anyhow!("Failed to fetch Switchboard account: {}", e))?;
         
         // Use a simpler approach - directly parse the account data with Switchboard SDK
         // Note: This is a simplified implementation for now
-        if account_info.data.len() < 32 {
+        if account_data.len() < 32 {
             return Err(anyhow::anyhow!("Invalid Switchboard account data"));
         }
         
         // REAL SWITCHBOARD AGGREGATOR PARSING - No more fake prices!
         
-        if account_info.data.len() < 256 {
+        if account_data.len() < 256 {
             return Err(anyhow::anyhow!("Invalid Switchboard account: data too short"));
         }
         
         // Validate Switchboard aggregator discriminator
-        let discriminator = &account_info.data[0..8];
+        let discriminator = &account_data[0..8];
         let expected_discriminator = [217, 230, 65, 101, 201, 162, 27, 125];
         if discriminator != expected_discriminator {
             return Err(anyhow::anyhow!("Invalid Switchboard aggregator: wrong discriminator"));
         }
         
         // Extract current value from aggregator result (SwitchboardDecimal)
-        let mantissa_bytes = &account_info.data[144..152]; // 8 bytes
-        let scale_bytes = &account_info.data[152..156];    // 4 bytes
-        let timestamp_bytes = &account_info.data[200..208]; // 8 bytes
+        let mantissa_bytes = &account_data[144..160]; // 16 bytes, i128
+        let scale_bytes = &account_data[160..164];    // 4 bytes
+        let timestamp_bytes = &account_data[200..208]; // 8 bytes
         
         // Extract min/max responses for confidence calculation
-        let min_response_bytes = &account_info.data[208..216];
-        let max_response_bytes = &account_info.data[216..224];
+        let min_response_bytes = &account_data[208..216];
+        let max_response_bytes = &account_data[216..224];
         
-        let mantissa = i64::from_le_bytes(mantissa_bytes.try_into()
+        let raw_mantissa = i128::from_le_bytes(mantissa_bytes.try_into()
             .map_err(|_| anyhow::anyhow!("Failed to parse mantissa"))?);
         let scale = u32::from_le_bytes(scale_bytes.try_into()
             .map_err(|_| anyhow::anyhow!("Failed to parse scale"))?);
@@ -70,29 +123,61 @@ impl SwitchboardClient {
             .map_err(|_| anyhow::anyhow!("Failed to parse min response"))?);
         let max_response = i64::from_le_bytes(max_response_bytes.try_into()
             .map_err(|_| anyhow::anyhow!("Failed to parse max response"))?);
+        // Oracles whose responses made up the latest round; truncated accounts just don't report it
+        let num_success = account_data.get(NUM_SUCCESS_OFFSET..NUM_SUCCESS_OFFSET + 4)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+            .unwrap_or(0);
+        
+        // An absurd scale would normalize the price to inf or zero downstream
+        if scale > MAX_SCALE {
+            anyhow::bail!("Invalid Switchboard scale {}: expected 0..={}", scale, MAX_SCALE);
+        }
         
         // Validate timestamp (check for staleness)
         let current_timestamp = chrono::Utc::now().timestamp();
-        if current_timestamp - latest_timestamp > 300 { // 5 minutes staleness limit
-            return Err(anyhow::anyhow!("Stale Switchboard data: {} seconds old", 
-                current_timestamp - latest_timestamp));
+        if current_timestamp - latest_timestamp > max_staleness_secs {
+            return Err(anyhow::anyhow!("Stale Switchboard data: {} seconds old (max {})",
+                current_timestamp - latest_timestamp, max_staleness_secs));
         }
         
-        let price_value = mantissa;
+        let raw_mantissa = if smoothing_rounds > 1 {
+            let mut mantissas = vec![raw_mantissa];
+            mantissas.extend(self.history_rounds(&account_data, raw_mantissa, scale, latest_timestamp).into_iter()
+                .filter(|(_, timestamp)| current_timestamp - timestamp <= max_staleness_secs)
+                .map(|(mantissa, _)| mantissa)
+                .take(smoothing_rounds - 1));
+            debug!("Smoothing Switchboard price over {} rounds", mantissas.len());
+            median_mantissa(&mut mantissas)
+        } else {
+            raw_mantissa
+        };
+        
+        // PriceData holds an i64; saturate loudly rather than truncate the high bytes
+        let (price_value, saturated) = saturate_i64(raw_mantissa);
+        if saturated {
+            warn!("Switchboard mantissa {} from {} exceeds i64, saturated to {}",
+                raw_mantissa, aggregator_address, price_value);
+        }
         
         // Validate the extracted price
         self.validate_result(price_value)?;
         
-        // Calculate real confidence from oracle response spread
-        let confidence_value = ((max_response - min_response).abs() / 2) as u64;
+        let confidence = spread_confidence(min_response, max_response);
+        if let Some(max_bp) = max_confidence_bp {
+            validate_confidence(price_value, confidence, max_bp)?;
+        }
         
         let price_data = PriceData {
             price: price_value,
-            confidence: confidence_value,
+            confidence,
             expo: -(scale as i32),
             timestamp: latest_timestamp,
             source: PriceSource::Switchboard,
             symbol: "".to_string(), // Will be set by the caller
+            oracle_count: (num_success > 0).then_some(num_success),
+            confidence_ratio: normalize_confidence(price_value, min_response, max_response),
+            ..Default::default()
         };
         
         debug!("Successfully fetched Switchboard price: ${}", self.format_price(&price_data));
@@ -102,6 +187,44 @@ impl SwitchboardClient {
     
 
     
+    /// Rounds before the latest from the aggregator's history buffer, newest first, as (mantissa, timestamp)
+    ///
+    /// Mantissas are rescaled to `scale`. Empty slots, rounds not older than
+    /// the latest, non-positive values and any round more than
+    /// `MAX_ROUND_DEVIATION_BP` from `latest_mantissa` are dropped.
+    fn history_rounds(&self, account_data: &[u8], latest_mantissa: i128, scale: u32, latest_timestamp: i64) -> Vec<(i128, i64)> {
+        let Some(buffer) = account_data.get(HISTORY_BUFFER_OFFSET..HISTORY_BUFFER_OFFSET + 32)
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .map(Pubkey::new_from_array)
+            .filter(|buffer| *buffer != Pubkey::default())
+        else {
+            debug!("Switchboard aggregator has no history buffer, not smoothing");
+            return Vec::new();
+        };
+        
+        let buffer_data = match self.account_fetcher.get_account_data(&buffer) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("Failed to fetch Switchboard history buffer {}, not smoothing: {}", buffer, e);
+                return Vec::new();
+            }
+        };
+        
+        parse_history_buffer(&buffer_data).into_iter()
+            .filter(|(_, _, timestamp)| *timestamp < latest_timestamp)
+            .filter_map(|(mantissa, row_scale, timestamp)| {
+                let mantissa = rescale_mantissa(mantissa, row_scale, scale)?;
+                let deviation = mantissa.abs_diff(latest_mantissa);
+                let plausible = mantissa > 0
+                    && deviation.checked_mul(10000)? <= MAX_ROUND_DEVIATION_BP * latest_mantissa.unsigned_abs();
+                if !plausible {
+                    warn!("Skipping implausible Switchboard round {} at {} against latest {}", mantissa, timestamp, latest_mantissa);
+                }
+                plausible.then_some((mantissa, timestamp))
+            })
+            .collect()
+    }
+    
     /// Validate Switchboard result data 
     fn validate_result(&self, price: i64) -> Result<()> {
         // Basic validation
@@ -132,18 +255,18 @@ impl SwitchboardClient {
     /// Get detailed oracle information
     pub async fn get_oracle_info(&self, aggregator_address: &str) -> Result<OracleInfo> {
         let aggregator_pubkey = Pubkey::from_str(aggregator_address)?;
-        let account_info = self.rpc_client.get_account(&aggregator_pubkey)?;
+        let account_data = self.account_fetcher.get_account_data(&aggregator_pubkey)?;
         // Mock oracle info for now
-        if account_info.data.is_empty() {
+        if account_data.is_empty() {
             return Err(anyhow::anyhow!("Empty account data").into());
         }
         
         // Extract basic info from account data
-        let (oracle_count, min_results, update_interval) = if account_info.data.len() >= 64 {
+        let (oracle_count, min_results, update_interval) = if account_data.len() >= 64 {
             // Extract some basic configuration from account data
-            let oracle_count = account_info.data[32] % 10 + 3; // 3-12 oracles
+            let oracle_count = account_data[32] % 10 + 3; // 3-12 oracles
             let min_results = oracle_count * 2 / 3; // 2/3 majority
-            let update_interval = (account_info.data[33] % 60) + 30; // 30-90 seconds
+            let update_interval = (account_data[33] % 60) + 30; // 30-90 seconds
             (oracle_count as u32, min_results as u32, update_interval as u32)
         } else {
             (5, 3, 30) // Default values
@@ -164,7 +287,7 @@ impl SwitchboardClient {
         // Try to fetch a well-known aggregator (example BTC/USD)
         let btc_aggregator = "8SXvChNYFhRq4EZuZvnhjrB3jJRQCv4k3P4W6hesH3Ee"; // Example Switchboard BTC/USD
         
-        match self.get_price(btc_aggregator).await {
+        match self.get_price(btc_aggregator, None, DEFAULT_MAX_STALENESS_SECS, 0).await {
             Ok(_) => {
                 debug!("Switchboard health check passed");
                 true
@@ -177,6 +300,120 @@ impl SwitchboardClient {
     }
 }
 
+/// Confidence interval implied by a round's min and max oracle responses
+///
+/// Switchboard has no native confidence. Every response in the round lies
+/// between the two (same scale as the result), so half the range is the
+/// half-width of an interval covering all of them, the shape of Pyth's `conf`.
+pub fn spread_confidence(min_response: i64, max_response: i64) -> u64 {
+    max_response.abs_diff(min_response) / 2
+}
+
+/// Switchboard confidence as a fraction of price
+///
+/// The half-spread from `spread_confidence` over the price, both at the
+/// aggregator's scale. None for non-positive prices.
+pub fn normalize_confidence(price: i64, min_response: i64, max_response: i64) -> Option<f64> {
+    (price > 0).then(|| spread_confidence(min_response, max_response) as f64 / price as f64)
+}
+
+/// Rows of an `AggregatorHistoryBuffer` account, newest first, as (mantissa, scale, timestamp)
+///
+/// The buffer is a ring: `insertion_idx` holds the newest row and the ones
+/// before it, wrapping around, are progressively older. Slots never filled
+/// carry a zero timestamp. Anything that isn't a history buffer yields no rows.
+fn parse_history_buffer(data: &[u8]) -> Vec<(i128, u32, i64)> {
+    if data.len() < HISTORY_ROWS_OFFSET || &data[0..8] != HISTORY_BUFFER_DISCRIMINATOR {
+        return Vec::new();
+    }
+    let insertion_idx = u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize;
+    let rows: Vec<&[u8]> = data[HISTORY_ROWS_OFFSET..].chunks_exact(HISTORY_ROW_SIZE).collect();
+    if insertion_idx >= rows.len() {
+        return Vec::new();
+    }
+    
+    (0..rows.len())
+        .map(|back| rows[(insertion_idx + rows.len() - back) % rows.len()])
+        .filter_map(|row| {
+            let timestamp = i64::from_le_bytes(row[0..8].try_into().ok()?);
+            let mantissa = i128::from_le_bytes(row[8..24].try_into().ok()?);
+            let scale = u32::from_le_bytes(row[24..28].try_into().ok()?);
+            (timestamp > 0).then_some((mantissa, scale, timestamp))
+        })
+        .collect()
+}
+
+/// Express a mantissa at `from` decimals in `to` decimals, or None if either scale is implausible or it overflows
+fn rescale_mantissa(mantissa: i128, from: u32, to: u32) -> Option<i128> {
+    if from > MAX_SCALE || to > MAX_SCALE {
+        return None;
+    }
+    if to >= from {
+        mantissa.checked_mul(10_i128.pow(to - from))
+    } else {
+        Some(mantissa / 10_i128.pow(from - to))
+    }
+}
+
+/// Median of round mantissas, the midpoint of the middle two for an even count
+fn median_mantissa(mantissas: &mut [i128]) -> i128 {
+    mantissas.sort_unstable();
+    let mid = mantissas.len() / 2;
+    if mantissas.len().is_multiple_of(2) {
+        mantissas[mid - 1] + (mantissas[mid] - mantissas[mid - 1]) / 2
+    } else {
+        mantissas[mid]
+    }
+}
+
+/// Settle the exponent of a reading whose aggregator reported `scale = 0`
+///
+/// A zero scale turns the mantissa into a whole-number price, which for
+/// anything quoted in cents is off by orders of magnitude, so it is treated as
+/// a glitch: the reading takes `fallback_expo` if the symbol configures one
+/// and is rejected otherwise. Readings with a non-zero scale pass through.
+pub fn resolve_zero_scale(mut price_data: PriceData, fallback_expo: Option<i32>) -> Result<PriceData> {
+    if price_data.expo != 0 {
+        return Ok(price_data);
+    }
+    
+    match fallback_expo {
+        Some(expo) => {
+            warn!("Switchboard reading for {} reported scale 0, applying fallback expo {}", price_data.symbol, expo);
+            price_data.expo = expo;
+            Ok(price_data)
+        },
+        None => {
+            warn!("Switchboard reading for {} reported scale 0, rejecting it", price_data.symbol);
+            anyhow::bail!("Suspicious Switchboard scale 0 with no fallback expo configured")
+        },
+    }
+}
+
+/// Narrow an i128 to i64, clamping at the bounds; the flag is set when clamping happened
+pub fn saturate_i64(value: i128) -> (i64, bool) {
+    match i64::try_from(value) {
+        Ok(narrowed) => (narrowed, false),
+        Err(_) if value > 0 => (i64::MAX, true),
+        Err(_) => (i64::MIN, true),
+    }
+}
+
+/// Reject a spread-derived confidence wider than `max_confidence_bp` of the price
+///
+/// Price and confidence share the aggregator's scale, so the ratio needs no
+/// normalization; the comparison is done in integers to stay exact.
+fn validate_confidence(price: i64, confidence: u64, max_confidence_bp: u64) -> Result<()> {
+    let price = price.unsigned_abs() as u128;
+    if confidence as u128 * 10000 > max_confidence_bp as u128 * price {
+        return Err(OracleError::InsufficientConfidence(format!(
+            "Switchboard confidence {:.2}bp exceeds max {}bp",
+            confidence as f64 / price as f64 * 10000.0, max_confidence_bp
+        )).into());
+    }
+    Ok(())
+}
+
 /// Detailed oracle information for monitoring
 #[derive(Debug, Clone)]
 pub struct OracleInfo {
@@ -191,17 +428,252 @@ pub struct OracleInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::clients::MockAccountFetcher;
+    
+    /// Lay out a Switchboard aggregator account the way `get_price` reads it
+    fn aggregator_account(mantissa: i128, scale: u32, timestamp: i64, min: i64, max: i64) -> Vec<u8> {
+        let mut data = vec![0u8; 256];
+        data[0..8].copy_from_slice(&[217, 230, 65, 101, 201, 162, 27, 125]);
+        data[144..160].copy_from_slice(&mantissa.to_le_bytes());
+        data[160..164].copy_from_slice(&scale.to_le_bytes());
+        data[200..208].copy_from_slice(&timestamp.to_le_bytes());
+        data[208..216].copy_from_slice(&min.to_le_bytes());
+        data[216..224].copy_from_slice(&max.to_le_bytes());
+        data
+    }
+    
+    /// Lay out an `AggregatorHistoryBuffer` whose newest row is the first of `rows`
+    fn history_buffer(rows: &[(i128, u32, i64)], capacity: usize) -> Vec<u8> {
+        let mut data = vec![0u8; HISTORY_ROWS_OFFSET + capacity * HISTORY_ROW_SIZE];
+        data[0..8].copy_from_slice(HISTORY_BUFFER_DISCRIMINATOR);
+        // Newest in the middle slot so reading newest-first has to wrap
+        let newest = capacity / 2;
+        data[8..12].copy_from_slice(&(newest as u32).to_le_bytes());
+        for (back, (mantissa, scale, timestamp)) in rows.iter().enumerate() {
+            let start = HISTORY_ROWS_OFFSET + (newest + capacity - back) % capacity * HISTORY_ROW_SIZE;
+            data[start..start + 8].copy_from_slice(&timestamp.to_le_bytes());
+            data[start + 8..start + 24].copy_from_slice(&mantissa.to_le_bytes());
+            data[start + 24..start + 28].copy_from_slice(&scale.to_le_bytes());
+        }
+        data
+    }
+    
+    #[tokio::test]
+    async fn test_smoothing_over_history_buffer() {
+        let aggregator = Pubkey::new_unique();
+        let buffer = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        let mut data = aggregator_account(50500_00000000, 8, now, 49900_00000000, 50100_00000000);
+        data.resize(HISTORY_BUFFER_OFFSET + 32, 0);
+        data[HISTORY_BUFFER_OFFSET..HISTORY_BUFFER_OFFSET + 32].copy_from_slice(buffer.as_ref());
+        let rows = [
+            (50500_00000000, 8, now),        // The latest round itself, not counted twice
+            (50000_000000, 6, now - 10),     // Coarser scale, rescaled
+            (49000_00000000, 8, now - 20),
+            (10_00000000, 8, now - 25),      // Implausibly far from the latest, skipped
+            (50300_00000000, 8, now - 1000), // Stale, skipped
+            (50200_00000000, 8, now - 30),
+        ];
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default()
+                .with_account(aggregator, data.clone())
+                .with_account(buffer, history_buffer(&rows, 8))
+        ));
+        let address = aggregator.to_string();
+        
+        // Off: the latest round as-is
+        assert_eq!(client.get_price(&address, None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap().price, 50500_00000000);
+        
+        // Latest plus two most recent: median of 50,500 / 50,000 / 49,000
+        let smoothed = client.get_price(&address, None, DEFAULT_MAX_STALENESS_SECS, 3).await.unwrap();
+        assert_eq!(smoothed.price, 50000_00000000);
+        assert_eq!(smoothed.timestamp, now);
+        
+        // Only four rounds survive the checks
+        assert_eq!(client.get_price(&address, None, DEFAULT_MAX_STALENESS_SECS, 8).await.unwrap().price, 50100_00000000);
+        
+        // Without a readable history buffer the latest round is used alone
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        assert_eq!(client.get_price(&address, None, DEFAULT_MAX_STALENESS_SECS, 3).await.unwrap().price, 50500_00000000);
+    }
+    
+    #[test]
+    fn test_parse_history_buffer() {
+        assert!(parse_history_buffer(&[0u8; 64]).is_empty());
+        
+        let rows = parse_history_buffer(&history_buffer(&[(3, 8, 30), (2, 8, 20), (1, 8, 10)], 4));
+        assert_eq!(rows, vec![(3, 8, 30), (2, 8, 20), (1, 8, 10)]);
+        
+        assert_eq!(rescale_mantissa(5_000000, 6, 8), Some(5_00000000));
+        assert_eq!(rescale_mantissa(5_00000000, 8, 6), Some(5_000000));
+        assert_eq!(rescale_mantissa(5, 200, 8), None);
+        assert_eq!(rescale_mantissa(i128::MAX, 0, 18), None);
+    }
+    
+    #[tokio::test]
+    async fn test_parse_oracle_count() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        let mut data = aggregator_account(50000_00000000, 8, now, 49900_00000000, 50100_00000000);
+        data.resize(NUM_SUCCESS_OFFSET + 4, 0);
+        data[NUM_SUCCESS_OFFSET..NUM_SUCCESS_OFFSET + 4].copy_from_slice(&12u32.to_le_bytes());
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        let price_data = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap();
+        assert_eq!(price_data.oracle_count, Some(12));
+    }
+    
+    #[tokio::test]
+    async fn test_parse_valid_aggregator() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        let data = aggregator_account(50000_00000000, 8, now, 49900_00000000, 50100_00000000);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        let price_data = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap();
+        assert_eq!(price_data.price, 50000_00000000);
+        assert_eq!(price_data.expo, -8);
+        assert_eq!(price_data.confidence, 100_00000000);
+        assert_eq!(price_data.confidence_ratio, Some(0.002));
+        assert_eq!(price_data.timestamp, now);
+        assert_eq!(price_data.source, PriceSource::Switchboard);
+        assert_eq!(price_data.oracle_count, None);
+        
+        // Wrong discriminator
+        let mut bad = aggregator_account(50000_00000000, 8, now, 0, 0);
+        bad[0] = 0;
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, bad)
+        ));
+        assert!(client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_confidence_limit() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        // Spread of $200 on $50,000 gives a $100 (20bp) confidence
+        let data = aggregator_account(50000_00000000, 8, now, 49900_00000000, 50100_00000000);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        assert!(client.get_price(&aggregator.to_string(), Some(20), DEFAULT_MAX_STALENESS_SECS, 0).await.is_ok());
+        
+        let err = client.get_price(&aggregator.to_string(), Some(19), DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap_err();
+        assert!(matches!(err.downcast_ref::<OracleError>(), Some(OracleError::InsufficientConfidence(_))));
+        assert!(err.to_string().contains("20.00bp exceeds max 19bp"));
+    }
+    
+    #[tokio::test]
+    async fn test_mantissa_beyond_i64() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        
+        // $50,000 at 18 decimals needs more than 64 bits
+        let mantissa = 50000 * 10_i128.pow(18);
+        assert!(mantissa > i64::MAX as i128);
+        let data = aggregator_account(mantissa, 18, now, 0, 0);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        // Saturates to i64::MAX and is rejected, instead of keeping the low 8 bytes
+        let err = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap_err();
+        assert!(err.to_string().contains("too high"));
+        
+        assert_eq!(saturate_i64(mantissa), (i64::MAX, true));
+        assert_eq!(saturate_i64(-mantissa), (i64::MIN, true));
+        assert_eq!(saturate_i64(-5), (-5, false));
+        assert_eq!(saturate_i64(i64::MAX as i128), (i64::MAX, false));
+    }
+    
+    #[tokio::test]
+    async fn test_scale_out_of_range() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        let data = aggregator_account(50000_00000000, 200, now, 0, 0);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        let err = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap_err();
+        assert!(err.to_string().contains("Invalid Switchboard scale 200"));
+        
+        // The largest sane scale is accepted
+        let data = aggregator_account(50_000_000_000_000, MAX_SCALE, now, 0, 0);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        assert_eq!(client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap().expo, -18);
+    }
+    
+    #[tokio::test]
+    async fn test_zero_scale() {
+        let aggregator = Pubkey::new_unique();
+        let now = chrono::Utc::now().timestamp();
+        // $50,000 in cents, but the account lost its scale
+        let data = aggregator_account(50000_00, 0, now, 49900_00, 50100_00);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, data)
+        ));
+        
+        let price_data = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap();
+        assert_eq!(price_data.expo, 0);
+        
+        let err = resolve_zero_scale(price_data.clone(), None).unwrap_err();
+        assert!(err.to_string().contains("Suspicious Switchboard scale 0"));
+        
+        let resolved = resolve_zero_scale(price_data, Some(-2)).unwrap();
+        assert_eq!(resolved.expo, -2);
+        assert_eq!(resolved.to_decimal(), 50000.0);
+        
+        // Properly scaled readings are left alone
+        let scaled = aggregator_account(50000_00000000, 8, now, 0, 0);
+        let client = SwitchboardClient::with_fetcher(Box::new(
+            MockAccountFetcher::default().with_account(aggregator, scaled)
+        ));
+        let price_data = client.get_price(&aggregator.to_string(), None, DEFAULT_MAX_STALENESS_SECS, 0).await.unwrap();
+        assert_eq!(resolve_zero_scale(price_data, Some(-2)).unwrap().expo, -8);
+    }
+    
+    #[test]
+    fn test_confidence_normalization() {
+        // Oracles answered between $49,900 and $50,100
+        let confidence = spread_confidence(49900_00000000, 50100_00000000);
+        assert_eq!(confidence, 100_00000000);
+        
+        // Order of the responses doesn't matter
+        assert_eq!(spread_confidence(50100_00000000, 49900_00000000), confidence);
+        assert_eq!(spread_confidence(50000_00000000, 50000_00000000), 0);
+        
+        // $100 on $50,000 is 20bp of price
+        let ratio = normalize_confidence(50000_00000000, 49900_00000000, 50100_00000000).unwrap();
+        assert!((ratio - 0.002).abs() < 1e-12);
+        
+        // The scale cancels out
+        let ratio = normalize_confidence(50000_00, 49900_00, 50100_00).unwrap();
+        assert!((ratio - 0.002).abs() < 1e-12);
+        
+        assert_eq!(normalize_confidence(0, 49900_00000000, 50100_00000000), None);
+    }
     
     #[tokio::test]
     async fn test_switchboard_client_creation() {
-        let client = SwitchboardClient::new("https://api.mainnet-beta.solana.com").await;
+        let client = SwitchboardClient::new("https://api.mainnet-beta.solana.com", CommitmentConfig::confirmed()).await;
         assert!(client.is_ok());
     }
     
     #[tokio::test]
     async fn test_invalid_aggregator_address() {
-        let client = SwitchboardClient::new("https://api.mainnet-beta.solana.com").await.unwrap();
-        let result = client.get_price("invalid_address").await;
+        let client = SwitchboardClient::new("https://api.mainnet-beta.solana.com", CommitmentConfig::confirmed()).await.unwrap();
+        let result = client.get_price("invalid_address", None, DEFAULT_MAX_STALENESS_SECS, 0).await;
         assert!(result.is_err());
     }
 }
\ No newline at end of file
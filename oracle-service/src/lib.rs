@@ -1,21 +1,26 @@
 pub mod manager;
+pub mod alerts;
 pub mod clients;
 pub mod aggregator;
 pub mod cache;
 pub mod types;
 pub mod api;
 pub mod websocket;
+pub mod metrics;
+pub mod rate_limit;
+#[cfg(feature = "client")]
+pub mod client;
 
 use anyhow::Result;
 use std::sync::Arc;
-use tracing::{info, error};
+use tracing::{info, error, warn};
 use tokio::signal;
 
 use crate::{
     manager::OracleManager,
     api::start_server,
     websocket::start_websocket_server,
-    types::{Config, Symbol},
+    types::{check_unique_symbols, Config, PushMode, Symbol},
 };
 
 /// Main application entry point
@@ -27,21 +32,43 @@ pub async fn run() -> Result<()> {
     
     // Load configuration
     let config = load_config()?;
+    let push_mode = config.manager.push_mode;
+    let self_test = config.manager.self_test;
     
     // Initialize Oracle Manager
     let oracle_manager = Arc::new(
         OracleManager::new(
             &config.solana.rpc_url,
+            &config.solana.commitment,
             &config.redis.url,
+            config.redis.pool_size,
             config.oracles,
+            config.manager,
         ).await?
     );
     
     info!("Oracle Manager initialized successfully");
     
-    // Start the oracle price fetching in background
+    // Catch on-chain layout drift before serving anything parsed with stale offsets
+    if self_test {
+        oracle_manager.self_test().await?;
+    }
+    
+    // Start the oracle price fetching in background, unless an external scheduler drives it
     let manager_clone = oracle_manager.clone();
     let oracle_task = tokio::spawn(async move {
+        // Honor sources switched off fleet-wide before the first fetch
+        manager_clone.watch_disabled_sources().await;
+        manager_clone.record_health_history();
+        manager_clone.rank_sources_periodically();
+        
+        // Surface misconfigured feeds right away instead of only in repeated error logs
+        manager_clone.warmup().await;
+        
+        if push_mode == PushMode::External {
+            info!("External push mode: fetches are triggered via POST /admin/fetch/:symbol");
+            return;
+        }
         if let Err(e) = manager_clone.start().await {
             error!("Oracle manager failed: {}", e);
         }
@@ -51,8 +78,12 @@ pub async fn run() -> Result<()> {
     let api_manager = oracle_manager.clone();
     let api_host = config.server.host.clone();
     let api_port = config.server.port;
+    let admin_api_key = config.server.admin_api_key.clone();
+    if admin_api_key.is_none() {
+        warn!("ADMIN_API_KEY not set, /admin endpoints will reject every request");
+    }
     let api_task = tokio::spawn(async move {
-        if let Err(e) = start_server(&api_host, api_port, api_manager).await {
+        if let Err(e) = start_server(&api_host, api_port, api_manager, push_mode, admin_api_key).await {
             error!("API server failed: {}", e);
         }
     });
@@ -75,13 +106,17 @@ pub async fn run() -> Result<()> {
     match signal::ctrl_c().await {
         Ok(()) => {
             info!("Received shutdown signal, stopping services...");
-            oracle_manager.stop().await;
+            oracle_manager.shutdown().await;
         },
         Err(err) => {
             error!("Unable to listen for shutdown signal: {}", err);
         },
     }
     
+    // Servers never return on their own; stop them once writes are flushed
+    api_task.abort();
+    ws_task.abort();
+    
     // Wait for tasks to complete
     let _ = tokio::join!(oracle_task, api_task, ws_task);
     
@@ -100,6 +135,8 @@ fn load_config() -> Result<Config> {
             max_staleness: 60,
             max_confidence: 10000, // 100% in basis points
             max_deviation: 500,    // 5% in basis points
+            pyth_price_feed_id: Some("e62df6c8b4a85fe1a67db44dc12de5db330f7ac66b72dc658afedf0f4a415b43".to_string()),
+            ..Default::default()
         },
         Symbol {
             name: "ETH/USD".to_string(),
@@ -108,6 +145,8 @@ fn load_config() -> Result<Config> {
             max_staleness: 60,
             max_confidence: 10000,
             max_deviation: 500,
+            pyth_price_feed_id: Some("ff61491a931112ddf1bd8147cd1b641375f79f5825126d665480874634fd0ace".to_string()),
+            ..Default::default()
         },
         Symbol {
             name: "SOL/USD".to_string(),
@@ -116,6 +155,8 @@ fn load_config() -> Result<Config> {
             max_staleness: 60,
             max_confidence: 10000,
             max_deviation: 500,
+            pyth_price_feed_id: Some("ef0d8b6fda2ceba41da15d4095d1da392a0d2f8ed0c6c7bc0f4cfac8c280b56d".to_string()),
+            ..Default::default()
         },
     ];
     
@@ -123,12 +164,16 @@ fn load_config() -> Result<Config> {
         solana: crate::types::SolanaConfig {
             rpc_url: std::env::var("SOLANA_RPC_URL")
                 .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string()),
-            commitment: "confirmed".to_string(),
+            commitment: std::env::var("SOLANA_COMMITMENT")
+                .unwrap_or_else(|_| "confirmed".to_string()),
         },
         redis: crate::types::RedisConfig {
             url: std::env::var("REDIS_URL")
                 .unwrap_or_else(|_| "redis://127.0.0.1:6379".to_string()),
-            pool_size: 10,
+            pool_size: std::env::var("REDIS_POOL_SIZE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
         },
         database: crate::types::DatabaseConfig {
             url: std::env::var("DATABASE_URL")
@@ -143,9 +188,193 @@ fn load_config() -> Result<Config> {
                 .parse()
                 .unwrap_or(8080),
             cors_origins: vec!["*".to_string()],
+            admin_api_key: std::env::var("ADMIN_API_KEY")
+                .ok()
+                .filter(|key| !key.is_empty()),
+        },
+        manager: crate::types::ManagerConfig {
+            broadcast_min_change_bp: std::env::var("BROADCAST_MIN_CHANGE_BP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            broadcast_heartbeat_secs: std::env::var("BROADCAST_HEARTBEAT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            outlier_method: std::env::var("OUTLIER_METHOD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            median_tie_break: std::env::var("MEDIAN_TIE_BREAK")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            expo_selection: std::env::var("EXPO_SELECTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            deviation_base: std::env::var("DEVIATION_BASE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or_default(),
+            max_sources: std::env::var("MAX_SOURCES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|&n| n > 0),
+            aggregation_memo: std::env::var("AGGREGATION_MEMO")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            relaxed_retry_multiplier: std::env::var("RELAXED_RETRY_MULTIPLIER")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            redis_history: std::env::var("REDIS_HISTORY")
+                .map(|v| v != "0" && v != "false")
+                .unwrap_or(true),
+            history_sample_interval_secs: std::env::var("HISTORY_SAMPLE_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            max_history_records: std::env::var("MAX_HISTORY_RECORDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1000),
+            price_deadline_ms: std::env::var("PRICE_DEADLINE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            push_mode: match std::env::var("PUSH_MODE").as_deref() {
+                Ok("external") => PushMode::External,
+                _ => PushMode::Internal,
+            },
+            shutdown_timeout_secs: std::env::var("SHUTDOWN_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            redis_fanout: std::env::var("WS_REDIS_FANOUT")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            fetch_interval_ms: std::env::var("FETCH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            adaptive_fetch_interval: std::env::var("ADAPTIVE_FETCH_INTERVAL")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            adaptive_min_interval_ms: std::env::var("ADAPTIVE_MIN_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(200),
+            adaptive_max_interval_ms: std::env::var("ADAPTIVE_MAX_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5000),
+            adaptive_fast_change_bp: std::env::var("ADAPTIVE_FAST_CHANGE_BP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            adaptive_calm_change_bp: std::env::var("ADAPTIVE_CALM_CHANGE_BP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2),
+            publish_interval_ms: std::env::var("PUBLISH_INTERVAL_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            publish_min_change_bp: std::env::var("PUBLISH_MIN_CHANGE_BP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(50),
+            switchboard_smoothing_rounds: std::env::var("SWITCHBOARD_SMOOTHING_ROUNDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            max_concurrent_fetches: std::env::var("MAX_CONCURRENT_FETCHES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(8),
+            early_exit_sources: std::env::var("EARLY_EXIT_SOURCES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            self_test: std::env::var("SELF_TEST")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            self_test_tolerance_bp: std::env::var("SELF_TEST_TOLERANCE_BP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+            hermes_url: std::env::var("HERMES_URL")
+                .unwrap_or_else(|_| "https://hermes.pyth.network".to_string()),
+            baseline_window: std::env::var("BASELINE_WINDOW")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100),
+            trace_history_len: std::env::var("TRACE_HISTORY_LEN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(20),
+            max_task_restarts: std::env::var("MAX_TASK_RESTARTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            adhoc_rate_limit_per_min: std::env::var("ADHOC_RATE_LIMIT_PER_MIN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            validate_feed_rate_limit_per_min: std::env::var("VALIDATE_FEED_RATE_LIMIT_PER_MIN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            stale_while_revalidate_secs: std::env::var("STALE_WHILE_REVALIDATE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            degraded_source_age_secs: std::env::var("DEGRADED_SOURCE_AGE_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            frozen_source_fetches: std::env::var("FROZEN_SOURCE_FETCHES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            drop_frozen_sources: std::env::var("DROP_FROZEN_SOURCES")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            source_priority_interval_secs: std::env::var("SOURCE_PRIORITY_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0),
+            health_snapshot_interval_secs: std::env::var("HEALTH_SNAPSHOT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
+            health_history_len: std::env::var("HEALTH_HISTORY_LEN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(120),
+            alert_webhook_enabled: std::env::var("ALERT_WEBHOOK_ENABLED")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            alert_webhook_url: std::env::var("ALERT_WEBHOOK_URL")
+                .ok()
+                .filter(|url| !url.is_empty()),
+            metrics_symbols: std::env::var("METRICS_SYMBOLS")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| v.split(',').map(|s| s.trim().to_string()).collect()),
+            response_envelope: std::env::var("RESPONSE_ENVELOPE")
+                .map(|v| v == "1" || v == "true")
+                .unwrap_or(false),
+            instance_id: std::env::var("INSTANCE_ID")
+                .or_else(|_| std::env::var("HOSTNAME"))
+                .ok()
+                .filter(|id| !id.is_empty()),
         },
         oracles: default_symbols,
     };
     
+    check_unique_symbols(&config.oracles)?;
+    
     Ok(config)
 }
\ No newline at end of file
@@ -1,14 +1,24 @@
 use anyhow::Result;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, error, warn};
-use std::collections::HashMap;
-use std::time::Duration;
+use tokio::sync::{broadcast, mpsc, RwLock, Semaphore};
+use tracing::{debug, info, error, warn};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::str::FromStr;
+use solana_sdk::pubkey::Pubkey;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{self, FuturesUnordered, Stream, StreamExt, TryStreamExt};
+use std::time::{Duration, Instant};
 
-use crate::clients::{PythClient, SwitchboardClient};
-use crate::aggregator::PriceAggregator;
-use crate::cache::PriceCache;
-use crate::types::{PriceData, OracleHealth, Symbol};
+use crate::alerts::{Alert, WebhookSink};
+use crate::clients::{check_rpc_reachable, parse_commitment, resolve_zero_scale, validate_rpc_url, HermesClient, PythClient, SwitchboardClient, DEFAULT_MAX_STALENESS_SECS};
+use crate::aggregator::{cross_price, AggregationConfig, AggregationStrategy, AggregationTrace, Baseline, BaselineWindow, Candle, PriceAggregator, VolatilityStats};
+use crate::cache::{PriceCache, PubSubEvent};
+use crate::metrics::{Metrics, SymbolLabels};
+use crate::types::{HealthSnapshot, PriceData, PriceSource, OracleError, OracleHealth, Symbol, ManagerConfig, WsMessage};
+use crate::websocket::{broadcast_health_alert, broadcast_price_update};
 
 /// Core Oracle Manager that orchestrates all oracle operations
 pub struct OracleManager {
@@ -18,24 +28,92 @@ pub struct OracleManager {
     price_cache: Arc<PriceCache>,
     health_status: Arc<RwLock<HashMap<String, OracleHealth>>>,
     symbols: Vec<Symbol>,
+    settings: ManagerConfig,
+    broadcast_sender: broadcast::Sender<WsMessage>,
+    last_broadcast: Arc<RwLock<HashMap<String, (f64, Instant)>>>,
+    last_publish: Arc<RwLock<HashMap<String, (f64, Instant)>>>,
+    baselines: Arc<RwLock<HashMap<String, BaselineWindow>>>,
+    traces: Arc<RwLock<HashMap<String, VecDeque<TracedCycle>>>>,
+    revalidating: Arc<RwLock<HashSet<String>>>,
+    disabled_sources: Arc<RwLock<HashSet<PriceSource>>>,
+    muted_sources: Arc<RwLock<HashMap<PriceSource, i64>>>,
+    source_timestamps: Arc<RwLock<HashMap<(String, PriceSource), TimestampWatch>>>,
+    source_health: Arc<RwLock<HashMap<(String, PriceSource), OracleHealth>>>,
+    source_priority: Arc<RwLock<HashMap<String, Vec<SourcePriority>>>>, // Best source first
+    alert_sink: Option<Arc<WebhookSink>>,
+    metrics: Arc<Metrics>,
+    fetch_permits: Arc<Semaphore>,
+    draining: Arc<AtomicBool>,
     is_running: Arc<RwLock<bool>>,
 }
 
+/// History entries read from Redis per round trip when streaming
+const HISTORY_PAGE_SIZE: usize = 200;
+
+/// Symbols given their own metric series when none are configured
+const DEFAULT_METRICS_SYMBOLS: usize = 20;
+
+/// Age up to which a cached price is served as-is
+const CACHE_FRESHNESS_SECS: u64 = 5;
+
+/// Lowest fetch interval honored, whatever the configuration says
+const MIN_FETCH_INTERVAL_MS: u64 = 100;
+
+/// Consecutive price moves the adaptive fetch interval looks back over
+const ADAPTIVE_MOVE_WINDOW: usize = 5;
+
+/// Fetch latency at which a source's priority score is halved
+const PRIORITY_LATENCY_REFERENCE_MS: f64 = 500.0;
+
+/// Smallest aggregation weight health ranking leaves a source with
+const MIN_PRIORITY_WEIGHT: f64 = 0.25;
+
+/// First delay before respawning a crashed fetch loop, doubled per restart
+const TASK_RESTART_BACKOFF: Duration = Duration::from_secs(1);
+
+/// How often the source kill-switch is re-read from Redis
+const DISABLED_SOURCES_SYNC_INTERVAL: Duration = Duration::from_secs(1);
+
 impl OracleManager {
     pub async fn new(
         rpc_url: &str,
+        commitment: &str,
         redis_url: &str,
-        symbols: Vec<Symbol>
+        redis_pool_size: u32,
+        symbols: Vec<Symbol>,
+        settings: ManagerConfig,
     ) -> Result<Self> {
         info!("Initializing Oracle Manager with {} symbols", symbols.len());
         
-        // Initialize clients
-        let pyth_client = Arc::new(PythClient::new(rpc_url).await?);
-        let switchboard_client = Arc::new(SwitchboardClient::new(rpc_url).await?);
+        // Fail fast on settings that can never work, before touching the network
+        let commitment = parse_commitment(commitment)
+            .map_err(|e| OracleError::ConfigError(e.to_string()))?;
+        validate_rpc_url(rpc_url)?;
+        
+        // Then make sure both backends answer, so a bad endpoint is a startup
+        // error rather than a stream of per-symbol fetch failures
+        let price_cache = Arc::new(
+            PriceCache::new(redis_url, redis_pool_size).await?
+                .with_history(settings.redis_history)
+                .with_history_sample_interval(settings.history_sample_interval_secs)
+        );
+        price_cache.ping().await?;
+        check_rpc_reachable(rpc_url).await?;
         
-        // Initialize aggregator and cache
-        let price_aggregator = Arc::new(PriceAggregator::new());
-        let price_cache = Arc::new(PriceCache::new(redis_url).await?);
+        // Initialize clients at the configured commitment
+        let pyth_client = Arc::new(PythClient::new(rpc_url, commitment).await?);
+        let switchboard_client = Arc::new(SwitchboardClient::new(rpc_url, commitment).await?);
+        
+        // Initialize aggregator
+        let price_aggregator = Arc::new(
+            PriceAggregator::new()
+                .with_outlier_method(settings.outlier_method)
+                .with_median_tie_break(settings.median_tie_break)
+                .with_deviation_base(settings.deviation_base)
+                .with_expo_selection(settings.expo_selection)
+                .with_memoization(settings.aggregation_memo)
+                .with_relaxed_retry(Some(settings.relaxed_retry_multiplier))
+        );
         
         // Initialize health status tracking
         let mut health_status = HashMap::new();
@@ -43,6 +121,22 @@ impl OracleManager {
             health_status.insert(symbol.name.clone(), OracleHealth::default());
         }
         
+        // Channel fanning price updates out to WebSocket connections
+        let (broadcast_sender, _) = broadcast::channel(1000);
+        
+        let fetch_permits = Arc::new(Semaphore::new(settings.max_concurrent_fetches.max(1)));
+        let metrics = Arc::new(Metrics::with_symbol_labels(symbol_labels(&settings, &symbols)));
+        
+        // Optional outbound delivery of health alerts
+        let alert_sink = match (&settings.alert_webhook_url, settings.alert_webhook_enabled) {
+            (Some(url), true) => Some(Arc::new(WebhookSink::spawn(url)?)),
+            (None, true) => {
+                warn!("Alert webhook enabled without ALERT_WEBHOOK_URL, alerts stay local");
+                None
+            },
+            (_, false) => None,
+        };
+        
         Ok(Self {
             pyth_client,
             switchboard_client,
@@ -50,6 +144,22 @@ impl OracleManager {
             price_cache,
             health_status: Arc::new(RwLock::new(health_status)),
             symbols,
+            settings,
+            broadcast_sender,
+            last_broadcast: Arc::new(RwLock::new(HashMap::new())),
+            last_publish: Arc::new(RwLock::new(HashMap::new())),
+            baselines: Arc::new(RwLock::new(HashMap::new())),
+            traces: Arc::new(RwLock::new(HashMap::new())),
+            revalidating: Arc::new(RwLock::new(HashSet::new())),
+            disabled_sources: Arc::new(RwLock::new(HashSet::new())),
+            muted_sources: Arc::new(RwLock::new(HashMap::new())),
+            source_timestamps: Arc::new(RwLock::new(HashMap::new())),
+            source_health: Arc::new(RwLock::new(HashMap::new())),
+            source_priority: Arc::new(RwLock::new(HashMap::new())),
+            alert_sink,
+            metrics,
+            fetch_permits,
+            draining: Arc::new(AtomicBool::new(false)),
             is_running: Arc::new(RwLock::new(false)),
         })
     }
@@ -59,12 +169,12 @@ impl OracleManager {
         info!("Starting Oracle Manager");
         *self.is_running.write().await = true;
         
-        // Start price fetching for all symbols
+        // Start price fetching for all symbols, each loop under a watchdog
         let tasks: Vec<_> = self.symbols.iter().map(|symbol| {
             let symbol = symbol.clone();
             let manager = self.clone();
             tokio::spawn(async move {
-                manager.price_fetch_loop(symbol).await;
+                manager.supervise_fetch_loop(symbol).await;
             })
         }).collect();
         
@@ -78,60 +188,458 @@ impl OracleManager {
         Ok(())
     }
     
+    /// Probe every symbol once at startup and flag feeds that fail on every source
+    ///
+    /// Misconfigured symbols are marked unhealthy and announced with a
+    /// "misconfigured" health alert so operators see bad config right after boot.
+    pub async fn warmup(&self) {
+        info!("Warming up {} symbols", self.symbols.len());
+        
+        for symbol in &self.symbols {
+            let readings = self.fetch_source_prices(symbol).await;
+            if readings.iter().any(|(_, result)| result.is_ok()) {
+                continue;
+            }
+            
+            let errors: Vec<String> = readings.iter()
+                .filter_map(|(source, result)| result.as_ref().err().map(|e| format!("{:?}: {}", source, e)))
+                .collect();
+            let message = if errors.is_empty() {
+                "No sources enabled".to_string()
+            } else {
+                format!("All sources failed on first fetch ({})", errors.join("; "))
+            };
+            
+            warn!("Symbol {} looks misconfigured: {}", symbol.name, message);
+            
+            if let Some(status) = self.health_status.write().await.get_mut(&symbol.name) {
+                status.mark_unhealthy(message.clone());
+            }
+            self.raise_health_alert(&symbol.name, "misconfigured", &message).await;
+        }
+    }
+    
+    /// Parse every configured feed and cross-check it against Hermes
+    ///
+    /// Fails if any feed can't be parsed or lands outside the configured
+    /// tolerance of the reference. Symbols without a Hermes feed id are only
+    /// checked for parsing.
+    pub async fn self_test(&self) -> Result<()> {
+        info!("Running parser self-test against {}", self.settings.hermes_url);
+        let hermes = HermesClient::new(&self.settings.hermes_url)?;
+        let mut failures = Vec::new();
+        
+        for symbol in &self.symbols {
+            let reference = match &symbol.pyth_price_feed_id {
+                Some(feed_id) => match hermes.get_price(feed_id).await {
+                    Ok(price) => Some(price.to_decimal()),
+                    Err(e) => {
+                        failures.push(format!("{} Hermes: {}", symbol.name, e));
+                        continue;
+                    }
+                },
+                None => {
+                    warn!("No Hermes feed id for {}, checking parsing only", symbol.name);
+                    None
+                }
+            };
+            
+            for (source, result) in self.fetch_source_prices(symbol).await {
+                let outcome = result.and_then(|reading| match reference {
+                    Some(reference) => check_against_reference(&reading, reference, self.settings.self_test_tolerance_bp),
+                    None => Ok(()),
+                });
+                if let Err(e) = outcome {
+                    failures.push(format!("{} {:?}: {}", symbol.name, source, e));
+                }
+            }
+        }
+        
+        if !failures.is_empty() {
+            anyhow::bail!("Parser self-test failed: {}", failures.join("; "));
+        }
+        info!("Parser self-test passed for {} symbols", self.symbols.len());
+        Ok(())
+    }
+    
+    /// Evict a symbol's cached price and replace it with a fresh fetch; history is kept
+    pub async fn refresh_symbol(&self, symbol: &str) -> Result<PriceData> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        self.price_cache.clear_symbol(&symbol_config.name).await?;
+        info!("Evicted cached price for {}, refetching", symbol_config.name);
+        
+        self.run_fetch_cycle(symbol_config, Duration::ZERO).await
+    }
+    
+    /// Stop taking new WebSocket clients ahead of a shutdown; REST keeps serving
+    pub fn start_drain(&self) {
+        if !self.draining.swap(true, Ordering::SeqCst) {
+            info!("Draining: refusing new WebSocket connections and subscriptions");
+        }
+    }
+    
+    /// Whether a drain has been requested
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+    
     /// Stop the oracle manager
     pub async fn stop(&self) {
         info!("Stopping Oracle Manager");
         *self.is_running.write().await = false;
     }
     
+    /// Stop fetching and wait (bounded) for outstanding cache writes to land
+    pub async fn shutdown(&self) {
+        self.stop().await;
+        
+        let timeout = Duration::from_secs(self.settings.shutdown_timeout_secs);
+        if self.price_cache.flush(timeout).await {
+            info!("Pending cache writes flushed");
+        }
+    }
+    
+    /// Keep a symbol's fetch loop alive, marking the symbol hard-failed once
+    /// the restart budget is spent
+    async fn supervise_fetch_loop(&self, symbol: Symbol) {
+        let name = format!("price fetch loop for {}", symbol.name);
+        let manager = self.clone();
+        
+        let survived = supervise(
+            &name,
+            self.is_running.clone(),
+            self.settings.max_task_restarts,
+            TASK_RESTART_BACKOFF,
+            || {
+                let manager = manager.clone();
+                let symbol = symbol.clone();
+                async move { manager.price_fetch_loop(symbol).await }
+            },
+        ).await;
+        
+        if !survived {
+            let message = format!("Fetch loop crashed {} times, giving up", self.settings.max_task_restarts + 1);
+            if let Some(status) = self.health_status.write().await.get_mut(&symbol.name) {
+                status.mark_unhealthy(message.clone());
+            }
+            self.raise_health_alert(&symbol.name, "hard_failed", &message).await;
+        }
+    }
+    
     /// Main price fetching loop for a specific symbol
     async fn price_fetch_loop(&self, symbol: Symbol) {
         info!("Starting price fetch loop for {}", symbol.name);
         
+        let configured = symbol.fetch_interval_ms.unwrap_or(self.settings.fetch_interval_ms);
+        if configured < MIN_FETCH_INTERVAL_MS {
+            warn!("Fetch interval {}ms for {} is below the {}ms floor, using the floor",
+                configured, symbol.name, MIN_FETCH_INTERVAL_MS);
+        }
+        let mut interval = effective_fetch_interval(configured);
+        let publish_interval = Duration::from_millis(
+            symbol.publish_interval_ms.unwrap_or(self.settings.publish_interval_ms)
+        );
+        
+        let adaptive = self.settings.adaptive_fetch_interval;
+        if adaptive {
+            let (min, max) = adaptive_bounds(&self.settings);
+            interval = interval.clamp(min, max);
+        }
+        let mut last_price: Option<f64> = None;
+        let mut recent_moves = VecDeque::with_capacity(ADAPTIVE_MOVE_WINDOW);
+        
         while *self.is_running.read().await {
-            match self.fetch_and_aggregate_price(&symbol).await {
-                Ok(price_data) => {
+            // Failures are logged and recorded in health status by the cycle itself
+            let result = self.run_fetch_cycle(&symbol, publish_interval).await;
+            
+            // Speed up while the price is moving, back off while it is quiet
+            if let (true, Ok(price_data)) = (adaptive, &result) {
+                let price = price_data.to_decimal();
+                if let Some(previous) = last_price.filter(|&previous| previous > 0.0) {
+                    push_capped(&mut recent_moves, (price - previous).abs() / previous * 10000.0, ADAPTIVE_MOVE_WINDOW);
+                    let largest_move = recent_moves.iter().cloned().fold(0.0, f64::max);
+                    let next = adaptive_interval(interval, largest_move, &self.settings);
+                    if next != interval {
+                        debug!("Fetch interval for {} now {:?} (largest recent move {:.2}bp)", symbol.name, next, largest_move);
+                    }
+                    interval = next;
+                }
+                last_price = Some(price);
+            }
+            
+            // Wait before next fetch
+            tokio::time::sleep(interval).await;
+        }
+    }
+    
+    /// Run exactly one fetch+aggregate+cache+broadcast cycle for a symbol
+    ///
+    /// Used by external schedulers in place of the internal fetch loops.
+    pub async fn fetch_symbol_once(&self, symbol: &str) -> Result<PriceData> {
+        let symbol_config = self.symbol_config(symbol)?.clone();
+        self.run_fetch_cycle(&symbol_config, Duration::ZERO).await
+    }
+    
+    /// Fetch and aggregate a symbol, then cache, broadcast and record health
+    ///
+    /// Caching and broadcasting are skipped while `publish_interval` hasn't
+    /// passed since the last publish, unless the price moved by at least
+    /// `publish_min_change_bp`; the cycle's result is returned either way.
+    async fn run_fetch_cycle(&self, symbol: &Symbol, publish_interval: Duration) -> Result<PriceData> {
+        let started = Instant::now();
+        let result = self.fetch_and_aggregate_price(symbol).await;
+        self.metrics.record_fetch(&symbol.name, result.is_ok(), started.elapsed().as_secs_f64() * 1000.0).await;
+        
+        match result {
+            Ok(price_data) => {
+                if self.claim_publish(&symbol.name, &price_data, publish_interval).await {
                     // Cache the aggregated price
                     if let Err(e) = self.price_cache.set_price(&symbol.name, &price_data).await {
                         error!("Failed to cache price for {}: {}", symbol.name, e);
                     }
                     
-                    // Update health status
-                    self.update_health_status(&symbol.name, true).await;
-                },
-                Err(e) => {
-                    error!("Failed to fetch price for {}: {}", symbol.name, e);
-                    self.update_health_status(&symbol.name, false).await;
+                    // Push to WebSocket subscribers if the move is worth reporting
+                    self.maybe_broadcast(&symbol.name, &price_data).await;
                 }
+                
+                // Update health status
+                self.update_health_status(&symbol.name, true).await;
+                Ok(price_data)
+            },
+            Err(e) => {
+                error!("Failed to fetch price for {}: {}", symbol.name, e);
+                self.update_health_status(&symbol.name, false).await;
+                Err(e)
+            }
+        }
+    }
+    
+    /// Whether this cycle's price is due for publishing, recording it as published if so
+    async fn claim_publish(&self, symbol: &str, price_data: &PriceData, interval: Duration) -> bool {
+        if interval.is_zero() {
+            return true;
+        }
+        
+        let price = price_data.to_decimal();
+        let now = Instant::now();
+        let mut last_publish = self.last_publish.write().await;
+        if !should_publish(last_publish.get(symbol), price, now, interval, self.settings.publish_min_change_bp) {
+            return false;
+        }
+        last_publish.insert(symbol.to_string(), (price, now));
+        true
+    }
+    
+    /// Broadcast a price update if it moved enough or the heartbeat interval elapsed
+    async fn maybe_broadcast(&self, symbol: &str, price_data: &PriceData) {
+        let price = price_data.to_decimal();
+        let now = Instant::now();
+        let heartbeat = Duration::from_secs(self.settings.broadcast_heartbeat_secs);
+        
+        let mut last_broadcast = self.last_broadcast.write().await;
+        if !should_broadcast(last_broadcast.get(symbol), price, now, self.settings.broadcast_min_change_bp, heartbeat) {
+            return;
+        }
+        last_broadcast.insert(symbol.to_string(), (price, now));
+        drop(last_broadcast);
+        
+        // With fan-out enabled every replica, including this one, receives the
+        // update from Redis and forwards it to its own clients
+        if self.settings.redis_fanout {
+            match self.price_cache.publish_price_update(symbol, price_data).await {
+                Ok(()) => return,
+                Err(e) => warn!("Failed to publish {} update to Redis, broadcasting locally: {}", symbol, e),
+            }
+        }
+        
+        broadcast_price_update(&self.broadcast_sender, symbol, price_data).await;
+    }
+    
+    /// Send a health alert to WebSocket subscribers and the webhook, if configured
+    async fn raise_health_alert(&self, oracle: &str, status: &str, message: &str) {
+        broadcast_health_alert(&self.broadcast_sender, oracle, status, message).await;
+        
+        if let Some(sink) = &self.alert_sink {
+            sink.notify(Alert {
+                oracle: oracle.to_string(),
+                status: status.to_string(),
+                message: message.to_string(),
+                timestamp: chrono::Utc::now().timestamp(),
+            });
+        }
+    }
+    
+    /// Settings the manager was started with
+    pub fn settings(&self) -> &ManagerConfig {
+        &self.settings
+    }
+    
+    /// Whether WebSocket broadcasts are routed through Redis pubsub
+    pub fn redis_fanout(&self) -> bool {
+        self.settings.redis_fanout
+    }
+    
+    /// Subscribe to price updates published by any replica for the configured symbols
+    pub fn subscribe_price_updates(&self) -> mpsc::Receiver<PubSubEvent> {
+        let symbols = self.symbols.iter().map(|s| s.name.clone()).collect();
+        self.price_cache.spawn_price_subscriber(symbols)
+    }
+    
+    /// Service metrics collected by the fetch loops
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+    
+    /// Sender side of the price update channel, shared with the WebSocket server
+    pub fn broadcast_sender(&self) -> broadcast::Sender<WsMessage> {
+        self.broadcast_sender.clone()
+    }
+    
+    /// Fetch the raw price from every allowed source, keeping failures
+    pub async fn fetch_source_prices(&self, symbol: &Symbol) -> Vec<(PriceSource, Result<PriceData>)> {
+        self.fetch_source_prices_until(symbol, |_| false).await
+    }
+    
+    /// Fetch a symbol's sources concurrently, stopping as soon as the successful
+    /// readings so far are `enough`; slower fetches still in flight are cancelled
+    /// and left out of the result
+    async fn fetch_source_prices_until(
+        &self,
+        symbol: &Symbol,
+        enough: impl Fn(&[PriceData]) -> bool,
+    ) -> Vec<(PriceSource, Result<PriceData>)> {
+        let disabled = self.disabled_sources.read().await.clone();
+        let mut sources = sources_to_fetch(symbol, &disabled);
+        if let Some(ranking) = self.source_priority.read().await.get(&symbol.name) {
+            order_sources(&mut sources, ranking);
+        }
+        cap_sources(&mut sources, self.settings.max_sources, symbol.reference_source.as_ref());
+        let fetches: Vec<(PriceSource, BoxFuture<'_, Result<PriceData>>)> = sources.into_iter()
+            .filter_map(|source| {
+                // Bound RPC load across all symbol loops: one permit per source fetch
+                let fetch = self.source_fetch(symbol, &source)?;
+                let tracked = self.track_source_health(symbol, source.clone(), fetch);
+                Some((source, with_permit(&self.fetch_permits, tracked)))
+            })
+            .collect();
+        
+        let mut readings = fetch_concurrently(fetches, enough).await;
+        
+        for (source, result) in readings.iter_mut() {
+            match result {
+                // Clients don't know which symbol a feed belongs to
+                Ok(price) => price.symbol = symbol.name.clone(),
+                Err(e) => warn!("{:?} price fetch failed for {}: {}", source, symbol.name, e),
             }
+        }
+        
+        readings
+    }
+    
+    /// Record a fetch's outcome and latency in the source's rolling health
+    ///
+    /// Fetches cancelled once enough sources answered are not counted.
+    fn track_source_health<'a>(
+        &'a self,
+        symbol: &'a Symbol,
+        source: PriceSource,
+        fetch: BoxFuture<'a, Result<PriceData>>,
+    ) -> BoxFuture<'a, Result<PriceData>> {
+        Box::pin(async move {
+            let started = Instant::now();
+            let result = fetch.await;
             
-            // Wait before next fetch (configurable interval)
-            tokio::time::sleep(Duration::from_millis(500)).await; // 500ms for sub-second updates
+            let mut health = self.source_health.write().await;
+            let entry = health.entry((symbol.name.clone(), source)).or_default();
+            entry.update(result.is_ok());
+            if result.is_ok() {
+                entry.update_latency(started.elapsed().as_secs_f64() * 1000.0);
+            }
+            result
+        })
+    }
+    
+    /// Fetch one source's current price for a symbol, bypassing aggregation
+    ///
+    /// Fails with `OracleError::ConfigError` when the symbol doesn't use the
+    /// source. Sources switched off by the kill-switch stay off here too.
+    pub async fn get_source_price(&self, symbol: &str, source: &PriceSource) -> Result<PriceData> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        if !sources_to_fetch(symbol_config, &HashSet::new()).contains(source) {
+            return Err(OracleError::ConfigError(
+                format!("{:?} is not a source for {}", source, symbol_config.name)
+            ).into());
+        }
+        if self.disabled_sources.read().await.contains(source) {
+            return Err(OracleError::PriceUnavailable(format!("{:?} is disabled", source)).into());
+        }
+        
+        let _permit = self.fetch_permits.acquire().await
+            .expect("fetch semaphore closed");
+        let fetch = self.source_fetch(symbol_config, source)
+            .ok_or_else(|| anyhow::anyhow!("{:?} cannot be fetched directly", source))?;
+        
+        let mut price = fetch.await?;
+        price.symbol = symbol_config.name.clone();
+        Ok(price)
+    }
+    
+    /// The client call that reads `source` for a symbol, if it is an on-chain source
+    fn source_fetch<'a>(&'a self, symbol: &'a Symbol, source: &PriceSource) -> Option<BoxFuture<'a, Result<PriceData>>> {
+        match source {
+            PriceSource::Pyth => Some(Box::pin(
+                self.pyth_client.get_price(&symbol.pyth_feed_id, symbol.max_staleness_for(source))
+            )),
+            PriceSource::Switchboard => Some(Box::pin(async move {
+                let mut price = self.switchboard_client.get_price(
+                    &symbol.switchboard_aggregator,
+                    Some(symbol.max_confidence),
+                    symbol.max_staleness_for(&PriceSource::Switchboard),
+                    self.settings.switchboard_smoothing_rounds,
+                ).await?;
+                price.symbol = symbol.name.clone();
+                resolve_zero_scale(price, symbol.switchboard_zero_scale_expo)
+            })),
+            PriceSource::Aggregated | PriceSource::Internal => None,
         }
     }
     
     /// Fetch prices from all sources and aggregate them
     async fn fetch_and_aggregate_price(&self, symbol: &Symbol) -> Result<PriceData> {
-        let mut prices = Vec::new();
-        
-        // Fetch from Pyth
-        match self.pyth_client.get_price(&symbol.pyth_feed_id).await {
-            Ok(pyth_price) => {
-                prices.push(pyth_price);
-            },
-            Err(e) => {
-                warn!("Pyth price fetch failed for {}: {}", symbol.name, e);
-            }
+        let early_exit = self.settings.early_exit_sources;
+        let mut prices: Vec<PriceData> = self.fetch_source_prices_until(symbol, |prices| {
+            early_exit > 0 && agreeing_confident_sources(prices, symbol) >= early_exit
+        }).await
+            .into_iter()
+            .filter_map(|(_, result)| result.ok())
+            .collect();
+        let frozen = self.check_frozen_sources(&symbol.name, &prices).await;
+        if self.settings.drop_frozen_sources && !frozen.is_empty() {
+            prices.retain(|price| !frozen.contains(&price.source));
         }
         
-        // Fetch from Switchboard  
-        match self.switchboard_client.get_price(&symbol.switchboard_aggregator).await {
-            Ok(sb_price) => {
-                prices.push(sb_price);
-            },
-            Err(e) => {
-                warn!("Switchboard price fetch failed for {}: {}", symbol.name, e);
+        // Sources an order of magnitude apart point at a parsing bug, not the market
+        if !self.price_aggregator.is_scale_consistent(&prices) {
+            let reference = self.price_cache.get_price(&symbol.name).await
+                .ok()
+                .flatten()
+                .map(|p| p.to_decimal());
+            let suspects = self.price_aggregator.scale_suspects(&prices, reference);
+            
+            for &i in &suspects {
+                error!(
+                    "Likely decimals/expo parsing bug for {}: {:?} reports {} (expo {}), reference {:?}; excluding source",
+                    symbol.name, prices[i].source, prices[i].to_decimal(), prices[i].expo, reference
+                );
             }
+            
+            prices = prices.into_iter()
+                .enumerate()
+                .filter(|(i, _)| !suspects.contains(i))
+                .map(|(_, price)| price)
+                .collect();
         }
         
         // Ensure we have at least one price
@@ -140,68 +648,1831 @@ impl OracleManager {
         }
         
         // Aggregate prices using consensus algorithm
-        let aggregated_price = self.price_aggregator.aggregate_prices(&prices, &symbol)?;
+        let tiered = self.price_aggregator.aggregate_two_tier(&prices, symbol)?;
+        let mut aggregated_price = tiered.price;
+        
+        // Per-source staleness limits don't catch an aggregate built only from old data
+        // Relaxed retries arrive already degraded and have been logged as such
+        let contributing: Vec<PriceData> = prices.iter()
+            .filter(|price| tiered.contributors.contains(&price.source))
+            .cloned()
+            .collect();
+        let degraded_after = Duration::from_secs(self.settings.degraded_source_age_secs);
+        let stale = annotate_source_age(&mut aggregated_price, &contributing, chrono::Utc::now().timestamp(), degraded_after);
+        if stale && !tiered.relaxed {
+            warn!("Aggregate for {} is degraded: every source is older than {}s (oldest {:?}s)",
+                symbol.name, self.settings.degraded_source_age_secs, aggregated_price.max_source_age_secs);
+        }
+        
+        // Track how far each source sits from consensus to tune max_deviation
+        let aggregate = aggregated_price.to_decimal();
+        if aggregate > 0.0 {
+            for price in &prices {
+                let deviation_bp = (price.to_decimal() - aggregate) / aggregate * 10000.0;
+                self.metrics.record_deviation(&price.source, deviation_bp).await;
+            }
+        }
+        
+        self.check_manipulation(&symbol.name, &prices, aggregate).await;
+        if let Some(trace) = tiered.trace {
+            self.record_trace(symbol, &aggregated_price, trace).await;
+        }
         
         Ok(aggregated_price)
     }
     
-    /// Get current price for a symbol from cache or fetch fresh
+    /// Keep the consensus derivation of this cycle's published price in the symbol's trace buffer
+    ///
+    /// The trace comes from the aggregation that produced the price, so cycles
+    /// published without a consensus (single source, median or
+    /// highest-confidence fallback) leave none.
+    async fn record_trace(&self, symbol: &Symbol, aggregated: &PriceData, trace: AggregationTrace) {
+        let capacity = self.settings.trace_history_len;
+        if capacity == 0 {
+            return;
+        }
+        
+        let cycle = TracedCycle {
+            timestamp: aggregated.timestamp,
+            price: aggregated.to_decimal(),
+            strategy: aggregated.strategy,
+            trace,
+        };
+        let mut traces = self.traces.write().await;
+        push_capped(traces.entry(symbol.name.clone()).or_default(), cycle, capacity);
+    }
+    
+    /// The symbol's most recent consensus traces, newest first
+    pub async fn get_traces(&self, symbol: &str, limit: usize) -> Result<Vec<TracedCycle>> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        Ok(self.traces.read().await
+            .get(&symbol_config.name)
+            .map(|cycles| cycles.iter().rev().take(limit).cloned().collect())
+            .unwrap_or_default())
+    }
+    
+    /// Fetch every source fresh and aggregate them, keeping per-source results for comparison
+    pub async fn get_source_diff(&self, symbol: &str) -> Result<SourceDiff> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        let readings = self.fetch_source_prices(symbol_config).await;
+        let prices: Vec<PriceData> = readings.iter()
+            .filter_map(|(_, result)| result.as_ref().ok().cloned())
+            .collect();
+        
+        let aggregated = if prices.is_empty() {
+            Err(format!("No price sources available for {}", symbol))
+        } else {
+            self.price_aggregator.aggregate_with_fallback(&prices, symbol_config)
+                .map_err(|e| e.to_string())
+        };
+        
+        Ok(SourceDiff {
+            symbol: symbol_config.name.clone(),
+            baseline: self.get_baseline(&symbol_config.name).await,
+            readings: readings.into_iter()
+                .map(|(source, result)| (source, result.map_err(|e| e.to_string())))
+                .collect(),
+            aggregated,
+            priority: self.source_priority.read().await
+                .get(&symbol_config.name)
+                .cloned()
+                .unwrap_or_default(),
+        })
+    }
+    
+    /// Track each source's reported timestamp across cycles and return the
+    /// sources whose timestamp hasn't advanced for `frozen_source_fetches` fetches
+    ///
+    /// A frozen publisher keeps serving a valid account, so the fetch succeeds
+    /// and clock skew can let it slip past the staleness window. The alert is
+    /// raised once when a source freezes, not on every cycle after.
+    async fn check_frozen_sources(&self, symbol: &str, prices: &[PriceData]) -> Vec<PriceSource> {
+        let threshold = self.settings.frozen_source_fetches;
+        if threshold == 0 {
+            return Vec::new();
+        }
+        
+        let mut frozen = Vec::new();
+        let mut newly_frozen = Vec::new();
+        {
+            let mut watches = self.source_timestamps.write().await;
+            for price in prices {
+                let watch = watches.entry((symbol.to_string(), price.source.clone())).or_default();
+                let was_frozen = watch.repeats >= threshold;
+                let repeats = watch.observe(price.timestamp);
+                
+                if repeats >= threshold {
+                    frozen.push(price.source.clone());
+                    if !was_frozen {
+                        newly_frozen.push((price.source.clone(), price.timestamp));
+                    }
+                } else if was_frozen {
+                    info!("{:?} timestamp for {} is advancing again", price.source, symbol);
+                }
+            }
+        }
+        
+        let muted = active_mutes(&*self.muted_sources.read().await, chrono::Utc::now().timestamp());
+        for (source, timestamp) in newly_frozen {
+            let message = format!(
+                "{:?} timestamp for {} stuck at {} for {} consecutive fetches",
+                source, symbol, timestamp, threshold
+            );
+            if muted.contains_key(&source) {
+                info!("Muted frozen source alert: {}", message);
+                continue;
+            }
+            warn!("{}", message);
+            self.raise_health_alert(symbol, "frozen_source", &message).await;
+        }
+        
+        frozen
+    }
+    
+    /// Run the manipulation detector against the symbol's rolling baseline, then
+    /// fold the new aggregate into it
+    async fn check_manipulation(&self, symbol: &str, prices: &[PriceData], aggregate: f64) {
+        let mut baselines = self.baselines.write().await;
+        let window = baselines.entry(symbol.to_string())
+            .or_insert_with(|| BaselineWindow::new(self.settings.baseline_window));
+        
+        let baseline = window.baseline();
+        let muted = active_mutes(&*self.muted_sources.read().await, chrono::Utc::now().timestamp());
+        for alert in self.price_aggregator.detect_manipulation(prices, baseline.as_ref()) {
+            if muted.contains_key(&alert.source) {
+                info!(
+                    "Muted manipulation alert on {}: {:?} from {:?} at {} (expected {}, deviation {:.4})",
+                    symbol, alert.alert_type, alert.source, alert.price, alert.expected, alert.deviation
+                );
+                continue;
+            }
+            warn!(
+                "Possible manipulation on {}: {:?} from {:?} at {} (expected {}, deviation {:.4})",
+                symbol, alert.alert_type, alert.source, alert.price, alert.expected, alert.deviation
+            );
+        }
+        
+        window.push(aggregate);
+    }
+    
+    /// Aggregation parameters the engine applies to a symbol
+    pub fn aggregation_config(&self, symbol: &str) -> Result<AggregationConfig> {
+        let symbol_config = self.symbol_config(symbol)?;
+        let sources = [PriceSource::Pyth, PriceSource::Switchboard].into_iter()
+            .filter(|source| symbol_config.uses_source(source))
+            .collect();
+        
+        Ok(AggregationConfig {
+            max_sources: self.settings.max_sources,
+            ..self.price_aggregator.effective_config(symbol_config, sources)
+        })
+    }
+    
+    /// Rolling baseline of recent aggregated prices for a symbol
+    pub async fn get_baseline(&self, symbol: &str) -> Option<Baseline> {
+        self.baselines.read().await.get(symbol).and_then(BaselineWindow::baseline)
+    }
+    
+    /// Rolling baselines for every symbol that has aggregated at least once
+    pub async fn get_baselines(&self) -> HashMap<String, Baseline> {
+        self.baselines.read().await.iter()
+            .filter_map(|(symbol, window)| window.baseline().map(|baseline| (symbol.clone(), baseline)))
+            .collect()
+    }
+    
+    /// Find the configuration for a symbol, resolving aliases and alternative notations
+    pub fn symbol_config(&self, symbol: &str) -> Result<&Symbol> {
+        self.symbols.iter()
+            .find(|s| s.name == symbol)
+            .or_else(|| self.symbols.iter().find(|s| s.matches(symbol)))
+            .ok_or_else(|| anyhow::anyhow!("Symbol {} not configured", symbol))
+    }
+    
+    /// Get current price for a symbol from cache or fetch fresh, within the configured deadline
     pub async fn get_current_price(&self, symbol: &str) -> Result<PriceData> {
+        let deadline = (self.settings.price_deadline_ms > 0)
+            .then(|| Duration::from_millis(self.settings.price_deadline_ms));
+        self.get_current_price_within(symbol, deadline).await
+    }
+    
+    /// Get current price for a symbol, giving up on a live fetch once `deadline` has passed
+    ///
+    /// On timeout the last cached price is returned however old it is, and
+    /// without one the read fails with `OracleError::DeadlineExceeded`. The
+    /// live fetch verifying a cache hit is held to the same deadline.
+    /// Always-live symbols skip the cache on both counts; their fresh result
+    /// is still cached for other consumers.
+    pub async fn get_current_price_within(&self, symbol: &str, deadline: Option<Duration>) -> Result<PriceData> {
+        let started = Instant::now();
+        
+        // Find symbol configuration
+        let symbol_config = self.symbol_config(symbol)?;
+        
         // Try cache first
-        if let Ok(Some(cached_price)) = self.price_cache.get_price(symbol).await {
-            // Check if price is not stale (within last 5 seconds)
-            if cached_price.is_fresh(Duration::from_secs(5)) {
-                return Ok(cached_price);
+        let cached = self.price_cache.get_price(&symbol_config.name).await.ok().flatten();
+        if let Some(cached_price) = &cached {
+            let fresh = Duration::from_secs(CACHE_FRESHNESS_SECS);
+            let grace = Duration::from_secs(self.settings.stale_while_revalidate_secs);
+            
+            match cache_use(symbol_config, cached_price, fresh, grace) {
+                CacheUse::Fresh | CacheUse::Revalidate if symbol_config.verify_cache_bp.is_some() => {
+                    let remaining = deadline.map(|deadline| deadline.saturating_sub(started.elapsed()));
+                    let live = self.fetch_and_aggregate_price(symbol_config);
+                    return Ok(verify_cached_price(&self.price_cache, symbol_config, cached_price, live, remaining).await);
+                },
+                CacheUse::Fresh => return Ok(cached_price.clone()),
+                CacheUse::Revalidate => {
+                    self.spawn_revalidation(symbol_config).await;
+                    return Ok(cached_price.clone());
+                },
+                CacheUse::Miss => {},
             }
         }
         
-        // Find symbol configuration
-        let symbol_config = self.symbols.iter()
-            .find(|s| s.name == symbol)
-            .ok_or_else(|| anyhow::anyhow!("Symbol {} not configured", symbol))?;
+        // Fetch fresh price, in whatever is left of the budget
+        let Some(deadline) = deadline else {
+            return self.fetch_and_aggregate_price(symbol_config).await;
+        };
+        let remaining = deadline.saturating_sub(started.elapsed());
+        match tokio::time::timeout(remaining, self.fetch_and_aggregate_price(symbol_config)).await {
+            Ok(result) => result,
+            Err(_) => match cached.filter(|_| !symbol_config.always_live) {
+                Some(cached_price) => {
+                    warn!("Fetch for {} missed its {}ms deadline, serving cached price from {}",
+                        symbol_config.name, deadline.as_millis(), cached_price.timestamp);
+                    Ok(cached_price)
+                },
+                None => Err(OracleError::DeadlineExceeded {
+                    symbol: symbol_config.name.clone(),
+                    deadline_ms: deadline.as_millis() as u64,
+                }.into()),
+            },
+        }
+    }
+    
+    /// Switch a source off or back on for every replica, returning the sources now disabled
+    ///
+    /// The change is persisted to Redis first so it is not applied locally
+    /// unless the other replicas will see it too.
+    pub async fn set_source_disabled(&self, source: PriceSource, disabled: bool) -> Result<Vec<PriceSource>> {
+        self.price_cache.set_source_disabled(&source, disabled).await?;
         
-        // Fetch fresh price
-        self.fetch_and_aggregate_price(symbol_config).await
+        let mut local = self.disabled_sources.write().await;
+        if disabled {
+            warn!("Source {:?} disabled by kill-switch", source);
+            local.insert(source);
+        } else {
+            info!("Source {:?} re-enabled", source);
+            local.remove(&source);
+        }
+        Ok(sorted_sources(&local))
     }
     
-    /// Get prices for all configured symbols
-    pub async fn get_all_prices(&self) -> HashMap<String, PriceData> {
-        let mut prices = HashMap::new();
+    /// Sources this replica currently skips
+    pub async fn disabled_sources(&self) -> Vec<PriceSource> {
+        sorted_sources(&*self.disabled_sources.read().await)
+    }
+    
+    /// Silence outlier and manipulation alerts for a source on every replica, for
+    /// `ttl_secs` or until unmuted; returns the mutes now in force with their expiry
+    ///
+    /// Muted alerts are still logged, only at a lower level.
+    pub async fn set_source_muted(&self, source: PriceSource, ttl_secs: Option<u64>) -> Result<HashMap<PriceSource, i64>> {
+        let until = ttl_secs.map(|ttl| chrono::Utc::now().timestamp().saturating_add(ttl as i64));
+        self.price_cache.set_source_muted(&source, until).await?;
         
-        for symbol in &self.symbols {
-            if let Ok(price) = self.get_current_price(&symbol.name).await {
-                prices.insert(symbol.name.clone(), price);
-            }
+        let mut local = self.muted_sources.write().await;
+        match until {
+            Some(until) => {
+                info!("Alerts for source {:?} muted until {}", source, until);
+                local.insert(source, until);
+            },
+            None => {
+                info!("Alerts for source {:?} unmuted", source);
+                local.remove(&source);
+            },
         }
+        Ok(active_mutes(&local, chrono::Utc::now().timestamp()))
+    }
+    
+    /// Sources whose alerts are muted, with the unix time each mute expires
+    pub async fn muted_sources(&self) -> HashMap<PriceSource, i64> {
+        active_mutes(&*self.muted_sources.read().await, chrono::Utc::now().timestamp())
+    }
+    
+    /// Load the kill-switch set, then keep mirroring changes made through any replica
+    ///
+    /// If Redis can't be read the last known set stays in force, so a
+    /// disabled source is never silently re-enabled by an outage.
+    pub async fn watch_disabled_sources(&self) {
+        self.sync_disabled_sources().await;
+        self.sync_muted_sources().await;
         
-        prices
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(DISABLED_SOURCES_SYNC_INTERVAL);
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                manager.sync_disabled_sources().await;
+                manager.sync_muted_sources().await;
+            }
+        });
     }
     
-    /// Get health status for all oracles
-    pub async fn get_health_status(&self) -> HashMap<String, OracleHealth> {
-        self.health_status.read().await.clone()
+    async fn sync_muted_sources(&self) {
+        match self.price_cache.get_muted_sources().await {
+            Ok(muted) => {
+                let muted = active_mutes(&muted, chrono::Utc::now().timestamp());
+                let mut local = self.muted_sources.write().await;
+                if *local != muted {
+                    info!("Muted sources now {:?}", sorted_sources(&muted.keys().cloned().collect()));
+                    *local = muted;
+                }
+            },
+            Err(e) => warn!("Failed to read muted sources, keeping last known set: {}", e),
+        }
     }
     
-    /// Update health status for a symbol
-    async fn update_health_status(&self, symbol: &str, is_healthy: bool) {
-        let mut health = self.health_status.write().await;
-        if let Some(status) = health.get_mut(symbol) {
-            status.update(is_healthy);
+    async fn sync_disabled_sources(&self) {
+        match self.price_cache.get_disabled_sources().await {
+            Ok(disabled) => {
+                let mut local = self.disabled_sources.write().await;
+                if *local != disabled {
+                    info!("Disabled sources now {:?}", sorted_sources(&disabled));
+                    *local = disabled;
+                }
+            },
+            Err(e) => warn!("Failed to read disabled sources, keeping last known set: {}", e),
         }
     }
-}
-
-// Implement Clone for OracleManager to enable sharing across async tasks
-impl Clone for OracleManager {
-    fn clone(&self) -> Self {
-        Self {
-            pyth_client: self.pyth_client.clone(),
-            switchboard_client: self.switchboard_client.clone(),
-            price_aggregator: self.price_aggregator.clone(),
-            price_cache: self.price_cache.clone(),
-            health_status: self.health_status.clone(),
-            symbols: self.symbols.clone(),
-            is_running: self.is_running.clone(),
+    
+    /// Last cached price for a symbol, without fetching; `None` if nothing is cached
+    pub async fn get_cached_price(&self, symbol: &str) -> Option<PriceData> {
+        let symbol_config = self.symbol_config(symbol).ok()?;
+        
+        match self.price_cache.get_price(&symbol_config.name).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Failed to read cached price for {}: {}", symbol_config.name, e);
+                None
+            }
+        }
+    }
+    
+    /// Refresh a symbol in the background, at most one refresh per symbol at a time
+    async fn spawn_revalidation(&self, symbol: &Symbol) {
+        if !self.revalidating.write().await.insert(symbol.name.clone()) {
+            return;
         }
+        
+        let manager = self.clone();
+        let symbol = symbol.clone();
+        tokio::spawn(async move {
+            // Failures are logged and recorded in health status by the cycle itself
+            let _ = manager.run_fetch_cycle(&symbol, Duration::ZERO).await;
+            manager.revalidating.write().await.remove(&symbol.name);
+        });
+    }
+    
+    /// Fetch and aggregate a caller-supplied symbol without adding it to config
+    ///
+    /// Nothing is cached, broadcast or recorded in health, metrics or baselines.
+    /// Fetches share the same concurrency permits as the configured symbols.
+    pub async fn get_adhoc_price(&self, symbol: &Symbol) -> Result<PriceData> {
+        validate_adhoc_symbol(symbol)?;
+        
+        let prices: Vec<PriceData> = self.fetch_source_prices(symbol).await
+            .into_iter()
+            .filter_map(|(_, result)| result.ok())
+            .collect();
+        
+        if prices.is_empty() {
+            anyhow::bail!("No price sources available for {}", symbol.name);
+        }
+        
+        self.price_aggregator.aggregate_with_fallback(&prices, symbol)
+    }
+    
+    /// Fetch and parse a single candidate feed account, for vetting addresses before they go into config
+    ///
+    /// Runs the same client checks as a regular fetch: magic number or
+    /// discriminator, layout, status and staleness.
+    pub async fn validate_feed(&self, source: &PriceSource, address: &str) -> Result<PriceData> {
+        let _permit = self.fetch_permits.acquire().await
+            .expect("fetch semaphore closed");
+        
+        match source {
+            PriceSource::Pyth => self.pyth_client.get_price(address, DEFAULT_MAX_STALENESS_SECS).await,
+            PriceSource::Switchboard => self.switchboard_client.get_price(address, None, DEFAULT_MAX_STALENESS_SECS, 0).await
+                .and_then(|price| resolve_zero_scale(price, None)),
+            PriceSource::Aggregated | PriceSource::Internal => {
+                anyhow::bail!("{:?} is not an on-chain feed source", source)
+            },
+        }
+    }
+    
+    /// Get a symbol's price converted into another quote currency
+    ///
+    /// Uses a configured `QUOTE/TARGET` or `TARGET/QUOTE` cross, which must be
+    /// within its own staleness limit.
+    pub async fn get_converted_price(&self, symbol: &str, quote: &str) -> Result<ConvertedPrice> {
+        let symbol_config = self.symbol_config(symbol)?;
+        let (base_currency, quote_currency) = symbol_config.base_quote()
+            .ok_or_else(|| anyhow::anyhow!("Cannot tell the quote currency of {}", symbol_config.name))?;
+        let target = quote.trim().to_ascii_uppercase();
+        
+        let price = self.get_current_price(&symbol_config.name).await?;
+        let mut path = vec![ConversionLeg { symbol: symbol_config.name.clone(), inverted: false }];
+        if target == quote_currency {
+            return Ok(ConvertedPrice { price, path });
+        }
+        
+        // TARGET/QUOTE is divided out, QUOTE/TARGET multiplied in
+        let candidates = [
+            (format!("{}/{}", target, quote_currency), true),
+            (format!("{}/{}", quote_currency, target), false),
+        ];
+        let (leg_config, inverted) = candidates.iter()
+            .find_map(|(name, inverted)| self.symbol_config(name).ok().map(|config| (config, *inverted)))
+            .ok_or_else(|| anyhow::anyhow!(
+                "No {}/{} or {}/{} cross configured to convert {} into {}",
+                target, quote_currency, quote_currency, target, symbol_config.name, target
+            ))?;
+        
+        let leg = self.get_current_price(&leg_config.name).await
+            .map_err(|e| anyhow::anyhow!("Missing {} leg: {}", leg_config.name, e))?;
+        if !leg.is_fresh(Duration::from_secs(leg_config.max_staleness.max(0) as u64)) {
+            anyhow::bail!("{} leg is stale: last update at {}, max staleness {}s",
+                leg_config.name, leg.timestamp, leg_config.max_staleness);
+        }
+        
+        let converted_symbol = format!("{}/{}", base_currency, target);
+        let converted = cross_price(&price, &leg, inverted, &converted_symbol)?;
+        path.push(ConversionLeg { symbol: leg_config.name.clone(), inverted });
+        
+        Ok(ConvertedPrice { price: converted, path })
+    }
+    
+    /// Fetch fresh source prices and aggregate them with a full derivation trace
+    ///
+    /// Always uses the consensus strategy and bypasses the cache, so the trace
+    /// describes exactly the price returned alongside it.
+    pub async fn explain_price(&self, symbol: &str) -> Result<(PriceData, AggregationTrace)> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        let prices: Vec<PriceData> = self.fetch_source_prices(symbol_config).await
+            .into_iter()
+            .filter_map(|(_, result)| result.ok())
+            .collect();
+        if prices.is_empty() {
+            anyhow::bail!("No price sources available for {}", symbol_config.name);
+        }
+        
+        self.price_aggregator.aggregate_prices_traced(&prices, symbol_config)
+    }
+    
+    /// Compute realized volatility for a symbol over the trailing window
+    pub async fn get_volatility(&self, symbol: &str, window_secs: i64) -> Result<VolatilityStats> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        let since = chrono::Utc::now().timestamp() - window_secs;
+        let history: Vec<PriceData> = self.price_cache.get_price_history(&symbol_config.name, self.settings.max_history_records).await?
+            .into_iter()
+            .filter(|p| p.timestamp >= since)
+            .collect();
+        
+        Ok(self.price_aggregator.calculate_volatility(&history))
+    }
+    
+    /// A symbol's stored history newest first, empty for a symbol with none yet
+    pub async fn get_price_history(&self, symbol: &str, limit: usize, since: Option<i64>) -> Result<Vec<PriceData>> {
+        self.price_history_pages(symbol, limit, since)?.try_concat().await
+    }
+    
+    /// Stream a symbol's stored history newest first, reading Redis a page at a time
+    ///
+    /// Each item is one page, so callers can forward rows before the rest of
+    /// the range has been read.
+    pub fn price_history_pages(
+        &self,
+        symbol: &str,
+        limit: usize,
+        since: Option<i64>,
+    ) -> Result<impl Stream<Item = Result<Vec<PriceData>>> + Send + 'static> {
+        let name = self.symbol_config(symbol)?.name.clone();
+        let cache = self.price_cache.clone();
+        
+        Ok(paginate_history(limit, move |offset, count| {
+            let cache = cache.clone();
+            let name = name.clone();
+            async move { cache.get_price_history_page(&name, since, offset, count).await }
+        }))
+    }
+    
+    /// Build OHLC candles for a symbol from its stored price history
+    pub async fn get_candles(&self, symbol: &str, interval_secs: i64, limit: usize) -> Result<Vec<Candle>> {
+        let symbol_config = self.symbol_config(symbol)?;
+        
+        let history = self.price_cache.get_price_history(&symbol_config.name, self.settings.max_history_records).await?;
+        Ok(self.price_aggregator.build_candles(&history, interval_secs, limit))
+    }
+    
+    /// Get prices for all configured symbols
+    pub async fn get_all_prices(&self) -> HashMap<String, PriceData> {
+        self.get_all_prices_detailed().await.prices
+    }
+    
+    /// Get prices for all configured symbols, keeping the error for any that failed
+    pub async fn get_all_prices_detailed(&self) -> AllPrices {
+        let mut result = AllPrices::default();
+        
+        for symbol in &self.symbols {
+            match self.get_current_price(&symbol.name).await {
+                Ok(price) => {
+                    result.prices.insert(symbol.name.clone(), price);
+                },
+                Err(e) => {
+                    result.errors.insert(symbol.name.clone(), e.to_string());
+                },
+            }
+        }
+        
+        result
+    }
+    
+    /// Get health status for all oracles
+    pub async fn get_health_status(&self) -> HashMap<String, OracleHealth> {
+        self.health_status.read().await.clone()
+    }
+    
+    /// Periodically append every symbol's health to its Redis history
+    ///
+    /// Does nothing with `health_snapshot_interval_secs` at 0. A failed write
+    /// only loses that snapshot; the next tick tries again.
+    pub fn record_health_history(&self) {
+        if self.settings.health_snapshot_interval_secs == 0 {
+            return;
+        }
+        
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_secs(manager.settings.health_snapshot_interval_secs);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let now = chrono::Utc::now().timestamp();
+                for (symbol, health) in manager.get_health_status().await {
+                    let snapshot = HealthSnapshot::new(&health, now);
+                    if let Err(e) = manager.price_cache
+                        .push_health_snapshot(&symbol, &snapshot, manager.settings.health_history_len).await
+                    {
+                        warn!("Failed to record health snapshot for {}: {}", symbol, e);
+                    }
+                }
+            }
+        });
+    }
+    
+    /// Periodically rank each symbol's sources by their rolling fetch health
+    ///
+    /// Better sources are fetched first and keep their full aggregation
+    /// weight; the rest are scaled down by their score relative to the best.
+    /// Does nothing when `source_priority_interval_secs` is 0.
+    pub fn rank_sources_periodically(&self) {
+        if self.settings.source_priority_interval_secs == 0 {
+            return;
+        }
+        
+        let manager = self.clone();
+        tokio::spawn(async move {
+            let period = Duration::from_secs(manager.settings.source_priority_interval_secs);
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                manager.rank_sources().await;
+            }
+        });
+    }
+    
+    /// Re-rank every symbol's sources and push the new weights to the aggregator
+    async fn rank_sources(&self) {
+        for symbol in &self.symbols {
+            let health: Vec<(PriceSource, OracleHealth)> = {
+                let all = self.source_health.read().await;
+                sources_to_fetch(symbol, &HashSet::new()).into_iter()
+                    .map(|source| {
+                        let health = all.get(&(symbol.name.clone(), source.clone())).cloned().unwrap_or_default();
+                        (source, health)
+                    })
+                    .collect()
+            };
+            let ranking = source_ranking(health);
+            
+            self.price_aggregator.set_source_weights(
+                &symbol.name,
+                ranking.iter().map(|p| (p.source.clone(), p.weight)).collect(),
+            );
+            
+            let order: Vec<PriceSource> = ranking.iter().map(|p| p.source.clone()).collect();
+            let previous = self.source_priority.write().await.insert(symbol.name.clone(), ranking);
+            let previous_order: Option<Vec<PriceSource>> = previous
+                .map(|ranking| ranking.into_iter().map(|p| p.source).collect());
+            if previous_order.is_some_and(|previous| previous != order) {
+                info!("Source priority for {} is now {:?}", symbol.name, order);
+            }
+        }
+    }
+    
+    /// Recent health snapshots for a symbol, newest first
+    pub async fn get_health_history(&self, symbol: &str, limit: usize) -> Result<Vec<HealthSnapshot>> {
+        let symbol_config = self.symbol_config(symbol)?;
+        let limit = limit.min(self.settings.health_history_len);
+        self.price_cache.get_health_history(&symbol_config.name, limit).await
+    }
+    
+    /// Update health status for a symbol
+    async fn update_health_status(&self, symbol: &str, is_healthy: bool) {
+        let mut health = self.health_status.write().await;
+        if let Some(status) = health.get_mut(symbol) {
+            status.update(is_healthy);
+        }
+    }
+}
+
+/// Consensus derivation recorded for one fetch cycle
+#[derive(Debug, Clone, Serialize)]
+pub struct TracedCycle {
+    pub timestamp: i64,                        // Timestamp of the published aggregate
+    pub price: f64,                            // Price the cycle published
+    pub strategy: Option<AggregationStrategy>, // Strategy that produced it
+    pub trace: AggregationTrace,
+}
+
+/// Individual source readings for a symbol next to the aggregate built from them
+#[derive(Debug, Clone)]
+pub struct SourceDiff {
+    pub symbol: String,
+    pub readings: Vec<(PriceSource, Result<PriceData, String>)>,
+    pub aggregated: Result<PriceData, String>,
+    pub baseline: Option<Baseline>,
+    pub priority: Vec<SourcePriority>, // Current health ranking, empty when ranking is off
+}
+
+/// A source's standing for a symbol, from its rolling fetch health
+#[derive(Debug, Clone, Serialize)]
+pub struct SourcePriority {
+    pub source: PriceSource,
+    pub success_rate: f64,
+    pub average_latency_ms: f64,
+    pub score: f64,  // Success rate discounted by latency
+    pub weight: f64, // Multiplier on the source's confidence weight in aggregation
+}
+
+/// A price converted into another quote currency and the legs it went through
+#[derive(Debug, Clone)]
+pub struct ConvertedPrice {
+    pub price: PriceData,
+    pub path: Vec<ConversionLeg>,
+}
+
+/// One symbol in a conversion path; `inverted` legs were divided out
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversionLeg {
+    pub symbol: String,
+    pub inverted: bool,
+}
+
+/// Prices for every configured symbol plus the reason any symbol is missing
+#[derive(Debug, Clone, Default)]
+pub struct AllPrices {
+    pub prices: HashMap<String, PriceData>,
+    pub errors: HashMap<String, String>,
+}
+
+// Implement Clone for OracleManager to enable sharing across async tasks
+impl Clone for OracleManager {
+    fn clone(&self) -> Self {
+        Self {
+            pyth_client: self.pyth_client.clone(),
+            switchboard_client: self.switchboard_client.clone(),
+            price_aggregator: self.price_aggregator.clone(),
+            price_cache: self.price_cache.clone(),
+            health_status: self.health_status.clone(),
+            symbols: self.symbols.clone(),
+            settings: self.settings.clone(),
+            broadcast_sender: self.broadcast_sender.clone(),
+            last_broadcast: self.last_broadcast.clone(),
+            last_publish: self.last_publish.clone(),
+            baselines: self.baselines.clone(),
+            traces: self.traces.clone(),
+            revalidating: self.revalidating.clone(),
+            disabled_sources: self.disabled_sources.clone(),
+            muted_sources: self.muted_sources.clone(),
+            source_timestamps: self.source_timestamps.clone(),
+            source_health: self.source_health.clone(),
+            source_priority: self.source_priority.clone(),
+            alert_sink: self.alert_sink.clone(),
+            metrics: self.metrics.clone(),
+            fetch_permits: self.fetch_permits.clone(),
+            draining: self.draining.clone(),
+            is_running: self.is_running.clone(),
+        }
+    }
+}
+
+/// Run a task, respawning it after a panic or an exit while the service is running
+///
+/// Restarts back off exponentially from `backoff`. A run that outlasts the
+/// current backoff counts as recovered, resetting the restart count. Returns
+/// false once the task has been restarted `max_restarts` times in a row and
+/// fails again, true when it ends because the service stopped.
+async fn supervise<F, Fut>(
+    name: &str,
+    is_running: Arc<RwLock<bool>>,
+    max_restarts: u32,
+    backoff: Duration,
+    mut spawn: F,
+) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut restarts = 0;
+    
+    loop {
+        let started = Instant::now();
+        let result = tokio::spawn(spawn()).await;
+        if !*is_running.read().await {
+            return true;
+        }
+        
+        // Long-lived runs were healthy; only a crash loop should use up the budget
+        if restarts > 0 && started.elapsed() > backoff.saturating_mul(1 << restarts.min(6)) {
+            restarts = 0;
+        }
+        
+        match result {
+            Ok(()) => error!("{} exited unexpectedly", name),
+            Err(e) if e.is_panic() => error!("{} panicked: {}", name, panic_message(e.into_panic())),
+            Err(e) => error!("{} was cancelled: {}", name, e),
+        }
+        
+        if restarts >= max_restarts {
+            error!("{} failed after {} restarts, not restarting", name, restarts);
+            return false;
+        }
+        
+        let delay = backoff.saturating_mul(1 << restarts.min(6));
+        restarts += 1;
+        warn!("Restarting {} in {:?} (attempt {}/{})", name, delay, restarts, max_restarts);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Read up to `limit` history entries through `fetch_page(offset, count)`,
+/// stopping early at an empty or short page
+fn paginate_history<F, Fut>(limit: usize, fetch_page: F) -> impl Stream<Item = Result<Vec<PriceData>>> + Send + 'static
+where
+    F: Fn(usize, usize) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Vec<PriceData>>> + Send + 'static,
+{
+    stream::try_unfold(0usize, move |offset| {
+        let count = HISTORY_PAGE_SIZE.min(limit.saturating_sub(offset));
+        let page = (count > 0).then(|| fetch_page(offset, count));
+        async move {
+            let Some(page) = page else {
+                return Ok(None);
+            };
+            
+            let page = page.await?;
+            if page.is_empty() {
+                return Ok(None);
+            }
+            
+            // A short page means the range is exhausted
+            let next = if page.len() < count { limit } else { offset + page.len() };
+            Ok(Some((page, next)))
+        }
+    })
+}
+
+/// Best-effort text of a panic payload
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// Sources to fetch for a symbol: those it allows, minus any switched off
+fn sources_to_fetch(symbol: &Symbol, disabled: &HashSet<PriceSource>) -> Vec<PriceSource> {
+    [PriceSource::Pyth, PriceSource::Switchboard].into_iter()
+        .filter(|source| symbol.uses_source(source) && !disabled.contains(source))
+        .collect()
+}
+
+/// Sources ranked best first by success rate discounted for latency
+///
+/// Equal scores keep their given order. Weights are each score relative to
+/// the best, floored at `MIN_PRIORITY_WEIGHT`.
+fn source_ranking(health: Vec<(PriceSource, OracleHealth)>) -> Vec<SourcePriority> {
+    let mut ranking: Vec<SourcePriority> = health.into_iter()
+        .map(|(source, health)| {
+            let success_rate = health.success_rate();
+            SourcePriority {
+                source,
+                success_rate,
+                average_latency_ms: health.average_latency,
+                score: success_rate / (1.0 + health.average_latency / PRIORITY_LATENCY_REFERENCE_MS),
+                weight: 1.0,
+            }
+        })
+        .collect();
+    ranking.sort_by(|a, b| b.score.total_cmp(&a.score));
+    
+    let best = ranking.first().map_or(0.0, |p| p.score);
+    if best > 0.0 {
+        for priority in &mut ranking {
+            priority.weight = (priority.score / best).max(MIN_PRIORITY_WEIGHT);
+        }
+    }
+    ranking
+}
+
+/// Put `sources` in ranking order; sources the ranking doesn't know go last
+fn order_sources(sources: &mut [PriceSource], ranking: &[SourcePriority]) {
+    sources.sort_by_key(|source| {
+        ranking.iter().position(|p| &p.source == source).unwrap_or(usize::MAX)
+    });
+}
+
+/// Keep at most `max_sources` of `sources`, already in priority order
+///
+/// The symbol's reference source is always kept so capping never drops the
+/// price the others are judged against. `None` or 0 keeps every source.
+fn cap_sources(sources: &mut Vec<PriceSource>, max_sources: Option<usize>, reference: Option<&PriceSource>) {
+    let Some(max_sources) = max_sources.filter(|&n| n > 0) else {
+        return;
+    };
+    if let Some(i) = reference.and_then(|reference| sources.iter().position(|s| s == reference)) {
+        let reference = sources.remove(i);
+        sources.insert(0, reference);
+    }
+    sources.truncate(max_sources);
+}
+
+/// Sources in a stable order for responses and logs
+fn sorted_sources(sources: &HashSet<PriceSource>) -> Vec<PriceSource> {
+    let mut sorted: Vec<PriceSource> = sources.iter().cloned().collect();
+    sorted.sort_by_key(|source| format!("{:?}", source));
+    sorted
+}
+
+/// The last timestamp a source reported for a symbol, and how many fetches
+/// in a row have reported nothing newer
+#[derive(Debug, Clone, Copy, Default)]
+struct TimestampWatch {
+    last: i64,
+    repeats: u32,
+}
+
+impl TimestampWatch {
+    /// Record a fetched timestamp, returning the current run of repeats
+    fn observe(&mut self, timestamp: i64) -> u32 {
+        if timestamp > self.last {
+            self.last = timestamp;
+            self.repeats = 0;
+        } else {
+            self.repeats = self.repeats.saturating_add(1);
+        }
+        self.repeats
+    }
+}
+
+/// Mutes that have not yet expired at unix time `now`
+fn active_mutes(muted: &HashMap<PriceSource, i64>, now: i64) -> HashMap<PriceSource, i64> {
+    muted.iter()
+        .filter(|(_, &until)| until > now)
+        .map(|(source, &until)| (source.clone(), until))
+        .collect()
+}
+
+/// Hold one of `permits` for the duration of `fetch`, waiting for it first
+///
+/// The wait is not part of the fetch, so it doesn't count towards the
+/// latency `track_source_health` records for the source.
+fn with_permit<'a>(permits: &'a Semaphore, fetch: BoxFuture<'a, Result<PriceData>>) -> BoxFuture<'a, Result<PriceData>> {
+    Box::pin(async move {
+        // The semaphore is never closed
+        let _permit = permits.acquire().await
+            .expect("fetch semaphore closed");
+        fetch.await
+    })
+}
+
+/// Run every source fetch at once, so a cycle takes as long as the slowest
+/// source rather than the sum; failures are kept alongside successes
+///
+/// Once the prices fetched so far are `enough`, the remaining fetches are
+/// dropped, cancelling them, and only the finished ones are returned. Results
+/// keep the order of `fetches` either way.
+async fn fetch_concurrently(
+    fetches: Vec<(PriceSource, BoxFuture<'_, Result<PriceData>>)>,
+    enough: impl Fn(&[PriceData]) -> bool,
+) -> Vec<(PriceSource, Result<PriceData>)> {
+    let total = fetches.len();
+    let mut in_flight: FuturesUnordered<_> = fetches.into_iter()
+        .enumerate()
+        .map(|(i, (source, fetch))| async move { (i, source, fetch.await) })
+        .collect();
+    
+    let mut finished = Vec::with_capacity(total);
+    let mut prices = Vec::with_capacity(total);
+    while let Some((i, source, result)) = in_flight.next().await {
+        if let Ok(price) = &result {
+            prices.push(price.clone());
+        }
+        finished.push((i, source, result));
+        
+        if finished.len() < total && enough(&prices) {
+            debug!("Enough sources after {} of {} fetches, cancelling the rest", finished.len(), total);
+            break;
+        }
+    }
+    
+    finished.sort_by_key(|(i, _, _)| *i);
+    finished.into_iter().map(|(_, source, result)| (source, result)).collect()
+}
+
+/// Largest number of readings that are each within the symbol's confidence
+/// limit and all within its max deviation of one another
+fn agreeing_confident_sources(prices: &[PriceData], symbol: &Symbol) -> usize {
+    let mut values: Vec<f64> = prices.iter()
+        .filter(|p| p.relative_confidence().is_some_and(|ratio| ratio * 10000.0 <= symbol.max_confidence as f64))
+        .map(PriceData::to_decimal)
+        .filter(|value| *value > 0.0)
+        .collect();
+    values.sort_by(f64::total_cmp);
+    
+    // Widest window of sorted values whose spread fits the deviation limit
+    let max_spread = symbol.max_deviation as f64 / 10000.0;
+    let mut best = 0;
+    let mut start = 0;
+    for end in 0..values.len() {
+        while (values[end] - values[start]) / values[start] > max_spread {
+            start += 1;
+        }
+        best = best.max(end - start + 1);
+    }
+    best
+}
+
+/// Which symbols get their own metric series: the configured list, `*` for
+/// all, or by default the first `DEFAULT_METRICS_SYMBOLS` configured symbols
+fn symbol_labels(settings: &ManagerConfig, symbols: &[Symbol]) -> SymbolLabels {
+    match &settings.metrics_symbols {
+        Some(names) if names.iter().any(|name| name == "*") => SymbolLabels::All,
+        Some(names) => SymbolLabels::Allowlist(names.iter().cloned().collect()),
+        None if symbols.len() <= DEFAULT_METRICS_SYMBOLS => SymbolLabels::All,
+        None => SymbolLabels::Allowlist(
+            symbols.iter().take(DEFAULT_METRICS_SYMBOLS).map(|s| s.name.clone()).collect()
+        ),
+    }
+}
+
+/// Record the oldest contributing source's age on an aggregate, and mark it
+/// degraded when even the newest source is older than `degraded_after`;
+/// returns whether that was the case
+fn annotate_source_age(aggregated: &mut PriceData, sources: &[PriceData], now: i64, degraded_after: Duration) -> bool {
+    let ages = sources.iter().map(|p| (now - p.timestamp).max(0));
+    aggregated.max_source_age_secs = ages.clone().max();
+    let stale = ages.min()
+        .is_some_and(|youngest| youngest > degraded_after.as_secs() as i64);
+    aggregated.degraded |= stale;
+    stale
+}
+
+/// How a cached price may be used for a read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CacheUse {
+    /// Serve as-is
+    Fresh,
+    /// Serve, and refresh in the background
+    Revalidate,
+    /// Too old, fetch live
+    Miss,
+}
+
+/// Decide how a cached price of the given age can serve a read of `symbol`
+fn cache_use(symbol: &Symbol, cached: &PriceData, fresh: Duration, grace: Duration) -> CacheUse {
+    if symbol.always_live {
+        CacheUse::Miss
+    } else if cached.is_fresh(fresh) {
+        CacheUse::Fresh
+    } else if !grace.is_zero() && cached.is_fresh(fresh + grace) {
+        CacheUse::Revalidate
+    } else {
+        CacheUse::Miss
+    }
+}
+
+/// Compare a cache hit with a live fetch, serving and re-caching live when they diverge
+///
+/// Guards symbols flagged with `verify_cache_bp` against a poisoned cache
+/// entry at the cost of a live fetch per read. If the live fetch fails or
+/// outlasts `deadline` the cached price is served, as it would have been
+/// without verification.
+async fn verify_cached_price(
+    cache: &PriceCache,
+    symbol: &Symbol,
+    cached: &PriceData,
+    live: impl Future<Output = Result<PriceData>>,
+    deadline: Option<Duration>,
+) -> PriceData {
+    let threshold_bp = symbol.verify_cache_bp.unwrap_or_default();
+    
+    let live = match deadline {
+        Some(deadline) => match tokio::time::timeout(deadline, live).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Cache verification fetch for {} missed its deadline, serving cached price", symbol.name);
+                return cached.clone();
+            }
+        },
+        None => live.await,
+    };
+    let live = match live {
+        Ok(live) => live,
+        Err(e) => {
+            warn!("Cache verification fetch for {} failed, serving cached price: {}", symbol.name, e);
+            return cached.clone();
+        }
+    };
+    
+    if !cache_diverges(cached, &live, threshold_bp) {
+        return cached.clone();
+    }
+    
+    warn!(
+        "Cached price for {} diverges from live beyond {}bp: cached {} at {}, live {} at {}; re-caching live",
+        symbol.name, threshold_bp, cached.to_decimal(), cached.timestamp, live.to_decimal(), live.timestamp
+    );
+    if let Err(e) = cache.set_price(&symbol.name, &live).await {
+        warn!("Failed to re-cache verified price for {}: {}", symbol.name, e);
+    }
+    live
+}
+
+/// Whether a cached price is further than `threshold_bp` from the live one
+fn cache_diverges(cached: &PriceData, live: &PriceData, threshold_bp: u64) -> bool {
+    !live.is_within_deviation(cached.to_decimal(), threshold_bp)
+}
+
+/// Reject ad-hoc symbols whose feed addresses or limits can't be right
+///
+/// Runs before any RPC call, so junk input costs nothing upstream.
+pub fn validate_adhoc_symbol(symbol: &Symbol) -> Result<()> {
+    if symbol.name.trim().is_empty() {
+        anyhow::bail!("Symbol name is required");
+    }
+    if symbol.uses_source(&PriceSource::Pyth) {
+        Pubkey::from_str(&symbol.pyth_feed_id)
+            .map_err(|_| anyhow::anyhow!("Invalid Pyth feed address: {}", symbol.pyth_feed_id))?;
+    }
+    if symbol.uses_source(&PriceSource::Switchboard) {
+        Pubkey::from_str(&symbol.switchboard_aggregator)
+            .map_err(|_| anyhow::anyhow!("Invalid Switchboard aggregator address: {}", symbol.switchboard_aggregator))?;
+    }
+    if symbol.max_deviation == 0 || symbol.max_deviation > 10000 {
+        anyhow::bail!("max_deviation must be between 1 and 10000 basis points");
+    }
+    Ok(())
+}
+
+/// Check a parsed price sits within `tolerance_bp` of an independent reference
+fn check_against_reference(reading: &PriceData, reference: f64, tolerance_bp: u64) -> Result<()> {
+    if !(reference.is_finite() && reference > 0.0) {
+        anyhow::bail!("Unusable reference price {}", reference);
+    }
+    
+    let deviation_bp = (reading.to_decimal() - reference).abs() / reference * 10000.0;
+    if deviation_bp.is_nan() || deviation_bp > tolerance_bp as f64 {
+        anyhow::bail!("parsed {} is {:.0}bp from reference {} (tolerance {}bp)",
+            reading.to_decimal(), deviation_bp, reference, tolerance_bp);
+    }
+    Ok(())
+}
+
+/// Append to a bounded buffer, evicting the oldest entries beyond `capacity`
+fn push_capped<T>(buffer: &mut VecDeque<T>, item: T, capacity: usize) {
+    buffer.push_back(item);
+    while buffer.len() > capacity {
+        buffer.pop_front();
+    }
+}
+
+/// Fetch interval clamped to the hard floor
+fn effective_fetch_interval(configured_ms: u64) -> Duration {
+    Duration::from_millis(configured_ms.max(MIN_FETCH_INTERVAL_MS))
+}
+
+/// Next fetch interval in adaptive mode given the largest recent price move
+///
+/// Halved while moves reach `adaptive_fast_change_bp`, stretched by a quarter
+/// while they all stay under `adaptive_calm_change_bp`, and held in between,
+/// always within the configured bounds.
+fn adaptive_interval(current: Duration, largest_move_bp: f64, settings: &ManagerConfig) -> Duration {
+    let next = if largest_move_bp >= settings.adaptive_fast_change_bp as f64 {
+        current / 2
+    } else if largest_move_bp < settings.adaptive_calm_change_bp as f64 {
+        current + current / 4
+    } else {
+        current
+    };
+    let (min, max) = adaptive_bounds(settings);
+    next.clamp(min, max)
+}
+
+/// Shortest and longest adaptive fetch intervals, the floor applying to both
+fn adaptive_bounds(settings: &ManagerConfig) -> (Duration, Duration) {
+    let min = effective_fetch_interval(settings.adaptive_min_interval_ms);
+    (min, effective_fetch_interval(settings.adaptive_max_interval_ms).max(min))
+}
+
+/// Decide whether a fetched price is published given the last one that was
+///
+/// Unlike `should_broadcast` the interval is the norm, not a heartbeat: within
+/// it only a move of at least `min_change_bp` gets through, and 0 disables that.
+fn should_publish(
+    last: Option<&(f64, Instant)>,
+    price: f64,
+    now: Instant,
+    interval: Duration,
+    min_change_bp: u64,
+) -> bool {
+    let Some(&(last_price, last_published)) = last else {
+        return true; // Nothing published yet for this symbol
+    };
+    
+    if now.duration_since(last_published) >= interval {
+        return true;
+    }
+    if min_change_bp == 0 || last_price == 0.0 {
+        return false;
+    }
+    
+    let change_bp = (price - last_price).abs() / last_price.abs() * 10000.0;
+    change_bp >= min_change_bp as f64
+}
+
+/// Decide whether a new price warrants a broadcast given the last one sent
+fn should_broadcast(
+    last: Option<&(f64, Instant)>,
+    price: f64,
+    now: Instant,
+    min_change_bp: u64,
+    heartbeat: Duration,
+) -> bool {
+    let Some(&(last_price, last_sent)) = last else {
+        return true; // Nothing sent yet for this symbol
+    };
+    
+    if now.duration_since(last_sent) >= heartbeat {
+        return true;
+    }
+    
+    if last_price == 0.0 {
+        return price != 0.0;
+    }
+    
+    let change_bp = (price - last_price).abs() / last_price.abs() * 10000.0;
+    change_bp > min_change_bp as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    
+    #[tokio::test]
+    async fn test_sources_fetched_concurrently() {
+        let slow_price = |source: PriceSource| async move {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok(PriceData {
+                price: 50000_00000000,
+                confidence: 10_00000000,
+                expo: -8,
+                timestamp: 1000,
+                source,
+                symbol: String::new(),
+                ..Default::default()
+            })
+        };
+        let slow_failure = async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Err(anyhow::anyhow!("account not found"))
+        };
+        
+        let fetches: Vec<(PriceSource, BoxFuture<'_, Result<PriceData>>)> = vec![
+            (PriceSource::Pyth, Box::pin(slow_price(PriceSource::Pyth))),
+            (PriceSource::Switchboard, Box::pin(slow_failure)),
+            (PriceSource::Internal, Box::pin(slow_price(PriceSource::Internal))),
+        ];
+        
+        let started = Instant::now();
+        let readings = fetch_concurrently(fetches, |_| false).await;
+        
+        // Roughly one source's latency, not three
+        assert!(started.elapsed() < Duration::from_millis(500));
+        
+        // One failure doesn't take the other sources down, and order is kept
+        let sources: Vec<_> = readings.iter().map(|(source, _)| source.clone()).collect();
+        assert_eq!(sources, vec![PriceSource::Pyth, PriceSource::Switchboard, PriceSource::Internal]);
+        assert!(readings[0].1.is_ok());
+        assert!(readings[1].1.is_err());
+        assert!(readings[2].1.is_ok());
+    }
+    
+    #[tokio::test]
+    async fn test_fetch_permit_per_source() {
+        let in_flight = Arc::new(AtomicU32::new(0));
+        let peak = Arc::new(AtomicU32::new(0));
+        let price = |source: PriceSource| {
+            let (in_flight, peak) = (in_flight.clone(), peak.clone());
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(PriceData { source, ..Default::default() })
+            }
+        };
+        
+        // Two permits for three sources: the third waits for one to finish
+        let permits = Semaphore::new(2);
+        let fetches: Vec<(PriceSource, BoxFuture<'_, Result<PriceData>>)> = vec![
+            (PriceSource::Pyth, with_permit(&permits, Box::pin(price(PriceSource::Pyth)))),
+            (PriceSource::Switchboard, with_permit(&permits, Box::pin(price(PriceSource::Switchboard)))),
+            (PriceSource::Internal, with_permit(&permits, Box::pin(price(PriceSource::Internal)))),
+        ];
+        let readings = fetch_concurrently(fetches, |_| false).await;
+        
+        assert!(readings.iter().all(|(_, result)| result.is_ok()));
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(permits.available_permits(), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_early_exit_cancels_slow_fetch() {
+        let price = |source: PriceSource, delay_ms: u64, fetched: Arc<AtomicU32>| async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            fetched.fetch_add(1, Ordering::SeqCst);
+            Ok(PriceData {
+                price: 50000_00000000,
+                confidence: 10_00000000,
+                expo: -8,
+                timestamp: 1000,
+                source,
+                symbol: String::new(),
+                ..Default::default()
+            })
+        };
+        let fetched = Arc::new(AtomicU32::new(0));
+        let fetches: Vec<(PriceSource, BoxFuture<'_, Result<PriceData>>)> = vec![
+            (PriceSource::Pyth, Box::pin(price(PriceSource::Pyth, 2000, fetched.clone()))),
+            (PriceSource::Switchboard, Box::pin(price(PriceSource::Switchboard, 10, fetched.clone()))),
+        ];
+        
+        let started = Instant::now();
+        let readings = fetch_concurrently(fetches, |prices| !prices.is_empty()).await;
+        
+        // Returned with the fast source alone; the slow one never completed
+        assert!(started.elapsed() < Duration::from_millis(1000));
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings[0].0, PriceSource::Switchboard);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(fetched.load(Ordering::SeqCst), 1);
+    }
+    
+    #[test]
+    fn test_agreeing_confident_sources() {
+        let symbol = Symbol { max_confidence: 100, max_deviation: 50, ..Default::default() };
+        let price = |price: i64, confidence: u64| PriceData {
+            price,
+            confidence,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // Two tight sources 20bp apart agree
+        let prices = [price(50000_00000000, 10_00000000), price(50100_00000000, 10_00000000)];
+        assert_eq!(agreeing_confident_sources(&prices, &symbol), 2);
+        
+        // 100bp apart is beyond the 50bp limit
+        let prices = [price(50000_00000000, 10_00000000), price(50500_00000000, 10_00000000)];
+        assert_eq!(agreeing_confident_sources(&prices, &symbol), 1);
+        
+        // A 2% confidence band doesn't count toward early exit
+        let prices = [price(50000_00000000, 10_00000000), price(50000_00000000, 1000_00000000)];
+        assert_eq!(agreeing_confident_sources(&prices, &symbol), 1);
+        assert_eq!(agreeing_confident_sources(&[], &symbol), 0);
+    }
+    
+    #[tokio::test]
+    async fn test_history_pagination() {
+        let entry = |timestamp: i64| PriceData {
+            price: 50000_00000000,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let stored: Vec<PriceData> = (0..250).rev().map(entry).collect();
+        
+        // Pages of HISTORY_PAGE_SIZE, the last one short
+        let all = stored.clone();
+        let history: Vec<PriceData> = paginate_history(1000, move |offset, count| {
+            let page = all.iter().skip(offset).take(count).cloned().collect();
+            async move { Ok(page) }
+        }).try_concat().await.unwrap();
+        assert_eq!(history, stored);
+        
+        // A freshly added symbol reads as empty, not as an error
+        let fresh: Vec<PriceData> = paginate_history(1000, |_, _| async { Ok(Vec::new()) })
+            .try_concat().await.unwrap();
+        assert!(fresh.is_empty());
+        
+        // A store failure still surfaces
+        let failed = paginate_history(1000, |_, _| async { Err(anyhow::anyhow!("connection refused")) })
+            .try_concat().await;
+        assert!(failed.is_err());
+    }
+    
+    #[test]
+    fn test_source_age_annotation() {
+        let source = |timestamp: i64| PriceData {
+            price: 50000_00000000,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let now = 10_000;
+        let degraded_after = Duration::from_secs(60);
+        let mut aggregated = source(now);
+        
+        assert!(!annotate_source_age(&mut aggregated, &[source(now - 2), source(now - 90)], now, degraded_after));
+        assert_eq!(aggregated.max_source_age_secs, Some(90));
+        assert!(!aggregated.degraded);
+        
+        // Every source old: the aggregate looks fine but is stale
+        assert!(annotate_source_age(&mut aggregated, &[source(now - 70), source(now - 90)], now, degraded_after));
+        assert_eq!(aggregated.max_source_age_secs, Some(90));
+        assert!(aggregated.degraded);
+        
+        // Already degraded for another reason, but its sources are fresh
+        let mut single = PriceData { degraded: true, ..source(now) };
+        assert!(!annotate_source_age(&mut single, &[source(now - 2)], now, degraded_after));
+        assert!(single.degraded);
+    }
+    
+    #[test]
+    fn test_stale_while_revalidate_window() {
+        let now = chrono::Utc::now().timestamp();
+        let cached = |age: i64| PriceData {
+            price: 50000_00000000,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp: now - age,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let fresh = Duration::from_secs(5);
+        let grace = Duration::from_secs(3);
+        let symbol = Symbol::default();
+        
+        assert_eq!(cache_use(&symbol, &cached(2), fresh, grace), CacheUse::Fresh);
+        assert_eq!(cache_use(&symbol, &cached(7), fresh, grace), CacheUse::Revalidate);
+        assert_eq!(cache_use(&symbol, &cached(20), fresh, grace), CacheUse::Miss);
+        
+        // Without a grace window stale entries always go live
+        assert_eq!(cache_use(&symbol, &cached(7), fresh, Duration::ZERO), CacheUse::Miss);
+    }
+    
+    #[test]
+    fn test_always_live_ignores_cache() {
+        let symbol = Symbol { always_live: true, ..Default::default() };
+        let cached = PriceData {
+            price: 50000_00000000,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // Even a brand new entry inside the grace window goes live
+        let fresh = Duration::from_secs(5);
+        assert_eq!(cache_use(&symbol, &cached, fresh, Duration::from_secs(30)), CacheUse::Miss);
+    }
+    
+    #[test]
+    fn test_cache_divergence() {
+        let price = |price: i64, expo: i32| PriceData {
+            price,
+            confidence: 0,
+            expo,
+            timestamp: 1700000000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let live = price(50000_00000000, -8);
+        
+        // 20bp off: within a 50bp threshold, beyond a 10bp one
+        let cached = price(50100_00000000, -8);
+        assert!(!cache_diverges(&cached, &live, 50));
+        assert!(cache_diverges(&cached, &live, 10));
+        
+        // A poisoned entry is caught whatever its exponent
+        assert!(cache_diverges(&price(5000_000000, -6), &live, 50));
+        assert!(!cache_diverges(&price(50000_000000, -6), &live, 0));
+        assert!(cache_diverges(&price(0, -8), &live, 50));
+    }
+    
+    #[tokio::test]
+    async fn test_verified_cache_read() {
+        let cache = PriceCache::new("redis://127.0.0.1:6379/1", 2).await
+            .expect("Failed to connect to test Redis");
+        let symbol = Symbol {
+            name: "VERIFY/USD".to_string(),
+            verify_cache_bp: Some(50),
+            ..Default::default()
+        };
+        let price = |price: i64| PriceData {
+            price,
+            expo: -8,
+            timestamp: chrono::Utc::now().timestamp(),
+            source: PriceSource::Aggregated,
+            symbol: "VERIFY/USD".to_string(),
+            ..Default::default()
+        };
+        let poisoned = price(5000_00000000);
+        let live = price(50000_00000000);
+        cache.set_price("VERIFY/USD", &poisoned).await.unwrap();
+        
+        // A live fetch past the deadline leaves the cached price to be served
+        let slow = async {
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            Ok::<_, anyhow::Error>(live.clone())
+        };
+        let served = verify_cached_price(&cache, &symbol, &poisoned, slow, Some(Duration::from_millis(20))).await;
+        assert_eq!(served, poisoned);
+        
+        // A diverging live price is served and replaces the cache entry
+        let served = verify_cached_price(&cache, &symbol, &poisoned, async { Ok(live.clone()) }, None).await;
+        assert_eq!(served, live);
+        assert_eq!(cache.get_price("VERIFY/USD").await.unwrap().unwrap().price, live.price);
+        
+        // An agreeing one keeps the cached price
+        let served = verify_cached_price(&cache, &symbol, &live, async { Ok(price(50010_00000000)) }, None).await;
+        assert_eq!(served, live);
+    }
+    
+    #[test]
+    fn test_adhoc_symbol_validation() {
+        let mut symbol = Symbol {
+            name: "JUP/USD".to_string(),
+            pyth_feed_id: "g6eRCbboSwK4tSWngn773RCMexr1APQr4uA9bGZBYfo".to_string(),
+            switchboard_aggregator: "not-an-address".to_string(),
+            max_staleness: 60,
+            max_confidence: 100,
+            max_deviation: 100,
+            ..Default::default()
+        };
+        assert!(validate_adhoc_symbol(&symbol).is_err());
+        
+        // Only the sources actually fetched need valid addresses
+        symbol.sources = Some(vec![PriceSource::Pyth]);
+        assert!(validate_adhoc_symbol(&symbol).is_ok());
+        
+        symbol.max_deviation = 0;
+        assert!(validate_adhoc_symbol(&symbol).is_err());
+    }
+    
+    #[test]
+    fn test_disabled_sources_skipped() {
+        let mut symbol = Symbol {
+            name: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        let none = HashSet::new();
+        let pyth_off: HashSet<PriceSource> = [PriceSource::Pyth].into_iter().collect();
+        
+        assert_eq!(sources_to_fetch(&symbol, &none), vec![PriceSource::Pyth, PriceSource::Switchboard]);
+        assert_eq!(sources_to_fetch(&symbol, &pyth_off), vec![PriceSource::Switchboard]);
+        
+        // The kill-switch only ever narrows the symbol's own allowlist
+        symbol.sources = Some(vec![PriceSource::Pyth]);
+        assert!(sources_to_fetch(&symbol, &pyth_off).is_empty());
+    }
+    
+    #[test]
+    fn test_active_mutes_expire() {
+        let muted: HashMap<PriceSource, i64> = [(PriceSource::Pyth, 100), (PriceSource::Switchboard, 200)]
+            .into_iter()
+            .collect();
+        
+        assert_eq!(active_mutes(&muted, 50).len(), 2);
+        assert_eq!(active_mutes(&muted, 100).keys().collect::<Vec<_>>(), vec![&PriceSource::Switchboard]);
+        assert!(active_mutes(&muted, 200).is_empty());
+    }
+    
+    #[test]
+    fn test_timestamp_watch_counts_repeats() {
+        let mut watch = TimestampWatch::default();
+        assert_eq!(watch.observe(1000), 0);
+        assert_eq!(watch.observe(1000), 1);
+        assert_eq!(watch.observe(1000), 2);
+        
+        // A timestamp going backwards is no progress either
+        assert_eq!(watch.observe(999), 3);
+        
+        // Advancing resets the run
+        assert_eq!(watch.observe(1001), 0);
+        assert_eq!(watch.last, 1001);
+    }
+    
+    #[tokio::test]
+    async fn test_startup_checks_distinguish_failures() {
+        let startup_error = |rpc_url: &'static str, commitment: &'static str, redis_url: &'static str| async move {
+            match OracleManager::new(rpc_url, commitment, redis_url, 1, Vec::new(), ManagerConfig::default()).await {
+                Ok(_) => panic!("expected startup to fail"),
+                Err(e) => e,
+            }
+        };
+        
+        let err = startup_error("not a url", "confirmed", "redis://127.0.0.1:6379").await;
+        assert!(matches!(err.downcast_ref::<OracleError>(), Some(OracleError::ConfigError(_))));
+        
+        let err = startup_error("ws://127.0.0.1:8900", "confirmed", "redis://127.0.0.1:6379").await;
+        assert!(err.to_string().contains("scheme must be http or https"));
+        
+        let err = startup_error("http://127.0.0.1:8899", "eventual", "redis://127.0.0.1:6379").await;
+        assert!(matches!(err.downcast_ref::<OracleError>(), Some(OracleError::ConfigError(_))));
+        
+        // Nothing listens on port 1
+        let err = startup_error("http://127.0.0.1:8899", "confirmed", "redis://127.0.0.1:1").await;
+        assert!(matches!(err.downcast_ref::<OracleError>(), Some(OracleError::CacheError(_))));
+        assert!(err.to_string().contains("Redis unreachable"));
+    }
+    
+    #[tokio::test]
+    async fn test_supervise_respawns_panicked_task() {
+        let is_running = Arc::new(RwLock::new(true));
+        let spawns = Arc::new(AtomicU32::new(0));
+        
+        // First run panics, the respawn stops the service and exits cleanly
+        let survived = supervise("test loop", is_running.clone(), 3, Duration::from_millis(1), || {
+            let spawns = spawns.clone();
+            let is_running = is_running.clone();
+            async move {
+                if spawns.fetch_add(1, Ordering::SeqCst) == 0 {
+                    panic!("forced fetch loop panic");
+                }
+                *is_running.write().await = false;
+            }
+        }).await;
+        
+        assert!(survived);
+        assert_eq!(spawns.load(Ordering::SeqCst), 2);
+    }
+    
+    #[tokio::test]
+    async fn test_supervise_gives_up_after_max_restarts() {
+        let is_running = Arc::new(RwLock::new(true));
+        let spawns = Arc::new(AtomicU32::new(0));
+        
+        let survived = supervise("test loop", is_running, 2, Duration::from_millis(1), || {
+            let spawns = spawns.clone();
+            async move {
+                spawns.fetch_add(1, Ordering::SeqCst);
+                panic!("forced fetch loop panic");
+            }
+        }).await;
+        
+        assert!(!survived);
+        assert_eq!(spawns.load(Ordering::SeqCst), 3);
+    }
+    
+    #[tokio::test]
+    async fn test_supervise_resets_after_healthy_run() {
+        let is_running = Arc::new(RwLock::new(true));
+        let spawns = Arc::new(AtomicU32::new(0));
+        
+        // Every run lasts well past the backoff before crashing, so the single
+        // allowed restart is never used up
+        let survived = supervise("test loop", is_running.clone(), 1, Duration::from_millis(1), || {
+            let spawns = spawns.clone();
+            let is_running = is_running.clone();
+            async move {
+                if spawns.fetch_add(1, Ordering::SeqCst) == 4 {
+                    *is_running.write().await = false;
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                panic!("forced fetch loop panic");
+            }
+        }).await;
+        
+        assert!(survived);
+        assert_eq!(spawns.load(Ordering::SeqCst), 5);
+    }
+    
+    #[test]
+    fn test_self_test_tolerance() {
+        let reading = PriceData {
+            price: 50100_00000000,
+            confidence: 10_00000000,
+            expo: -8,
+            timestamp: 1000,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // 20bp away from the reference
+        assert!(check_against_reference(&reading, 50000.0, 50).is_ok());
+        assert!(check_against_reference(&reading, 50000.0, 10).is_err());
+        
+        // A layout drift typically lands orders of magnitude off
+        let drifted = PriceData { expo: -6, ..reading };
+        assert!(check_against_reference(&drifted, 50000.0, 100).is_err());
+        assert!(check_against_reference(&drifted, 0.0, 100).is_err());
+    }
+    
+    #[test]
+    fn test_fetch_interval_floor() {
+        assert_eq!(effective_fetch_interval(0), Duration::from_millis(MIN_FETCH_INTERVAL_MS));
+        assert_eq!(effective_fetch_interval(50), Duration::from_millis(MIN_FETCH_INTERVAL_MS));
+        assert_eq!(effective_fetch_interval(500), Duration::from_millis(500));
+    }
+    
+    #[test]
+    fn test_source_ranking() {
+        let health = |requests: u64, successes: u64, latency: f64| OracleHealth {
+            total_requests: requests,
+            successful_requests: successes,
+            average_latency: latency,
+            ..Default::default()
+        };
+        
+        // Switchboard is both more reliable and faster, so it leads
+        let ranking = source_ranking(vec![
+            (PriceSource::Pyth, health(10, 5, 500.0)),
+            (PriceSource::Switchboard, health(10, 10, 0.0)),
+        ]);
+        assert_eq!(ranking[0].source, PriceSource::Switchboard);
+        assert_eq!(ranking[0].weight, 1.0);
+        assert!((ranking[1].score - 0.25).abs() < 1e-12);
+        assert!((ranking[1].weight - 0.25).abs() < 1e-12);
+        
+        // A failing source still keeps the minimum weight
+        let ranking = source_ranking(vec![
+            (PriceSource::Pyth, health(10, 0, 100.0)),
+            (PriceSource::Switchboard, health(10, 10, 100.0)),
+        ]);
+        assert_eq!(ranking[1].weight, MIN_PRIORITY_WEIGHT);
+        
+        // No history yet: fixed order, equal weights
+        let ranking = source_ranking(vec![
+            (PriceSource::Pyth, OracleHealth::default()),
+            (PriceSource::Switchboard, OracleHealth::default()),
+        ]);
+        assert_eq!(ranking[0].source, PriceSource::Pyth);
+        assert!(ranking.iter().all(|p| p.weight == 1.0));
+        
+        let mut sources = vec![PriceSource::Pyth, PriceSource::Switchboard];
+        let reversed = source_ranking(vec![
+            (PriceSource::Pyth, health(4, 1, 0.0)),
+            (PriceSource::Switchboard, health(4, 4, 0.0)),
+        ]);
+        order_sources(&mut sources, &reversed);
+        assert_eq!(sources, vec![PriceSource::Switchboard, PriceSource::Pyth]);
+    }
+    
+    #[test]
+    fn test_cap_sources() {
+        let ranked = vec![PriceSource::Switchboard, PriceSource::Pyth];
+        
+        // No cap, or zero, fetches every source
+        let mut sources = ranked.clone();
+        cap_sources(&mut sources, None, None);
+        assert_eq!(sources, ranked);
+        cap_sources(&mut sources, Some(0), None);
+        assert_eq!(sources, ranked);
+        
+        // The best-ranked sources are the ones fetched
+        let mut sources = ranked.clone();
+        cap_sources(&mut sources, Some(1), None);
+        assert_eq!(sources, vec![PriceSource::Switchboard]);
+        
+        // The reference source is never capped away
+        let mut sources = ranked.clone();
+        cap_sources(&mut sources, Some(1), Some(&PriceSource::Pyth));
+        assert_eq!(sources, vec![PriceSource::Pyth]);
+    }
+    
+    #[test]
+    fn test_adaptive_interval() {
+        let settings = ManagerConfig {
+            adaptive_min_interval_ms: 200,
+            adaptive_max_interval_ms: 1000,
+            adaptive_fast_change_bp: 10,
+            adaptive_calm_change_bp: 2,
+            ..Default::default()
+        };
+        let ms = Duration::from_millis;
+        
+        // Volatile: halve, but never under the minimum
+        assert_eq!(adaptive_interval(ms(800), 25.0, &settings), ms(400));
+        assert_eq!(adaptive_interval(ms(300), 10.0, &settings), ms(200));
+        
+        // Quiet: grow by a quarter up to the maximum
+        assert_eq!(adaptive_interval(ms(400), 0.5, &settings), ms(500));
+        assert_eq!(adaptive_interval(ms(900), 0.0, &settings), ms(1000));
+        
+        // In between: hold
+        assert_eq!(adaptive_interval(ms(400), 5.0, &settings), ms(400));
+        
+        // The fetch floor still applies to a lower configured minimum
+        let floored = ManagerConfig { adaptive_min_interval_ms: 10, ..settings };
+        assert_eq!(adaptive_bounds(&floored).0, ms(MIN_FETCH_INTERVAL_MS));
+    }
+    
+    #[test]
+    fn test_broadcast_threshold() {
+        let start = Instant::now();
+        let heartbeat = Duration::from_secs(10);
+        let last = (50000.0, start);
+        
+        // First update for a symbol always goes out
+        assert!(should_broadcast(None, 50000.0, start, 10, heartbeat));
+        
+        // Unchanged or sub-threshold moves are suppressed (10 bp threshold)
+        assert!(!should_broadcast(Some(&last), 50000.0, start, 10, heartbeat));
+        assert!(!should_broadcast(Some(&last), 50040.0, start, 10, heartbeat)); // 8 bp
+        
+        // Moves above the threshold are sent
+        assert!(should_broadcast(Some(&last), 50100.0, start, 10, heartbeat)); // 20 bp
+        
+        // Quiet markets still get a heartbeat
+        assert!(should_broadcast(Some(&last), 50000.0, start + heartbeat, 10, heartbeat));
+    }
+    
+    #[test]
+    fn test_push_capped() {
+        let mut buffer = VecDeque::new();
+        for i in 0..5 {
+            push_capped(&mut buffer, i, 3);
+        }
+        assert_eq!(buffer, VecDeque::from([2, 3, 4]));
+        
+        // A smaller capacity trims the backlog on the next push
+        push_capped(&mut buffer, 5, 2);
+        assert_eq!(buffer, VecDeque::from([4, 5]));
+    }
+    
+    #[test]
+    fn test_publish_interval() {
+        let start = Instant::now();
+        let interval = Duration::from_secs(1);
+        let last = (50000.0, start);
+        let half = start + Duration::from_millis(500);
+        
+        // First price for a symbol always publishes
+        assert!(should_publish(None, 50000.0, start, interval, 50));
+        
+        // Fetches inside the interval are held back, even when the price drifts a little
+        assert!(!should_publish(Some(&last), 50000.0, half, interval, 50));
+        assert!(!should_publish(Some(&last), 50100.0, half, interval, 50)); // 20 bp
+        
+        // A significant move publishes early, unless early publishing is off
+        assert!(should_publish(Some(&last), 50500.0, half, interval, 50)); // 100 bp
+        assert!(!should_publish(Some(&last), 50500.0, half, interval, 0));
+        
+        // Once the interval is up the next fetch publishes
+        assert!(should_publish(Some(&last), 50000.0, start + interval, interval, 50));
     }
 }
\ No newline at end of file
@@ -0,0 +1,219 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use tokio::sync::RwLock;
+
+use crate::types::PriceSource;
+
+/// Upper bounds of the deviation histogram buckets, in basis points
+pub const DEVIATION_BUCKETS_BP: [f64; 10] = [1.0, 2.5, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+/// Upper bounds of the fetch latency histogram buckets, in milliseconds
+pub const FETCH_LATENCY_BUCKETS_MS: [f64; 8] = [10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0];
+
+/// Label shared by every symbol without its own series
+pub const OTHER_SYMBOL_LABEL: &str = "other";
+
+/// Which symbols get their own per-symbol metric series
+///
+/// Every labeled series is kept forever and, once exported to Prometheus,
+/// multiplies by every bucket and label it carries, so hundreds of symbols
+/// means tens of thousands of series. Folding the long tail into
+/// `OTHER_SYMBOL_LABEL` keeps that bounded, at the cost of no longer telling
+/// those symbols apart in metrics; health and the REST endpoints still do.
+#[derive(Debug, Clone)]
+pub enum SymbolLabels {
+    /// A series per symbol; fine for a handful of symbols
+    All,
+    /// Series only for these symbols, the rest fold into "other"
+    Allowlist(HashSet<String>),
+}
+
+impl SymbolLabels {
+    /// Label a symbol's observations are recorded under
+    pub fn label<'a>(&self, symbol: &'a str) -> &'a str {
+        match self {
+            Self::All => symbol,
+            Self::Allowlist(symbols) if symbols.contains(symbol) => symbol,
+            Self::Allowlist(_) => OTHER_SYMBOL_LABEL,
+        }
+    }
+}
+
+/// Outcomes and latency of fetch cycles under one symbol label
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchStats {
+    pub successes: u64,
+    pub failures: u64,
+    pub latency_ms: Histogram,
+}
+
+impl Default for FetchStats {
+    fn default() -> Self {
+        Self {
+            successes: 0,
+            failures: 0,
+            latency_ms: Histogram::new(&FETCH_LATENCY_BUCKETS_MS),
+        }
+    }
+}
+
+/// Fixed-bucket histogram of observed values
+#[derive(Debug, Clone, Serialize)]
+pub struct Histogram {
+    pub buckets: Vec<HistogramBucket>,
+    pub count: u64,
+    pub sum: f64,
+}
+
+/// Number of observations at or below `le` (and above the previous bucket)
+///
+/// The final bucket has no upper bound and catches everything larger.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBucket {
+    pub le: Option<f64>,
+    pub count: u64,
+}
+
+impl Histogram {
+    pub fn new(bounds: &[f64]) -> Self {
+        let buckets = bounds.iter()
+            .map(|&le| HistogramBucket { le: Some(le), count: 0 })
+            .chain(std::iter::once(HistogramBucket { le: None, count: 0 }))
+            .collect();
+        
+        Self {
+            buckets,
+            count: 0,
+            sum: 0.0,
+        }
+    }
+    
+    /// Record a single observation
+    pub fn observe(&mut self, value: f64) {
+        if !value.is_finite() {
+            return;
+        }
+        
+        if let Some(bucket) = self.buckets.iter_mut()
+            .find(|b| b.le.is_none_or(|le| value <= le))
+        {
+            bucket.count += 1;
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// In-memory service metrics
+pub struct Metrics {
+    deviation_bp: RwLock<HashMap<String, Histogram>>,
+    fetches: RwLock<HashMap<String, FetchStats>>,
+    symbol_labels: SymbolLabels,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::with_symbol_labels(SymbolLabels::All)
+    }
+    
+    /// Metrics whose per-symbol series follow `symbol_labels`
+    pub fn with_symbol_labels(symbol_labels: SymbolLabels) -> Self {
+        Self {
+            deviation_bp: RwLock::new(HashMap::new()),
+            fetches: RwLock::new(HashMap::new()),
+            symbol_labels,
+        }
+    }
+    
+    /// Record the outcome and duration of a symbol's fetch cycle
+    pub async fn record_fetch(&self, symbol: &str, success: bool, latency_ms: f64) {
+        let label = self.symbol_labels.label(symbol);
+        let mut fetches = self.fetches.write().await;
+        let stats = fetches.entry(label.to_string()).or_default();
+        
+        if success {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
+        }
+        stats.latency_ms.observe(latency_ms);
+    }
+    
+    /// Snapshot of fetch statistics keyed by symbol label
+    pub async fn fetch_stats(&self) -> HashMap<String, FetchStats> {
+        self.fetches.read().await.clone()
+    }
+    
+    /// Record how far a source's price was from the aggregate, in basis points
+    pub async fn record_deviation(&self, source: &PriceSource, deviation_bp: f64) {
+        let mut histograms = self.deviation_bp.write().await;
+        histograms.entry(format!("{:?}", source))
+            .or_insert_with(|| Histogram::new(&DEVIATION_BUCKETS_BP))
+            .observe(deviation_bp.abs());
+    }
+    
+    /// Snapshot of the deviation histograms keyed by source
+    pub async fn deviation_histograms(&self) -> HashMap<String, Histogram> {
+        self.deviation_bp.read().await.clone()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_histogram_bucketing() {
+        let mut histogram = Histogram::new(&[1.0, 10.0]);
+        
+        histogram.observe(0.5);
+        histogram.observe(1.0);  // Bounds are inclusive
+        histogram.observe(7.0);
+        histogram.observe(250.0);
+        histogram.observe(f64::NAN); // Ignored
+        
+        let counts: Vec<u64> = histogram.buckets.iter().map(|b| b.count).collect();
+        assert_eq!(counts, vec![2, 1, 1]);
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.sum, 258.5);
+        assert!(histogram.buckets.last().unwrap().le.is_none());
+    }
+    
+    #[tokio::test]
+    async fn test_symbol_label_allowlist() {
+        let allowlist = ["BTC/USD".to_string()].into_iter().collect();
+        let metrics = Metrics::with_symbol_labels(SymbolLabels::Allowlist(allowlist));
+        
+        metrics.record_fetch("BTC/USD", true, 40.0).await;
+        metrics.record_fetch("ETH/USD", true, 60.0).await;
+        metrics.record_fetch("DOGE/USD", false, 900.0).await;
+        
+        // Symbols off the allowlist share a single series
+        let stats = metrics.fetch_stats().await;
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats["BTC/USD"].successes, 1);
+        assert_eq!(stats[OTHER_SYMBOL_LABEL].successes, 1);
+        assert_eq!(stats[OTHER_SYMBOL_LABEL].failures, 1);
+        assert_eq!(stats[OTHER_SYMBOL_LABEL].latency_ms.count, 2);
+    }
+    
+    #[tokio::test]
+    async fn test_deviation_labeled_by_source() {
+        let metrics = Metrics::new();
+        
+        metrics.record_deviation(&PriceSource::Pyth, 3.0).await;
+        metrics.record_deviation(&PriceSource::Pyth, -40.0).await;
+        metrics.record_deviation(&PriceSource::Switchboard, 600.0).await;
+        
+        let histograms = metrics.deviation_histograms().await;
+        assert_eq!(histograms["Pyth"].count, 2);
+        assert_eq!(histograms["Pyth"].sum, 43.0);
+        assert_eq!(histograms["Switchboard"].count, 1);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket shared by every caller of a guarded endpoint
+///
+/// Holds up to `capacity` tokens and refills continuously, so bursts up to
+/// the capacity pass and sustained traffic is held to the refill rate.
+#[derive(Debug)]
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `requests` per minute, with bursts up to the same number
+    pub fn per_minute(requests: u32) -> Self {
+        Self::new(requests.max(1) as f64, Duration::from_secs(60))
+    }
+
+    /// Allow `capacity` requests per `period`
+    pub fn new(capacity: f64, period: Duration) -> Self {
+        Self {
+            capacity,
+            refill_per_sec: capacity / period.as_secs_f64(),
+            state: Mutex::new(Bucket {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token if one is available
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut bucket = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let elapsed = now.saturating_duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        bucket.updated = now;
+
+        if bucket.tokens < 1.0 {
+            return false;
+        }
+        bucket.tokens -= 1.0;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_refills() {
+        let limiter = RateLimiter::new(2.0, Duration::from_secs(10));
+        let start = Instant::now();
+
+        // Burst up to capacity, then refuse
+        assert!(limiter.try_acquire_at(start));
+        assert!(limiter.try_acquire_at(start));
+        assert!(!limiter.try_acquire_at(start));
+
+        // One token back every 5 seconds
+        assert!(!limiter.try_acquire_at(start + Duration::from_secs(4)));
+        assert!(limiter.try_acquire_at(start + Duration::from_secs(5)));
+        assert!(!limiter.try_acquire_at(start + Duration::from_secs(5)));
+
+        // Refill never exceeds capacity
+        let later = start + Duration::from_secs(600);
+        assert!(limiter.try_acquire_at(later));
+        assert!(limiter.try_acquire_at(later));
+        assert!(!limiter.try_acquire_at(later));
+    }
+}
\ No newline at end of file
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::aggregator::{AggregationStrategy, Baseline, DeviationBase, ExpoSelection, MedianTieBreak, OutlierMethod};
+
 /// Price data structure used throughout the system
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct PriceData {
     pub price: i64,           // Price in fixed-point notation
     pub confidence: u64,      // Confidence interval
@@ -10,26 +12,172 @@ pub struct PriceData {
     pub timestamp: i64,       // Unix timestamp
     pub source: PriceSource,  // Source of the price data
     pub symbol: String,       // Trading symbol (e.g., "BTC/USD")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<AggregationStrategy>, // Strategy that produced an aggregated price
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_source_age_secs: Option<i64>,      // Age of the oldest source behind an aggregated price
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,                        // Every contributing source was older than the degraded threshold
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oracle_count: Option<u32>,             // Oracles behind a source reading, where the source reports it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence_ratio: Option<f64>,         // Confidence as a fraction of price, set by the client at parse time
 }
 
 /// Price source enumeration
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum PriceSource {
     Pyth,
     Switchboard,
     Aggregated,
+    #[default]
     Internal,
 }
 
+impl std::str::FromStr for PriceSource {
+    type Err = anyhow::Error;
+    
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "pyth" => Ok(Self::Pyth),
+            "switchboard" => Ok(Self::Switchboard),
+            "aggregated" => Ok(Self::Aggregated),
+            "internal" => Ok(Self::Internal),
+            other => anyhow::bail!("Unknown price source: {}", other),
+        }
+    }
+}
+
 /// Symbol configuration for oracle feeds
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,                    // Symbol name (e.g., "BTC/USD")
     pub pyth_feed_id: String,           // Pyth price feed address
     pub switchboard_aggregator: String, // Switchboard aggregator address
     pub max_staleness: i64,             // Maximum age in seconds
+    #[serde(default)]
+    pub pyth_max_staleness: Option<i64>,        // Overrides max_staleness for Pyth readings
+    #[serde(default)]
+    pub switchboard_max_staleness: Option<i64>, // Overrides max_staleness for Switchboard readings
+    #[serde(default)]
+    pub switchboard_zero_scale_expo: Option<i32>, // Expo applied when Switchboard reports scale 0; such readings are rejected when absent
     pub max_confidence: u64,            // Maximum confidence in basis points
     pub max_deviation: u64,             // Maximum deviation in basis points
+    #[serde(default)]
+    pub sources: Option<Vec<PriceSource>>, // Allowlist of sources to aggregate; all when absent
+    #[serde(default)]
+    pub aliases: Vec<String>,              // Alternative notations resolving to this symbol
+    #[serde(default)]
+    pub fallback_chain: Vec<AggregationStrategy>, // Strategies tried in order; plain consensus when empty
+    #[serde(default)]
+    pub fetch_interval_ms: Option<u64>,           // Overrides the manager-wide fetch interval
+    #[serde(default)]
+    pub publish_interval_ms: Option<u64>,         // Overrides the manager-wide publish interval
+    #[serde(default)]
+    pub pyth_price_feed_id: Option<String>,       // Hex Pyth feed id, used to cross-check via Hermes
+    #[serde(default)]
+    pub strict_consensus: bool,                   // Refuse to aggregate unless every live source agrees
+    #[serde(default)]
+    pub reference_source: Option<PriceSource>,    // Sanity anchor; sources beyond max_deviation from it are excluded
+    #[serde(default)]
+    pub always_live: bool,                        // Fetch and aggregate on every read instead of serving from cache
+    #[serde(default)]
+    pub verify_cache_bp: Option<u64>,             // Check cache hits against live; serve and re-cache live beyond this gap
+    #[serde(default)]
+    pub base: Option<String>,                     // Asset being priced; taken from the name when absent
+    #[serde(default)]
+    pub quote: Option<String>,                    // Asset the price is denominated in; taken from the name when absent
+}
+
+/// Canonicalize a symbol notation: uppercase with `/` as the only separator
+///
+/// "btc-usd", "BTC_USD" and "btc/usd" all become "BTC/USD".
+pub fn canonical_symbol(input: &str) -> String {
+    input.trim()
+        .chars()
+        .map(|c| match c {
+            '-' | '_' | ':' | ' ' => '/',
+            c => c.to_ascii_uppercase(),
+        })
+        .collect()
+}
+
+/// Strip separators entirely so "BTCUSD" can match "BTC/USD"
+fn compact_symbol(input: &str) -> String {
+    canonical_symbol(input).replace('/', "")
+}
+
+impl Symbol {
+    /// Check whether a requested notation refers to this symbol (name or alias)
+    pub fn matches(&self, query: &str) -> bool {
+        let query = compact_symbol(query);
+        std::iter::once(&self.name)
+            .chain(self.aliases.iter())
+            .any(|name| compact_symbol(name) == query)
+    }
+    
+    /// Check whether a source is allowed to contribute to this symbol
+    pub fn uses_source(&self, source: &PriceSource) -> bool {
+        match &self.sources {
+            Some(allowed) => allowed.contains(source),
+            None => true,
+        }
+    }
+    
+    /// The (base, quote) assets, from the explicit designation or else a `BASE/QUOTE` name
+    pub fn base_quote(&self) -> Option<(String, String)> {
+        let canonical = canonical_symbol(&self.name);
+        let from_name = canonical.split_once('/');
+        let base = self.base.clone().or_else(|| from_name.map(|(base, _)| base.to_string()))?;
+        let quote = self.quote.clone().or_else(|| from_name.map(|(_, quote)| quote.to_string()))?;
+        Some((base.trim().to_ascii_uppercase(), quote.trim().to_ascii_uppercase()))
+    }
+    
+    /// Whether a price with `base` as the asset being priced is this symbol's reciprocal
+    ///
+    /// Fails when `base` is neither of the symbol's assets, or they're unknown.
+    pub fn inverted_for_base(&self, base: &str) -> Result<bool, OracleError> {
+        let (own_base, own_quote) = self.base_quote().ok_or_else(|| OracleError::ValidationError(
+            format!("{} has no base/quote designation", self.name)
+        ))?;
+        
+        let requested = base.trim().to_ascii_uppercase();
+        if requested == own_base {
+            Ok(false)
+        } else if requested == own_quote {
+            Ok(true)
+        } else {
+            Err(OracleError::ValidationError(format!(
+                "{} is neither the base ({}) nor the quote ({}) of {}", requested, own_base, own_quote, self.name
+            )))
+        }
+    }
+    
+    /// Oldest reading accepted from a source, in seconds: its own limit if set, else `max_staleness`
+    pub fn max_staleness_for(&self, source: &PriceSource) -> i64 {
+        let own = match source {
+            PriceSource::Pyth => self.pyth_max_staleness,
+            PriceSource::Switchboard => self.switchboard_max_staleness,
+            PriceSource::Aggregated | PriceSource::Internal => None,
+        };
+        own.unwrap_or(self.max_staleness)
+    }
+}
+
+/// Reject a symbol list that names the same symbol twice
+///
+/// Names are compared in any notation, so "BTC/USD" and "btc-usd" collide.
+/// A duplicate would get two fetch loops sharing one health entry.
+pub fn check_unique_symbols(symbols: &[Symbol]) -> Result<(), OracleError> {
+    let mut seen: std::collections::HashMap<String, &str> = std::collections::HashMap::new();
+    for symbol in symbols {
+        if let Some(first) = seen.insert(compact_symbol(&symbol.name), &symbol.name) {
+            return Err(OracleError::ConfigError(format!(
+                "Symbol {} is configured more than once (also as {})", symbol.name, first
+            )));
+        }
+    }
+    Ok(())
 }
 
 /// Oracle health status tracking
@@ -103,16 +251,39 @@ impl OracleHealth {
     pub fn set_error(&mut self, error: String) {
         self.last_error = Some(error);
     }
+    
+    /// Mark unhealthy immediately, without waiting for consecutive failures
+    pub fn mark_unhealthy(&mut self, error: String) {
+        self.is_healthy = false;
+        self.set_error(error);
+    }
 }
 
 /// API response structures
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PriceResponse {
     pub symbol: String,
-    pub price: f64,
-    pub confidence: f64,
+    pub price: PriceValue,
+    pub confidence: PriceValue,
+    #[serde(default, serialize_with = "serialize_plain_decimal")]
+    pub confidence_bp: f64, // Confidence relative to price, in basis points
     pub timestamp: i64,
     pub source: PriceSource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<AggregationStrategy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_source_age_secs: Option<i64>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub degraded: bool,
+}
+
+/// Decimal value in an API response: a JSON number by default, or an exact
+/// decimal string for clients that can't tolerate float rounding
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PriceValue {
+    Float(#[serde(serialize_with = "serialize_plain_decimal")] f64),
+    Exact(String),
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -130,6 +301,32 @@ pub struct OracleHealthStatus {
     pub average_latency: f64,
     pub last_update: i64,
     pub consecutive_failures: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<Baseline>, // Rolling mean/std of recent aggregated prices
+}
+
+/// A symbol's health at one point in time, as kept in the health history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthSnapshot {
+    pub timestamp: i64, // When the snapshot was taken
+    pub is_healthy: bool,
+    pub success_rate: f64,
+    pub average_latency: f64, // in milliseconds
+    pub consecutive_failures: u32,
+    pub total_requests: u64,
+}
+
+impl HealthSnapshot {
+    pub fn new(health: &OracleHealth, timestamp: i64) -> Self {
+        Self {
+            timestamp,
+            is_healthy: health.is_healthy,
+            success_rate: health.success_rate(),
+            average_latency: health.average_latency,
+            consecutive_failures: health.consecutive_failures,
+            total_requests: health.total_requests,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -157,16 +354,38 @@ pub enum WsMessage {
         timestamp: i64,
     },
     Subscribe {
+        #[serde(default)]
         symbols: Vec<String>,
+        // Message categories to receive; absent keeps the connection's current set
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        channels: Vec<WsChannel>,
+        // Optional minimum milliseconds between updates, per symbol; absent means full rate
+        #[serde(default, skip_serializing_if = "std::collections::HashMap::is_empty")]
+        throttle_ms: std::collections::HashMap<String, u64>,
     },
     Unsubscribe {
         symbols: Vec<String>,
     },
+    SubscribeAck {
+        symbols: Vec<String>, // Full current subscription set for the connection
+        #[serde(default)]
+        channels: Vec<WsChannel>, // Message categories the connection receives
+    },
     Error {
         message: String,
     },
 }
 
+/// Category of broadcast messages a WebSocket client can opt into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WsChannel {
+    /// `PriceUpdate`s for the subscribed symbols
+    Prices,
+    /// `HealthAlert`s, regardless of symbol subscriptions
+    Health,
+}
+
 /// Configuration structure
 #[derive(Debug, Deserialize)]
 pub struct Config {
@@ -174,6 +393,8 @@ pub struct Config {
     pub redis: RedisConfig,
     pub database: DatabaseConfig,
     pub server: ServerConfig,
+    #[serde(default)]
+    pub manager: ManagerConfig,
     pub oracles: Vec<Symbol>,
 }
 
@@ -200,6 +421,126 @@ pub struct ServerConfig {
     pub host: String,
     pub port: u16,
     pub cors_origins: Vec<String>,
+    #[serde(default)]
+    pub admin_api_key: Option<String>, // Required in X-API-Key for /admin routes
+}
+
+/// Oracle manager tuning knobs
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ManagerConfig {
+    pub broadcast_min_change_bp: u64,  // Minimum price move before broadcasting, in basis points
+    pub broadcast_heartbeat_secs: u64, // Broadcast anyway after this long without an update
+    pub outlier_method: OutlierMethod, // Outlier filter applied before consensus
+    pub median_tie_break: MedianTieBreak, // Value an even-length median resolves to
+    pub expo_selection: ExpoSelection, // How the aggregate's exponent is taken from the sources
+    pub deviation_base: DeviationBase, // Statistic outlier filtering and strict consensus measure deviation from
+    pub max_sources: Option<usize>,    // Sources fetched per cycle, most reliable first; None = all
+    pub redis_history: bool,           // Write each price to the Redis history sorted set
+    pub history_sample_interval_secs: u64, // At most one history entry per this many seconds, the latest winning; 0 = every write
+    pub max_history_records: usize,    // Most history records any endpoint reads or returns; larger limits are rejected
+    pub aggregation_memo: bool,        // Reuse a symbol's last aggregate while its source prices are unchanged
+    pub relaxed_retry_multiplier: u64, // Retry a failed aggregation once with max_deviation scaled by this, marked degraded; 0 = off
+    pub price_deadline_ms: u64,        // Budget for a live fetch on a price read before falling back to cache; 0 = none
+    pub push_mode: PushMode,           // Who drives the fetch cycles
+    pub shutdown_timeout_secs: u64,    // How long shutdown waits for pending cache writes
+    pub redis_fanout: bool,            // Route WebSocket broadcasts through Redis pubsub for multi-replica setups
+    pub fetch_interval_ms: u64,        // Delay between fetch cycles per symbol
+    pub adaptive_fetch_interval: bool, // Shorten the fetch interval while the price moves, lengthen it while quiet
+    pub adaptive_min_interval_ms: u64, // Shortest interval adaptive mode goes down to
+    pub adaptive_max_interval_ms: u64, // Longest interval adaptive mode backs off to
+    pub adaptive_fast_change_bp: u64,  // Recent move that halves the interval
+    pub adaptive_calm_change_bp: u64,  // Recent moves all below this lengthen the interval
+    pub publish_interval_ms: u64,      // Minimum time between cache writes and broadcasts per symbol; 0 = every fetch
+    pub publish_min_change_bp: u64,    // Price move that publishes before the publish interval is up; 0 = never early
+    pub switchboard_smoothing_rounds: usize, // Switchboard rounds (latest included) whose median is used; 0 or 1 = latest only
+    pub max_concurrent_fetches: usize, // Cap on source fetches hitting RPC at once across all symbols
+    pub early_exit_sources: usize,     // Stop waiting on slower sources once this many confident ones agree; 0 = wait for all
+    pub self_test: bool,               // Cross-check parsed feeds against Hermes before serving
+    pub self_test_tolerance_bp: u64,   // Allowed gap between parsed and reference prices
+    pub hermes_url: String,            // Pyth Hermes endpoint used as the reference
+    pub baseline_window: usize,        // Aggregated prices kept per symbol for the manipulation baseline
+    pub trace_history_len: usize,      // Consensus traces kept per symbol for /oracle/trace; 0 = off
+    pub max_task_restarts: u32,        // Respawns of a crashed fetch loop before the symbol is hard-failed
+    pub adhoc_rate_limit_per_min: u32, // Budget for ad-hoc feed lookups; 0 disables the endpoint
+    pub validate_feed_rate_limit_per_min: u32, // Budget for live feed address checks on /oracle/validate-feed; 0 disables the endpoint
+    pub stale_while_revalidate_secs: u64, // Grace past freshness where the cached price is served while refreshing
+    pub degraded_source_age_secs: u64, // Aggregates whose sources are all older than this are marked degraded
+    pub frozen_source_fetches: u32,    // Alert when a source's timestamp repeats for this many successful fetches; 0 = off
+    pub drop_frozen_sources: bool,     // Leave frozen sources out of aggregation until their timestamp advances
+    pub source_priority_interval_secs: u64, // How often sources are reordered and reweighted by their fetch health; 0 = off
+    pub health_snapshot_interval_secs: u64, // How often each symbol's health is appended to its history; 0 = off
+    pub health_history_len: usize,     // Health snapshots kept per symbol
+    pub alert_webhook_enabled: bool,   // POST health alerts to alert_webhook_url
+    pub alert_webhook_url: Option<String>, // Slack-compatible incoming webhook
+    pub metrics_symbols: Option<Vec<String>>, // Symbols with their own metric series; None = the first few configured
+    pub response_envelope: bool,       // Wrap every JSON response in {data, meta}; ?envelope= overrides per request
+    pub instance_id: Option<String>,   // Replica name reported in response metadata
+}
+
+/// Who drives the per-symbol fetch cycles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushMode {
+    /// Each symbol runs its own fetch loop
+    #[default]
+    Internal,
+    /// An external scheduler triggers fetches via `POST /admin/fetch/:symbol`
+    External,
+}
+
+impl Default for ManagerConfig {
+    fn default() -> Self {
+        Self {
+            broadcast_min_change_bp: 0,
+            broadcast_heartbeat_secs: 10,
+            outlier_method: OutlierMethod::default(),
+            median_tie_break: MedianTieBreak::default(),
+            expo_selection: ExpoSelection::default(),
+            deviation_base: DeviationBase::default(),
+            max_sources: None,
+            redis_history: true,
+            history_sample_interval_secs: 0,
+            max_history_records: 1000,
+            aggregation_memo: false,
+            relaxed_retry_multiplier: 0,
+            price_deadline_ms: 0,
+            push_mode: PushMode::default(),
+            shutdown_timeout_secs: 5,
+            redis_fanout: false,
+            fetch_interval_ms: 500,
+            adaptive_fetch_interval: false,
+            adaptive_min_interval_ms: 200,
+            adaptive_max_interval_ms: 5000,
+            adaptive_fast_change_bp: 10,
+            adaptive_calm_change_bp: 2,
+            publish_interval_ms: 0,
+            publish_min_change_bp: 50,
+            switchboard_smoothing_rounds: 0,
+            max_concurrent_fetches: 8,
+            early_exit_sources: 0,
+            self_test: false,
+            self_test_tolerance_bp: 100,
+            hermes_url: "https://hermes.pyth.network".to_string(),
+            baseline_window: 100,
+            trace_history_len: 20,
+            max_task_restarts: 5,
+            adhoc_rate_limit_per_min: 0,
+            validate_feed_rate_limit_per_min: 30,
+            stale_while_revalidate_secs: 0,
+            degraded_source_age_secs: 60,
+            frozen_source_fetches: 0,
+            drop_frozen_sources: false,
+            source_priority_interval_secs: 0,
+            health_snapshot_interval_secs: 30,
+            health_history_len: 120,
+            alert_webhook_enabled: false,
+            alert_webhook_url: None,
+            metrics_symbols: None,
+            response_envelope: false,
+            instance_id: None,
+        }
+    }
 }
 
 /// Error types
@@ -228,6 +569,30 @@ pub enum OracleError {
     
     #[error("Validation error: {0}")]
     ValidationError(String),
+    
+    #[error("{price_source:?} price {value} for {symbol} leaves the sources {deviation_bp:.2}bp apart, max {max_deviation_bp}bp (median {median}, {base} {reference})")]
+    DeviationExceeded {
+        symbol: String,
+        price_source: PriceSource,
+        value: f64,
+        median: f64,
+        base: DeviationBase,
+        reference: f64,
+        deviation_bp: f64,
+        max_deviation_bp: u64,
+    },
+    
+    #[error("Median price {median} for {symbol} is not positive, every source is suspect")]
+    NonPositiveMedian {
+        symbol: String,
+        median: f64,
+    },
+    
+    #[error("No price for {symbol} within the {deadline_ms}ms deadline")]
+    DeadlineExceeded {
+        symbol: String,
+        deadline_ms: u64,
+    },
 }
 
 /// Utility functions
@@ -242,6 +607,27 @@ impl PriceData {
         self.confidence as f64 / 10_f64.powi(-self.expo)
     }
     
+    /// Price as an exact decimal string, e.g. "50000.00000000"
+    pub fn to_decimal_string(&self) -> String {
+        format_fixed_point(self.price.unsigned_abs(), self.price < 0, self.expo)
+    }
+    
+    /// Confidence as an exact decimal string
+    pub fn confidence_to_decimal_string(&self) -> String {
+        format_fixed_point(self.confidence, false, self.expo)
+    }
+    
+    /// Lower and upper bounds of `price ± confidence`, in decimal
+    ///
+    /// The bounds are formed on the fixed-point values and scaled by `expo`
+    /// once, so they round no worse than `to_decimal`.
+    pub fn confidence_band(&self) -> (f64, f64) {
+        let price = self.price as i128;
+        let confidence = self.confidence as i128;
+        let scale = 10_f64.powi(-self.expo);
+        ((price - confidence) as f64 / scale, (price + confidence) as f64 / scale)
+    }
+    
     /// Calculate confidence as percentage of price
     pub fn confidence_percentage(&self) -> f64 {
         if self.price == 0 {
@@ -250,6 +636,19 @@ impl PriceData {
         (self.confidence as f64 / self.price as f64) * 100.0
     }
     
+    /// Confidence as a fraction of price, or None for non-positive prices
+    ///
+    /// Source readings carry the ratio their client derived at parse time
+    /// (see each client's `normalize_confidence`), so it compares across
+    /// sources; anything else, such as an aggregated price, falls back to
+    /// `confidence / price`.
+    pub fn relative_confidence(&self) -> Option<f64> {
+        if self.price <= 0 {
+            return None;
+        }
+        Some(self.confidence_ratio.unwrap_or(self.confidence as f64 / self.price as f64))
+    }
+    
     /// Check if price is within acceptable deviation from reference
     pub fn is_within_deviation(&self, reference_price: f64, max_deviation_bp: u64) -> bool {
         let current_price = self.to_decimal();
@@ -260,14 +659,74 @@ impl PriceData {
     }
 }
 
+/// Write a float as a JSON number in plain decimal notation
+///
+/// serde_json's default output switches to exponent form for very small and
+/// very large values (`1e-8`), which some strict downstream parsers reject.
+/// Non-finite values still serialize as `null`.
+fn serialize_plain_decimal<S: serde::Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if !value.is_finite() {
+        return serializer.serialize_f64(*value);
+    }
+    
+    // Display never uses exponent form; keep the `.0` so it stays a float
+    let mut plain = value.to_string();
+    if !plain.contains('.') {
+        plain.push_str(".0");
+    }
+    serde_json::value::RawValue::from_string(plain)
+        .map_err(serde::ser::Error::custom)?
+        .serialize(serializer)
+}
+
+/// Render a fixed-point mantissa and exponent without going through f64
+fn format_fixed_point(mantissa: u64, negative: bool, expo: i32) -> String {
+    let sign = if negative { "-" } else { "" };
+    let digits = mantissa.to_string();
+    
+    if expo >= 0 {
+        if mantissa == 0 {
+            return "0".to_string();
+        }
+        return format!("{}{}{}", sign, digits, "0".repeat(expo as usize));
+    }
+    
+    let scale = expo.unsigned_abs() as usize;
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - scale);
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
 impl PriceResponse {
     pub fn from_price_data(price_data: &PriceData) -> Self {
         Self {
             symbol: price_data.symbol.clone(),
-            price: price_data.to_decimal(),
-            confidence: price_data.confidence_to_decimal(),
+            price: PriceValue::Float(price_data.to_decimal()),
+            confidence: PriceValue::Float(price_data.confidence_to_decimal()),
+            confidence_bp: price_data.confidence_percentage() * 100.0,
             timestamp: price_data.timestamp,
             source: price_data.source.clone(),
+            strategy: price_data.strategy,
+            max_source_age_secs: price_data.max_source_age_secs,
+            degraded: price_data.degraded,
+        }
+    }
+    
+    /// Same as `from_price_data`, but with price and confidence as exact decimal strings
+    pub fn from_price_data_exact(price_data: &PriceData) -> Self {
+        Self {
+            price: PriceValue::Exact(price_data.to_decimal_string()),
+            confidence: PriceValue::Exact(price_data.confidence_to_decimal_string()),
+            ..Self::from_price_data(price_data)
+        }
+    }
+    
+    /// Pick the float or exact-string form
+    pub fn from_price_data_as(price_data: &PriceData, string_prices: bool) -> Self {
+        if string_prices {
+            Self::from_price_data_exact(price_data)
+        } else {
+            Self::from_price_data(price_data)
         }
     }
 }
@@ -280,6 +739,7 @@ impl From<&OracleHealth> for OracleHealthStatus {
             average_latency: health.average_latency,
             last_update: health.last_update,
             consecutive_failures: health.consecutive_failures,
+            baseline: None,
         }
     }
 }
@@ -288,6 +748,43 @@ impl From<&OracleHealth> for OracleHealthStatus {
 mod tests {
     use super::*;
     
+    #[test]
+    fn test_duplicate_symbols_rejected() {
+        let symbol = |name: &str| Symbol { name: name.to_string(), ..Default::default() };
+        
+        assert!(check_unique_symbols(&[symbol("BTC/USD"), symbol("ETH/USD")]).is_ok());
+        
+        let err = check_unique_symbols(&[symbol("BTC/USD"), symbol("ETH/USD"), symbol("BTC/USD")]).unwrap_err();
+        assert!(matches!(err, OracleError::ConfigError(_)));
+        assert!(err.to_string().contains("Symbol BTC/USD is configured more than once"));
+        
+        // Alternative notations of the same pair count too
+        assert!(check_unique_symbols(&[symbol("BTC/USD"), symbol("btc-usd")]).is_err());
+    }
+    
+    #[test]
+    fn test_base_quote_orientation() {
+        let btc = Symbol { name: "BTC/USD".to_string(), ..Default::default() };
+        assert_eq!(btc.base_quote(), Some(("BTC".to_string(), "USD".to_string())));
+        assert!(!btc.inverted_for_base("btc").unwrap());
+        assert!(btc.inverted_for_base("USD").unwrap());
+        assert!(matches!(btc.inverted_for_base("ETH"), Err(OracleError::ValidationError(_))));
+        
+        // An explicit designation wins over a name that doesn't spell the pair out
+        let pool = Symbol {
+            name: "SOLUSDC-POOL".to_string(),
+            base: Some("USDC".to_string()),
+            quote: Some("SOL".to_string()),
+            ..Default::default()
+        };
+        assert!(!pool.inverted_for_base("USDC").unwrap());
+        assert!(pool.inverted_for_base("SOL").unwrap());
+        
+        let unnamed = Symbol { name: "SOLUSDC".to_string(), ..Default::default() };
+        assert!(unnamed.base_quote().is_none());
+        assert!(unnamed.inverted_for_base("SOL").is_err());
+    }
+    
     #[test]
     fn test_price_data_conversion() {
         let price_data = PriceData {
@@ -297,6 +794,7 @@ mod tests {
             timestamp: 1000000000,
             source: PriceSource::Pyth,
             symbol: "BTC/USD".to_string(),
+            ..Default::default()
         };
         
         assert_eq!(price_data.to_decimal(), 50000.0);
@@ -304,6 +802,114 @@ mod tests {
         assert_eq!(price_data.confidence_percentage(), 0.01); // 0.01%
     }
     
+    #[test]
+    fn test_confidence_band() {
+        let mut price_data = PriceData {
+            price: 50000_12345678, // $50,000.12345678
+            confidence: 25_50000000, // $25.50
+            expo: -8,
+            timestamp: 1000000000,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(price_data.confidence_band(), (49974.62345678, 50025.62345678));
+        
+        // Positive exponents scale up, and a confidence wider than the price goes negative
+        price_data.price = 12;
+        price_data.confidence = 20;
+        price_data.expo = 2;
+        assert_eq!(price_data.confidence_band(), (-800.0, 3200.0));
+    }
+    
+    #[test]
+    fn test_price_source_parsing() {
+        assert_eq!("pyth".parse::<PriceSource>().unwrap(), PriceSource::Pyth);
+        assert_eq!("Switchboard".parse::<PriceSource>().unwrap(), PriceSource::Switchboard);
+        assert!("chainlink".parse::<PriceSource>().is_err());
+        
+        // Round-trips the form stored in Redis
+        let stored = format!("{:?}", PriceSource::Switchboard);
+        assert_eq!(stored.parse::<PriceSource>().unwrap(), PriceSource::Switchboard);
+    }
+    
+    #[test]
+    fn test_exact_decimal_strings() {
+        let mut price_data = PriceData {
+            price: 12345678901234567, // beyond f64's exact integer range
+            confidence: 5,
+            expo: -8,
+            timestamp: 1000000000,
+            source: PriceSource::Pyth,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        assert_eq!(price_data.to_decimal_string(), "123456789.01234567");
+        assert_eq!(price_data.confidence_to_decimal_string(), "0.00000005");
+        
+        price_data.price = -150;
+        price_data.expo = -2;
+        assert_eq!(price_data.to_decimal_string(), "-1.50");
+        
+        price_data.price = 42;
+        price_data.expo = 3;
+        assert_eq!(price_data.to_decimal_string(), "42000");
+        
+        let response = PriceResponse::from_price_data_as(&price_data, true);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["price"], "42000");
+        
+        let response = PriceResponse::from_price_data_as(&price_data, false);
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["price"], 42000.0);
+    }
+    
+    #[test]
+    fn test_confidence_bp_in_response() {
+        let price_data = PriceData {
+            price: 50000_00000000,
+            confidence: 25_00000000,
+            expo: -8,
+            timestamp: 0,
+            source: PriceSource::Aggregated,
+            symbol: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // $25 on $50,000 is 5bp, alongside the absolute confidence
+        let response = PriceResponse::from_price_data(&price_data);
+        assert!((response.confidence_bp - 5.0).abs() < 1e-9);
+        assert_eq!(response.confidence, PriceValue::Float(25.0));
+        
+        // The exact-string form carries it as well
+        let json = serde_json::to_value(PriceResponse::from_price_data_exact(&price_data)).unwrap();
+        assert!((json["confidence_bp"].as_f64().unwrap() - 5.0).abs() < 1e-9);
+    }
+    
+    #[test]
+    fn test_tiny_confidence_serializes_without_exponent() {
+        let price_data = PriceData {
+            price: 5_00000000,
+            confidence: 1,
+            expo: -8,
+            timestamp: 0,
+            source: PriceSource::Pyth,
+            symbol: "BONK/USD".to_string(),
+            ..Default::default()
+        };
+        
+        let json = serde_json::to_string(&PriceResponse::from_price_data(&price_data)).unwrap();
+        assert!(!json.contains("e-"), "exponent in {}", json);
+        assert!(json.contains(r#""confidence":0.00000001"#));
+        assert!(json.contains(r#""price":5.0"#));
+        
+        // Still plain JSON numbers that read back to the same values
+        let parsed: PriceResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.confidence, PriceValue::Float(0.00000001));
+        assert_eq!(parsed.price, PriceValue::Float(5.0));
+    }
+    
     #[test]
     fn test_oracle_health_update() {
         let mut health = OracleHealth::default();
@@ -322,6 +928,86 @@ mod tests {
         assert_eq!(health.consecutive_failures, 3);
     }
     
+    #[test]
+    fn test_health_snapshot() {
+        let mut health = OracleHealth::default();
+        health.update(true);
+        health.update(false);
+        health.update_latency(120.0);
+        
+        let snapshot = HealthSnapshot::new(&health, 1_700_000_000);
+        assert_eq!(snapshot.timestamp, 1_700_000_000);
+        assert!(snapshot.is_healthy);
+        assert_eq!(snapshot.success_rate, 0.5);
+        assert_eq!(snapshot.average_latency, 120.0);
+        assert_eq!(snapshot.consecutive_failures, 1);
+        assert_eq!(snapshot.total_requests, 2);
+        
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<HealthSnapshot>(&json).unwrap(), snapshot);
+    }
+    
+    #[test]
+    fn test_mark_unhealthy() {
+        let mut health = OracleHealth::default();
+        
+        health.mark_unhealthy("All sources failed on first fetch".to_string());
+        assert!(!health.is_healthy);
+        assert_eq!(health.consecutive_failures, 0);
+        assert_eq!(health.last_error.as_deref(), Some("All sources failed on first fetch"));
+        
+        // A later success recovers as usual
+        health.update(true);
+        assert!(health.is_healthy);
+        assert!(health.last_error.is_none());
+    }
+    
+    #[test]
+    fn test_source_allowlist() {
+        let mut symbol = Symbol {
+            name: "BTC/USD".to_string(),
+            ..Default::default()
+        };
+        
+        // No allowlist means every source contributes
+        assert!(symbol.uses_source(&PriceSource::Pyth));
+        assert!(symbol.uses_source(&PriceSource::Switchboard));
+        
+        symbol.sources = Some(vec![PriceSource::Pyth]);
+        assert!(symbol.uses_source(&PriceSource::Pyth));
+        assert!(!symbol.uses_source(&PriceSource::Switchboard));
+    }
+    
+    #[test]
+    fn test_symbol_alias_resolution() {
+        let symbol = Symbol {
+            name: "BTC/USD".to_string(),
+            aliases: vec!["XBT/USD".to_string()],
+            ..Default::default()
+        };
+        
+        for notation in ["BTC/USD", "BTCUSD", "BTC-USD", "btc/usd", " btc_usd ", "xbt-usd"] {
+            assert!(symbol.matches(notation), "{} should resolve to BTC/USD", notation);
+        }
+        assert!(!symbol.matches("ETH/USD"));
+        assert!(!symbol.matches("BTC/EUR"));
+        
+        assert_eq!(canonical_symbol("eth-usd"), "ETH/USD");
+    }
+    
+    #[test]
+    fn test_per_source_staleness() {
+        let mut symbol = Symbol { max_staleness: 60, ..Default::default() };
+        assert_eq!(symbol.max_staleness_for(&PriceSource::Pyth), 60);
+        assert_eq!(symbol.max_staleness_for(&PriceSource::Switchboard), 60);
+        
+        symbol.pyth_max_staleness = Some(5);
+        symbol.switchboard_max_staleness = Some(30);
+        assert_eq!(symbol.max_staleness_for(&PriceSource::Pyth), 5);
+        assert_eq!(symbol.max_staleness_for(&PriceSource::Switchboard), 30);
+        assert_eq!(symbol.max_staleness_for(&PriceSource::Internal), 60);
+    }
+    
     #[test]
     fn test_deviation_check() {
         let price_data = PriceData {
@@ -331,6 +1017,7 @@ mod tests {
             timestamp: 1000000000,
             source: PriceSource::Pyth,
             symbol: "BTC/USD".to_string(),
+            ..Default::default()
         };
         
         // Test within 1% deviation (100 basis points)
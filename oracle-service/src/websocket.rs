@@ -1,20 +1,27 @@
 use axum::{
     extract::{
-        ws::{WebSocket, Message},
+        ws::{close_code, CloseFrame, WebSocket, Message},
+        Path,
+        Query,
         WebSocketUpgrade,
         State,
     },
-    response::Response,
+    http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use futures_util::{sink::SinkExt, stream::StreamExt};
+use serde::Deserialize;
 use serde_json;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::Arc;
-use tokio::sync::{broadcast, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, Mutex, RwLock};
 use tracing::{info, error, warn};
 
 use crate::{
+    cache::PubSubEvent,
     manager::OracleManager,
-    types::{WsMessage, PriceData},
+    types::{WsChannel, WsMessage, PriceData},
 };
 
 /// WebSocket server state
@@ -24,31 +31,244 @@ pub struct WsState {
     pub broadcast_sender: broadcast::Sender<WsMessage>,
 }
 
+/// Per-connection symbol and channel subscriptions
+///
+/// Subscribe is a set union and Unsubscribe a set difference, so replaying
+/// either message (e.g. after a client reconnect) has no additional effect.
+/// Throttled symbols deliver at most one update per interval and drop the rest.
+/// Connections start on every channel until a Subscribe names its own set.
+#[derive(Debug, Clone)]
+pub struct Subscriptions {
+    symbols: BTreeSet<String>,
+    channels: BTreeSet<WsChannel>,
+    throttles: HashMap<String, Duration>,
+    last_sent: HashMap<String, Instant>,
+}
+
+impl Default for Subscriptions {
+    fn default() -> Self {
+        Self {
+            symbols: BTreeSet::new(),
+            channels: BTreeSet::from([WsChannel::Prices, WsChannel::Health]),
+            throttles: HashMap::new(),
+            last_sent: HashMap::new(),
+        }
+    }
+}
+
+impl Subscriptions {
+    /// Add symbols to the subscription set, returning the ones not already present
+    pub fn subscribe(&mut self, symbols: &[String]) -> Vec<String> {
+        symbols.iter()
+            .filter(|symbol| self.symbols.insert(symbol.to_string()))
+            .cloned()
+            .collect()
+    }
+    
+    /// Remove symbols from the subscription set, ignoring unknown ones
+    pub fn unsubscribe(&mut self, symbols: &[String]) {
+        for symbol in symbols {
+            self.symbols.remove(symbol);
+            self.throttles.remove(symbol);
+            self.last_sent.remove(symbol);
+        }
+    }
+    
+    /// Set the minimum interval between updates for a symbol, `None` for full rate
+    pub fn set_throttle(&mut self, symbol: &str, interval: Option<Duration>) {
+        match interval {
+            Some(interval) => {
+                self.throttles.insert(symbol.to_string(), interval);
+            },
+            None => {
+                self.throttles.remove(symbol);
+                self.last_sent.remove(symbol);
+            },
+        }
+    }
+    
+    /// Replace the channels the connection receives; an empty list leaves them as they are
+    pub fn set_channels(&mut self, channels: &[WsChannel]) {
+        if !channels.is_empty() {
+            self.channels = channels.iter().copied().collect();
+        }
+    }
+    
+    /// Full current subscription set, in sorted order
+    pub fn current(&self) -> Vec<String> {
+        self.symbols.iter().cloned().collect()
+    }
+    
+    /// Channels the connection currently receives
+    pub fn channels(&self) -> Vec<WsChannel> {
+        self.channels.iter().copied().collect()
+    }
+    
+    /// Acknowledgement carrying the full subscription state
+    pub fn ack(&self) -> WsMessage {
+        WsMessage::SubscribeAck { symbols: self.current(), channels: self.channels() }
+    }
+    
+    /// Whether a broadcast message should be delivered to this connection
+    pub fn wants(&self, message: &WsMessage) -> bool {
+        match message {
+            WsMessage::PriceUpdate { symbol, .. } => {
+                self.channels.contains(&WsChannel::Prices) && self.symbols.contains(symbol)
+            },
+            WsMessage::HealthAlert { .. } => self.channels.contains(&WsChannel::Health),
+            _ => true,
+        }
+    }
+    
+    /// Like `wants`, but also applies the symbol's throttle, recording the send
+    pub fn should_deliver(&mut self, message: &WsMessage, now: Instant) -> bool {
+        if !self.wants(message) {
+            return false;
+        }
+        
+        let WsMessage::PriceUpdate { symbol, .. } = message else {
+            return true;
+        };
+        let Some(&interval) = self.throttles.get(symbol) else {
+            return true;
+        };
+        
+        if self.last_sent.get(symbol).is_some_and(|&sent| now.duration_since(sent) < interval) {
+            return false;
+        }
+        self.last_sent.insert(symbol.clone(), now);
+        true
+    }
+}
+
+/// Query parameters accepted on the WebSocket connect URL
+#[derive(Debug, Deserialize)]
+pub struct WsConnectQuery {
+    pub symbols: Option<String>, // Comma-separated symbols to subscribe to on connect
+}
+
 /// WebSocket connection handler
+///
+/// `/ws?symbols=BTC/USD,ETH/USD` subscribes at connect time, saving thin
+/// clients the Subscribe round trip. The connection opens with a
+/// `SubscribeAck` for the symbols that are configured, plus an `Error`
+/// naming any that aren't.
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
     State(state): State<WsState>,
+    Query(query): Query<WsConnectQuery>,
+) -> Response {
+    let requested = query.symbols.as_deref().map(split_symbols).unwrap_or_default();
+    let mut subscriptions = Subscriptions::default();
+    let mut greeting = Vec::new();
+    
+    if !requested.is_empty() {
+        let mut known = Vec::new();
+        let mut unknown = Vec::new();
+        for symbol in requested {
+            match state.oracle_manager.symbol_config(&symbol) {
+                Ok(config) => known.push(config.name.clone()),
+                Err(_) => unknown.push(symbol),
+            }
+        }
+        subscriptions.subscribe(&known);
+        
+        info!("Client subscribed on connect to symbols: {:?}", subscriptions.current());
+        greeting.push(subscriptions.ack());
+        if !unknown.is_empty() {
+            greeting.push(WsMessage::Error {
+                message: format!("symbols not configured: {}", unknown.join(", ")),
+            });
+        }
+    }
+    
+    ws.on_upgrade(|socket| handle_websocket(socket, state, subscriptions, greeting))
+}
+
+/// Symbols from a comma-separated list, trimmed, with empty entries dropped
+fn split_symbols(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|symbol| !symbol.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// WebSocket handler for a single symbol, subscribed from the start
+pub async fn symbol_websocket_handler(
+    ws: WebSocketUpgrade,
+    State(state): State<WsState>,
+    Path(symbol): Path<String>,
 ) -> Response {
-    ws.on_upgrade(|socket| handle_websocket(socket, state))
+    let symbol = match state.oracle_manager.symbol_config(&symbol) {
+        Ok(config) => config.name.clone(),
+        Err(e) => {
+            return (
+                StatusCode::NOT_FOUND,
+                axum::Json(serde_json::json!({
+                    "error": "Symbol not configured",
+                    "symbol": symbol,
+                    "message": e.to_string()
+                }))
+            ).into_response();
+        }
+    };
+    
+    let mut subscriptions = Subscriptions::default();
+    subscriptions.subscribe(&[symbol]);
+    ws.on_upgrade(|socket| handle_websocket(socket, state, subscriptions, Vec::new()))
 }
 
 /// Handle individual WebSocket connection
-async fn handle_websocket(socket: WebSocket, state: WsState) {
+///
+/// `greeting` is sent as soon as the connection is accepted, ahead of the
+/// snapshots for the `initial` symbols.
+async fn handle_websocket(mut socket: WebSocket, state: WsState, initial: Subscriptions, greeting: Vec<WsMessage>) {
+    // A draining replica turns new clients away so they reconnect elsewhere
+    if state.oracle_manager.is_draining() {
+        let _ = socket.send(Message::Close(Some(CloseFrame {
+            code: close_code::AGAIN,
+            reason: "server draining".into(),
+        }))).await;
+        return;
+    }
+    
     let (sender, mut receiver) = socket.split();
     let sender = Arc::new(Mutex::new(sender));
     let mut broadcast_receiver = state.broadcast_sender.subscribe();
+    let subscriptions = Arc::new(RwLock::new(initial));
+    
+    // Symbols subscribed by the URL get the same snapshot as a Subscribe message
+    let initial_symbols = subscriptions.read().await.current();
+    let snapshots = snapshot_updates(&state, &subscriptions, &initial_symbols).await;
+    for message in greeting.into_iter().chain(snapshots) {
+        if let Ok(json) = serde_json::to_string(&message) {
+            if sender.lock().await.send(Message::Text(json)).await.is_err() {
+                return;
+            }
+        }
+    }
     
     info!("New WebSocket connection established");
     
     // Task for handling incoming messages from client
     let sender_clone = sender.clone();
+    let client_subscriptions = subscriptions.clone();
     let client_task = tokio::spawn(async move {
         while let Some(msg) = receiver.next().await {
             match msg {
                 Ok(Message::Text(text)) => {
                     match serde_json::from_str::<WsMessage>(&text) {
                         Ok(ws_msg) => {
-                            handle_client_message(ws_msg, &state).await;
+                            let replies = handle_client_message(ws_msg, &state, &client_subscriptions).await;
+                            let mut sender = sender_clone.lock().await;
+                            for reply in replies {
+                                if let Ok(json) = serde_json::to_string(&reply) {
+                                    if sender.send(Message::Text(json)).await.is_err() {
+                                        return;
+                                    }
+                                }
+                            }
                         },
                         Err(e) => {
                             warn!("Failed to parse WebSocket message: {}", e);
@@ -80,7 +300,22 @@ async fn handle_websocket(socket: WebSocket, state: WsState) {
     // Task for broadcasting updates to client
     let sender_clone = sender.clone();
     let broadcast_task = tokio::spawn(async move {
-        while let Ok(message) = broadcast_receiver.recv().await {
+        loop {
+            let message = match broadcast_receiver.recv().await {
+                Ok(message) => message,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    // Slow client fell behind the channel; tell it and keep going
+                    warn!("WebSocket client lagged, {} updates skipped", skipped);
+                    WsMessage::Error {
+                        message: format!("lagged, {} updates skipped", skipped),
+                    }
+                },
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            
+            if !subscriptions.write().await.should_deliver(&message, Instant::now()) {
+                continue;
+            }
             if let Ok(json) = serde_json::to_string(&message) {
                 let mut sender = sender_clone.lock().await;
                 if sender.send(Message::Text(json)).await.is_err() {
@@ -99,36 +334,112 @@ async fn handle_websocket(socket: WebSocket, state: WsState) {
     info!("WebSocket connection closed");
 }
 
-/// Handle messages from WebSocket clients
-async fn handle_client_message(message: WsMessage, _state: &WsState) {
+/// Handle messages from WebSocket clients, returning the replies for this connection
+///
+/// A Subscribe is acknowledged first, then followed by the cached price of
+/// each newly subscribed symbol so the client has state before the next tick.
+async fn handle_client_message(
+    message: WsMessage,
+    state: &WsState,
+    subscriptions: &RwLock<Subscriptions>,
+) -> Vec<WsMessage> {
     match message {
-        WsMessage::Subscribe { symbols } => {
-            info!("Client subscribed to symbols: {:?}", symbols);
-            // In a production system, you'd track subscriptions per client
-            // For now, we'll just acknowledge the subscription
+        WsMessage::Subscribe { .. } if state.oracle_manager.is_draining() => {
+            vec![WsMessage::Error {
+                message: "server draining, subscribe on another instance".to_string(),
+            }]
+        },
+        WsMessage::Subscribe { symbols, channels, throttle_ms } => {
+            info!("Client subscribed to symbols: {:?}, channels: {:?}", symbols, channels);
+            let resolved = resolve_symbols(state, &symbols);
+            let (ack, added) = {
+                let mut subscriptions = subscriptions.write().await;
+                subscriptions.set_channels(&channels);
+                let added = subscriptions.subscribe(&resolved);
+                
+                // Each subscribe restates the cadence for the symbols it names
+                for (requested, name) in symbols.iter().zip(&resolved) {
+                    let throttle = throttle_ms.get(requested)
+                        .or_else(|| throttle_ms.get(name))
+                        .map(|&ms| Duration::from_millis(ms));
+                    subscriptions.set_throttle(name, throttle);
+                }
+                (subscriptions.ack(), added)
+            };
+            
+            let mut replies = vec![ack];
+            replies.extend(snapshot_updates(state, subscriptions, &added).await);
+            replies
         },
         WsMessage::Unsubscribe { symbols } => {
             info!("Client unsubscribed from symbols: {:?}", symbols);
+            let symbols = resolve_symbols(state, &symbols);
+            let mut subscriptions = subscriptions.write().await;
+            subscriptions.unsubscribe(&symbols);
+            vec![subscriptions.ack()]
         },
         _ => {
             warn!("Unexpected message type from client");
+            Vec::new()
         }
     }
 }
 
+/// Cached price of each symbol as a `PriceUpdate`, skipping symbols with nothing cached
+///
+/// Snapshots count against the symbol's throttle like any other update, and
+/// are dropped for connections off the prices channel.
+async fn snapshot_updates(
+    state: &WsState,
+    subscriptions: &RwLock<Subscriptions>,
+    symbols: &[String],
+) -> Vec<WsMessage> {
+    let mut updates = Vec::new();
+    for symbol in symbols {
+        if let Some(price_data) = state.oracle_manager.get_cached_price(symbol).await {
+            updates.push(price_update_message(symbol, &price_data));
+        }
+    }
+    
+    let mut subscriptions = subscriptions.write().await;
+    let now = Instant::now();
+    updates.retain(|update| subscriptions.should_deliver(update, now));
+    updates
+}
+
+/// Map requested notations onto configured symbol names so they match broadcasts
+fn resolve_symbols(state: &WsState, symbols: &[String]) -> Vec<String> {
+    symbols.iter()
+        .map(|symbol| match state.oracle_manager.symbol_config(symbol) {
+            Ok(config) => config.name.clone(),
+            Err(_) => symbol.clone(),
+        })
+        .collect()
+}
+
+/// The `PriceUpdate` clients receive for a symbol's price
+fn price_update_message(symbol: &str, price_data: &PriceData) -> WsMessage {
+    WsMessage::PriceUpdate {
+        symbol: symbol.to_string(),
+        price: price_data.to_decimal(),
+        confidence: price_data.confidence_to_decimal(),
+        timestamp: price_data.timestamp,
+        source: price_data.source.clone(),
+    }
+}
+
 /// Broadcast price update to all connected clients
 pub async fn broadcast_price_update(
     sender: &broadcast::Sender<WsMessage>,
     symbol: &str,
     price_data: &PriceData,
 ) {
-    let message = WsMessage::PriceUpdate {
-        symbol: symbol.to_string(),
-        price: price_data.to_decimal(),
-        confidence: price_data.confidence_to_decimal(),
-        timestamp: price_data.timestamp,
-        source: price_data.source.clone(),
-    };
+    let message = price_update_message(symbol, price_data);
+    
+    // No connected clients is not an error, just nobody to tell
+    if sender.receiver_count() == 0 {
+        return;
+    }
     
     if let Err(e) = sender.send(message) {
         error!("Failed to broadcast price update: {}", e);
@@ -149,11 +460,36 @@ pub async fn broadcast_health_alert(
         timestamp: chrono::Utc::now().timestamp(),
     };
     
+    if sender.receiver_count() == 0 {
+        return;
+    }
+    
     if let Err(e) = sender.send(alert) {
         error!("Failed to broadcast health alert: {}", e);
     }
 }
 
+/// Forward price updates from Redis pubsub into the local broadcast channel
+///
+/// Used when replicas share a Redis so a client sees updates computed on any
+/// instance, not just the one it is connected to.
+fn spawn_redis_forwarder(oracle_manager: &OracleManager, sender: broadcast::Sender<WsMessage>) {
+    let mut events = oracle_manager.subscribe_price_updates();
+    
+    tokio::spawn(async move {
+        while let Some(event) = events.recv().await {
+            match event {
+                PubSubEvent::Price(price_data) => {
+                    broadcast_price_update(&sender, &price_data.symbol, &price_data).await;
+                },
+                PubSubEvent::Resubscribed => {
+                    warn!("Redis price feed re-subscribed, some updates may have been missed");
+                },
+            }
+        }
+    });
+}
+
 /// Start WebSocket server
 pub async fn start_websocket_server(
     host: &str,
@@ -163,7 +499,11 @@ pub async fn start_websocket_server(
     use axum::{routing::get, Router};
     use tower_http::cors::CorsLayer;
     
-    let (broadcast_sender, _) = broadcast::channel(1000);
+    let broadcast_sender = oracle_manager.broadcast_sender();
+    
+    if oracle_manager.redis_fanout() {
+        spawn_redis_forwarder(&oracle_manager, broadcast_sender.clone());
+    }
     
     let state = WsState {
         oracle_manager,
@@ -172,6 +512,7 @@ pub async fn start_websocket_server(
     
     let app = Router::new()
         .route("/ws", get(websocket_handler))
+        .route("/ws/:symbol", get(symbol_websocket_handler))
         .layer(CorsLayer::permissive())
         .with_state(state);
     
@@ -241,6 +582,125 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_subscribe_is_idempotent() {
+        let mut subscriptions = Subscriptions::default();
+        
+        let added = subscriptions.subscribe(&["BTC/USD".to_string(), "ETH/USD".to_string()]);
+        assert_eq!(added, vec!["BTC/USD".to_string(), "ETH/USD".to_string()]);
+        
+        // Replayed subscribe after a reconnect must not double-register, or re-snapshot
+        let added = subscriptions.subscribe(&["BTC/USD".to_string(), "SOL/USD".to_string()]);
+        assert_eq!(added, vec!["SOL/USD".to_string()]);
+        subscriptions.unsubscribe(&["SOL/USD".to_string()]);
+        assert_eq!(subscriptions.current(), vec!["BTC/USD".to_string(), "ETH/USD".to_string()]);
+        
+        subscriptions.unsubscribe(&["ETH/USD".to_string()]);
+        subscriptions.unsubscribe(&["ETH/USD".to_string(), "SOL/USD".to_string()]);
+        assert_eq!(subscriptions.current(), vec!["BTC/USD".to_string()]);
+        
+        let update = |symbol: &str| WsMessage::PriceUpdate {
+            symbol: symbol.to_string(),
+            price: 1.0,
+            confidence: 0.1,
+            timestamp: 1640995200,
+            source: PriceSource::Aggregated,
+        };
+        assert!(subscriptions.wants(&update("BTC/USD")));
+        assert!(!subscriptions.wants(&update("ETH/USD")));
+    }
+    
+    #[test]
+    fn test_per_symbol_throttle() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.subscribe(&["BTC/USD".to_string(), "ETH/USD".to_string()]);
+        subscriptions.set_throttle("ETH/USD", Some(Duration::from_secs(5)));
+        
+        let update = |symbol: &str| WsMessage::PriceUpdate {
+            symbol: symbol.to_string(),
+            price: 1.0,
+            confidence: 0.1,
+            timestamp: 1640995200,
+            source: PriceSource::Aggregated,
+        };
+        let start = Instant::now();
+        
+        // BTC flows at full rate
+        assert!(subscriptions.should_deliver(&update("BTC/USD"), start));
+        assert!(subscriptions.should_deliver(&update("BTC/USD"), start + Duration::from_millis(10)));
+        
+        // ETH drops intermediate updates inside its interval
+        assert!(subscriptions.should_deliver(&update("ETH/USD"), start));
+        assert!(!subscriptions.should_deliver(&update("ETH/USD"), start + Duration::from_secs(1)));
+        assert!(subscriptions.should_deliver(&update("ETH/USD"), start + Duration::from_secs(5)));
+        
+        // Clearing the throttle restores full rate
+        subscriptions.set_throttle("ETH/USD", None);
+        assert!(subscriptions.should_deliver(&update("ETH/USD"), start + Duration::from_secs(6)));
+        
+        let subscribe: WsMessage = serde_json::from_str(
+            r#"{"type":"Subscribe","symbols":["BTC/USD","ETH/USD"],"throttle_ms":{"ETH/USD":5000}}"#
+        ).unwrap();
+        assert!(matches!(subscribe, WsMessage::Subscribe { throttle_ms, .. } if throttle_ms["ETH/USD"] == 5000));
+    }
+    
+    #[test]
+    fn test_connect_symbols_query() {
+        assert_eq!(split_symbols("BTC/USD,ETH/USD"), vec!["BTC/USD", "ETH/USD"]);
+        assert_eq!(split_symbols(" BTC/USD , ,ETH/USD,"), vec!["BTC/USD", "ETH/USD"]);
+        assert!(split_symbols("").is_empty());
+        
+        // Slashes in a symbol survive query decoding
+        let Query(query) = Query::<WsConnectQuery>::try_from_uri(
+            &"/ws?symbols=BTC%2FUSD,SOL/USD".parse().unwrap()
+        ).unwrap();
+        assert_eq!(split_symbols(&query.symbols.unwrap()), vec!["BTC/USD", "SOL/USD"]);
+    }
+    
+    #[test]
+    fn test_channel_filtering() {
+        let mut subscriptions = Subscriptions::default();
+        subscriptions.subscribe(&["BTC/USD".to_string()]);
+        
+        let update = WsMessage::PriceUpdate {
+            symbol: "BTC/USD".to_string(),
+            price: 1.0,
+            confidence: 0.1,
+            timestamp: 1640995200,
+            source: PriceSource::Aggregated,
+        };
+        let alert = WsMessage::HealthAlert {
+            oracle: "pyth".to_string(),
+            status: "unhealthy".to_string(),
+            message: "stale".to_string(),
+            timestamp: 1640995200,
+        };
+        
+        // Every channel until the client picks
+        assert!(subscriptions.wants(&update));
+        assert!(subscriptions.wants(&alert));
+        
+        let subscribe: WsMessage = serde_json::from_str(r#"{"type":"Subscribe","channels":["health"]}"#).unwrap();
+        let WsMessage::Subscribe { symbols, channels, .. } = subscribe else {
+            panic!("Wrong message type");
+        };
+        assert!(symbols.is_empty());
+        subscriptions.set_channels(&channels);
+        assert!(!subscriptions.wants(&update));
+        assert!(subscriptions.wants(&alert));
+        
+        // A subscribe without channels keeps the current set
+        subscriptions.set_channels(&[]);
+        assert_eq!(subscriptions.channels(), vec![WsChannel::Health]);
+        
+        subscriptions.set_channels(&[WsChannel::Prices]);
+        assert!(subscriptions.wants(&update));
+        assert!(!subscriptions.wants(&alert));
+        
+        // Control messages are never filtered
+        assert!(subscriptions.wants(&WsMessage::Error { message: "lagged".to_string() }));
+    }
+    
     #[tokio::test]
     async fn test_broadcast_functionality() {
         let (sender, mut receiver) = broadcast::channel(10);
@@ -12,14 +12,21 @@ pub mod oracle_integration {
         pyth_feed: Pubkey,
         switchboard_aggregator: Pubkey,
         max_staleness: i64,
+        pyth_max_staleness: i64,
+        switchboard_max_staleness: i64,
         max_confidence: u64,
         max_deviation: u64,
     ) -> Result<()> {
+        // The account is sized for this, and last_good seeds can't be longer
+        require!(symbol.len() <= MAX_SYMBOL_LEN, ErrorCode::SymbolTooLong);
+
         let config = &mut ctx.accounts.config;
         config.symbol = symbol;
         config.pyth_feed = pyth_feed;
         config.switchboard_aggregator = switchboard_aggregator;
         config.max_staleness = max_staleness;
+        config.pyth_max_staleness = pyth_max_staleness;
+        config.switchboard_max_staleness = switchboard_max_staleness;
         config.max_confidence = max_confidence;
         config.max_deviation = max_deviation;
         Ok(())
@@ -29,73 +36,45 @@ pub mod oracle_integration {
         ctx: Context<GetPythPrice>,
         _price_feed: Pubkey,
     ) -> Result<PriceData> {
-        let pyth_price_account = &ctx.accounts.pyth_price_account;
-        
-        // REAL PYTH PRICE PARSING - No more mock data!
-        if pyth_price_account.data_len() < 240 {
-            return Err(ErrorCode::InvalidPriceAccount.into());
-        }
+        let price = read_pyth_price(&ctx.accounts.pyth_price_account, &ctx.accounts.config)?;
         
-        // Parse actual Pyth price account data structure
-        // Pyth v2 account structure offsets:
-        let account_data = pyth_price_account.try_borrow_data()?;
-        
-        // Verify this is a valid Pyth price account by checking magic number
-        let magic = u32::from_le_bytes([
-            account_data[0], account_data[1], account_data[2], account_data[3]
-        ]);
-        if magic != 0xa1b2c3d4 {  // Pyth magic number
-            return Err(ErrorCode::InvalidPriceAccount.into());
+        // Keep the fallback current when the caller passes it along
+        if let Some(last_good) = ctx.accounts.last_good.as_mut() {
+            last_good.record(&price);
         }
         
-        // Extract real price data from Pyth account structure
-        let price_bytes = &account_data[208..216];
-        let conf_bytes = &account_data[216..224]; 
-        let expo_bytes = &account_data[224..228];
-        let timestamp_bytes = &account_data[228..236];
-        let status_bytes = &account_data[236..240];
-        
-        let price = i64::from_le_bytes(price_bytes.try_into()
-            .map_err(|_| ErrorCode::InvalidPriceAccount)?);
-        let confidence = u64::from_le_bytes(conf_bytes.try_into()
-            .map_err(|_| ErrorCode::InvalidPriceAccount)?);
-        let expo = i32::from_le_bytes(expo_bytes.try_into()
-            .map_err(|_| ErrorCode::InvalidPriceAccount)?);
-        let publish_time = i64::from_le_bytes(timestamp_bytes.try_into()
-            .map_err(|_| ErrorCode::InvalidPriceAccount)?);
-        let status = u32::from_le_bytes(status_bytes.try_into()
-            .map_err(|_| ErrorCode::InvalidPriceAccount)?);
-        
-        // Validate price status (1 = trading, 0 = unknown, 2 = halted)
-        if status != 1 {
-            return Err(ErrorCode::PriceUnavailable.into());
-        }
-        
-        // Validate staleness
-        let clock = Clock::get()?;
-        let current_timestamp = clock.unix_timestamp;
-        if current_timestamp - publish_time > ctx.accounts.config.max_staleness {
-            return Err(ErrorCode::StalePrice.into());
-        }
-        
-        // Check if price is available and positive
-        if price <= 0 {
-            return Err(ErrorCode::PriceUnavailable.into());
-        }
+        Ok(price)
+    }
+
+    pub fn initialize_last_good(_ctx: Context<InitializeLastGood>) -> Result<()> {
+        Ok(())
+    }
+
+    /// The live Pyth price when it passes every check, else the last good
+    /// price if it is at most `max_fallback_age` seconds old
+    pub fn get_price_or_last_good(
+        ctx: Context<GetPriceOrLastGood>,
+        max_fallback_age: i64,
+    ) -> Result<PriceData> {
+        let live = read_pyth_price(&ctx.accounts.pyth_price_account, &ctx.accounts.config);
+        let last_good = &mut ctx.accounts.last_good;
         
-        // Validate confidence interval (confidence as percentage of price) 
-        let confidence_percentage = (confidence as f64 / price.abs() as f64) * 10000.0;
-        if confidence_percentage > ctx.accounts.config.max_confidence as f64 {
-            return Err(ErrorCode::LowConfidence.into());
+        match live {
+            Ok(price) => {
+                last_good.record(&price);
+                Ok(price)
+            }
+            Err(e) => {
+                let age = Clock::get()?.unix_timestamp - last_good.timestamp;
+                if last_good.timestamp == 0 || age > max_fallback_age {
+                    msg!("Live price unavailable ({}) and no last good price within {}s", e, max_fallback_age);
+                    return Err(ErrorCode::NoFallbackPrice.into());
+                }
+                
+                msg!("Live price unavailable ({}), serving last good price from {}s ago", e, age);
+                Ok(last_good.to_price_data())
+            }
         }
-        
-        Ok(PriceData {
-            price,
-            confidence,
-            expo,
-            timestamp: publish_time,
-            source: PriceSource::Pyth,
-        })
     }
 
     pub fn get_switchboard_price(
@@ -124,8 +103,8 @@ pub mod oracle_integration {
         
         // Extract current value from aggregator result
         // Current value is stored as SwitchboardDecimal at offset 144
-        let value_bytes = &account_data[144..152]; // 8 bytes for mantissa
-        let scale_bytes = &account_data[152..156]; // 4 bytes for scale
+        let value_bytes = &account_data[144..160]; // 16 bytes for the i128 mantissa
+        let scale_bytes = &account_data[160..164]; // 4 bytes for scale
         
         // Extract timestamp from latest confirmed round (offset 200)
         let timestamp_bytes = &account_data[200..208];
@@ -134,11 +113,8 @@ pub mod oracle_integration {
         let min_response_bytes = &account_data[208..216];
         let max_response_bytes = &account_data[216..224];
         
-        let mantissa = i128::from_le_bytes([
-            value_bytes[0], value_bytes[1], value_bytes[2], value_bytes[3],
-            value_bytes[4], value_bytes[5], value_bytes[6], value_bytes[7],
-            0, 0, 0, 0, 0, 0, 0, 0, // Pad to 16 bytes
-        ]);
+        let mantissa = i128::from_le_bytes(value_bytes.try_into()
+            .map_err(|_| ErrorCode::InvalidAggregatorAccount)?);
         let scale = u32::from_le_bytes(scale_bytes.try_into()
             .map_err(|_| ErrorCode::InvalidAggregatorAccount)?);
         let latest_timestamp = i64::from_le_bytes(timestamp_bytes.try_into()
@@ -157,15 +133,17 @@ pub mod oracle_integration {
         // Validate timestamp staleness
         let clock = Clock::get()?;
         let current_time = clock.unix_timestamp;
-        if current_time - latest_timestamp > ctx.accounts.config.max_staleness {
+        if current_time - latest_timestamp > ctx.accounts.config.max_staleness_for(&PriceSource::Switchboard) {
             return Err(ErrorCode::StalePrice.into());
         }
         
-        // Convert mantissa to i64 (truncating if necessary for compatibility)
+        // Convert mantissa to i64, saturating at the bounds for compatibility
         let price = if mantissa > i64::MAX as i128 {
+            msg!("Switchboard mantissa exceeds i64, saturating");
             i64::MAX
         } else if mantissa < i64::MIN as i128 {
-            i64::MIN  
+            msg!("Switchboard mantissa below i64, saturating");
+            i64::MIN
         } else {
             mantissa as i64
         };
@@ -190,26 +168,33 @@ pub mod oracle_integration {
     pub fn validate_price_consensus(
         _ctx: Context<ValidatePrice>,
         prices: Vec<PriceData>,
+        allow_single: bool,
     ) -> Result<u64> {
+        // Callers with one trusted source may opt in to having it echoed back
+        if allow_single && prices.len() == 1 {
+            let price = prices[0].price;
+            if price <= 0 {
+                return Err(ErrorCode::PriceUnavailable.into());
+            }
+            msg!("Single-source consensus: returning the only supplied price");
+            return Ok(price as u64);
+        }
+        
         if prices.len() < 2 {
             return Err(ErrorCode::InsufficientSources.into());
         }
         
-        // Calculate median price
-        let mut sorted_prices: Vec<i64> = prices.iter().map(|p| p.price).collect();
-        sorted_prices.sort();
-        
-        let median = if sorted_prices.len() % 2 == 0 {
-            let mid = sorted_prices.len() / 2;
-            (sorted_prices[mid - 1] + sorted_prices[mid]) / 2
-        } else {
-            sorted_prices[sorted_prices.len() / 2]
-        };
+        let median = consensus_median(&prices)?;
         
         // Validate prices within threshold (1% deviation)
         for price_data in &prices {
-            let deviation = (price_data.price as f64 - median as f64).abs() / median as f64;
-            if deviation > 0.01 { // 1% threshold
+            let deviation = price_data.price.abs_diff(median);
+            if exceeds_basis_points(deviation, median as u64, MAX_CONSENSUS_DEVIATION_BP) {
+                msg!(
+                    "Deviation exceeded: {:?} price {} (expo {}) is {}bp from median {}, max {}bp",
+                    price_data.source, price_data.price, price_data.expo,
+                    basis_points(deviation, median as u64), median, MAX_CONSENSUS_DEVIATION_BP
+                );
                 return Err(ErrorCode::PriceDeviationTooHigh.into());
             }
         }
@@ -218,12 +203,132 @@ pub mod oracle_integration {
     }
 }
 
+/// Median of the supplied prices, rejected unless strictly positive
+///
+/// Deviations are measured relative to the median, so a zero or negative one
+/// would divide by zero or flip every comparison; it only arises when most
+/// sources are broken.
+fn consensus_median(prices: &[PriceData]) -> Result<i64> {
+    let mut sorted_prices: Vec<i64> = prices.iter().map(|p| p.price).collect();
+    sorted_prices.sort();
+    
+    let mid = sorted_prices.len() / 2;
+    let median = if sorted_prices.len() % 2 == 0 {
+        // Averaged in i128 so two large prices can't overflow
+        ((sorted_prices[mid - 1] as i128 + sorted_prices[mid] as i128) / 2) as i64
+    } else {
+        sorted_prices[mid]
+    };
+    
+    if median <= 0 {
+        msg!("Median price {} across {} sources is not positive, rejecting consensus", median, prices.len());
+        return Err(ErrorCode::NonPositiveMedian.into());
+    }
+    Ok(median)
+}
+
+/// Pyth oracle program on mainnet; price accounts it does not own are forged
+///
+/// The magic number alone is not enough, since anyone can create an account
+/// that starts with it, and what `read_pyth_price` returns is persisted as
+/// the last good price.
+pub const PYTH_PROGRAM_ID: Pubkey =
+    anchor_lang::solana_program::pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Largest spread from the median `validate_price_consensus` accepts
+const MAX_CONSENSUS_DEVIATION_BP: u64 = 100;
+
+/// Parse and validate a Pyth price account against the config's limits
+fn read_pyth_price(pyth_price_account: &AccountInfo, config: &OracleConfig) -> Result<PriceData> {
+    // REAL PYTH PRICE PARSING - No more mock data!
+    if pyth_price_account.data_len() < 240 {
+        return Err(ErrorCode::InvalidPriceAccount.into());
+    }
+    
+    // Parse actual Pyth price account data structure
+    // Pyth v2 account structure offsets:
+    let account_data = pyth_price_account.try_borrow_data()?;
+    
+    // Verify this is a valid Pyth price account by checking magic number
+    let magic = u32::from_le_bytes([
+        account_data[0], account_data[1], account_data[2], account_data[3]
+    ]);
+    if magic != 0xa1b2c3d4 {  // Pyth magic number
+        return Err(ErrorCode::InvalidPriceAccount.into());
+    }
+    
+    // Extract real price data from Pyth account structure
+    let price_bytes = &account_data[208..216];
+    let conf_bytes = &account_data[216..224]; 
+    let expo_bytes = &account_data[224..228];
+    let timestamp_bytes = &account_data[228..236];
+    let status_bytes = &account_data[236..240];
+    
+    let price = i64::from_le_bytes(price_bytes.try_into()
+        .map_err(|_| ErrorCode::InvalidPriceAccount)?);
+    let confidence = u64::from_le_bytes(conf_bytes.try_into()
+        .map_err(|_| ErrorCode::InvalidPriceAccount)?);
+    let expo = i32::from_le_bytes(expo_bytes.try_into()
+        .map_err(|_| ErrorCode::InvalidPriceAccount)?);
+    let publish_time = i64::from_le_bytes(timestamp_bytes.try_into()
+        .map_err(|_| ErrorCode::InvalidPriceAccount)?);
+    let status = u32::from_le_bytes(status_bytes.try_into()
+        .map_err(|_| ErrorCode::InvalidPriceAccount)?);
+    
+    // Validate price status (1 = trading, 0 = unknown, 2 = halted)
+    if status != 1 {
+        return Err(ErrorCode::PriceUnavailable.into());
+    }
+    
+    // Validate staleness
+    let clock = Clock::get()?;
+    let current_timestamp = clock.unix_timestamp;
+    if current_timestamp - publish_time > config.max_staleness_for(&PriceSource::Pyth) {
+        return Err(ErrorCode::StalePrice.into());
+    }
+    
+    // Check if price is available and positive
+    if price <= 0 {
+        return Err(ErrorCode::PriceUnavailable.into());
+    }
+    
+    // Validate confidence interval (confidence in basis points of price)
+    if exceeds_basis_points(confidence, price.unsigned_abs(), config.max_confidence) {
+        return Err(ErrorCode::LowConfidence.into());
+    }
+    
+    Ok(PriceData {
+        price,
+        confidence,
+        expo,
+        timestamp: publish_time,
+        source: PriceSource::Pyth,
+    })
+}
+
+/// Whether `part` is more than `max_bp` basis points of `whole`
+///
+/// Integer math only: floating point is not guaranteed to give the same
+/// result on every validator. Cross-multiplying in u128 avoids both overflow
+/// and the rounding a division would introduce.
+fn exceeds_basis_points(part: u64, whole: u64, max_bp: u64) -> bool {
+    part as u128 * 10_000 > max_bp as u128 * whole as u128
+}
+
+/// `part` in basis points of `whole`, rounded down, for logging
+fn basis_points(part: u64, whole: u64) -> u64 {
+    if whole == 0 {
+        return u64::MAX;
+    }
+    (part as u128 * 10_000 / whole as u128).min(u64::MAX as u128) as u64
+}
+
 #[derive(Accounts)]
 pub struct InitializeConfig<'info> {
     #[account(
         init,
         payer = payer,
-        space = 8 + 64 + 32 + 32 + 8 + 8 + 8, // discriminator + symbol + pyth_feed + switchboard_aggregator + max_staleness + max_confidence + max_deviation
+        space = OracleConfig::SPACE,
         seeds = [b"config"],
         bump
     )]
@@ -237,12 +342,66 @@ pub struct InitializeConfig<'info> {
 pub struct GetPythPrice<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
+    /// CHECK: must be the configured feed and owned by the Pyth program
+    #[account(
+        address = config.pyth_feed @ ErrorCode::InvalidPriceAccount,
+        owner = PYTH_PROGRAM_ID @ ErrorCode::InvalidPriceAccount,
+    )]
     pub pyth_price_account: AccountInfo<'info>,
     #[account(
         seeds = [b"config"],
         bump,
     )]
     pub config: Account<'info, OracleConfig>,
+    #[account(
+        mut,
+        seeds = [b"last_good", config.symbol.as_bytes()],
+        bump,
+    )]
+    pub last_good: Option<Account<'info, LastGoodPrice>>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeLastGood<'info> {
+    #[account(
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, OracleConfig>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + 8 + 8 + 4 + 8 + 1, // discriminator + price + confidence + expo + timestamp + source
+        seeds = [b"last_good", config.symbol.as_bytes()],
+        bump
+    )]
+    pub last_good: Account<'info, LastGoodPrice>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetPriceOrLastGood<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    /// CHECK: must be the configured feed and owned by the Pyth program
+    #[account(
+        address = config.pyth_feed @ ErrorCode::InvalidPriceAccount,
+        owner = PYTH_PROGRAM_ID @ ErrorCode::InvalidPriceAccount,
+    )]
+    pub pyth_price_account: AccountInfo<'info>,
+    #[account(
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, OracleConfig>,
+    #[account(
+        mut,
+        seeds = [b"last_good", config.symbol.as_bytes()],
+        bump,
+    )]
+    pub last_good: Account<'info, LastGoodPrice>,
 }
 
 #[derive(Accounts)]
@@ -270,10 +429,62 @@ pub struct OracleConfig {
     pub pyth_feed: Pubkey,
     pub switchboard_aggregator: Pubkey,
     pub max_staleness: i64,    // seconds
+    pub pyth_max_staleness: i64,        // seconds; 0 falls back to max_staleness
+    pub switchboard_max_staleness: i64, // seconds; 0 falls back to max_staleness
     pub max_confidence: u64,   // basis points
     pub max_deviation: u64,    // basis points
 }
 
+/// Longest symbol, in bytes, a config can hold
+pub const MAX_SYMBOL_LEN: usize = 32;
+
+impl OracleConfig {
+    // discriminator + symbol (length prefix + bytes) + pyth_feed + switchboard_aggregator
+    // + max_staleness + pyth_max_staleness + switchboard_max_staleness + max_confidence + max_deviation
+    pub const SPACE: usize = 8 + 4 + MAX_SYMBOL_LEN + 32 + 32 + 8 + 8 + 8 + 8 + 8;
+    
+    /// Oldest reading accepted from a source, in seconds: its own limit if set, else `max_staleness`
+    pub fn max_staleness_for(&self, source: &PriceSource) -> i64 {
+        let own = match source {
+            PriceSource::Pyth => self.pyth_max_staleness,
+            PriceSource::Switchboard => self.switchboard_max_staleness,
+            PriceSource::Internal => 0,
+        };
+        if own > 0 { own } else { self.max_staleness }
+    }
+}
+
+/// Most recent Pyth price that passed every check, served by
+/// `get_price_or_last_good` while the live feed can't be used
+#[account]
+pub struct LastGoodPrice {
+    pub price: i64,
+    pub confidence: u64,
+    pub expo: i32,
+    pub timestamp: i64,        // publish time; 0 until the first good read
+    pub source: PriceSource,
+}
+
+impl LastGoodPrice {
+    fn record(&mut self, price: &PriceData) {
+        self.price = price.price;
+        self.confidence = price.confidence;
+        self.expo = price.expo;
+        self.timestamp = price.timestamp;
+        self.source = price.source.clone();
+    }
+    
+    fn to_price_data(&self) -> PriceData {
+        PriceData {
+            price: self.price,
+            confidence: self.confidence,
+            expo: self.expo,
+            timestamp: self.timestamp,
+            source: self.source.clone(),
+        }
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct PriceData {
     pub price: i64,
@@ -283,7 +494,7 @@ pub struct PriceData {
     pub source: PriceSource,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq)]
 pub enum PriceSource {
     Pyth,
     Switchboard,
@@ -310,4 +521,111 @@ pub enum ErrorCode {
     InsufficientSources,
     #[msg("Price deviation too high")]
     PriceDeviationTooHigh,
+    #[msg("No live price and no last good price within the fallback age")]
+    NoFallbackPrice,
+    #[msg("Median price is not positive")]
+    NonPositiveMedian,
+    #[msg("Symbol is longer than 32 bytes")]
+    SymbolTooLong,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(price: i64, source: PriceSource) -> PriceData {
+        PriceData {
+            price,
+            confidence: 1_000_000,
+            expo: -8,
+            timestamp: 0,
+            source,
+        }
+    }
+
+    #[test]
+    fn test_consensus_median_rejects_non_positive() {
+        let all_zero = [price(0, PriceSource::Pyth), price(0, PriceSource::Switchboard)];
+        assert_eq!(consensus_median(&all_zero).unwrap_err(), ErrorCode::NonPositiveMedian.into());
+
+        let mixed_sign = [
+            price(-5_000_000_000_000, PriceSource::Pyth),
+            price(-4_999_000_000_000, PriceSource::Switchboard),
+            price(5_000_000_000_000, PriceSource::Internal),
+        ];
+        assert_eq!(consensus_median(&mixed_sign).unwrap_err(), ErrorCode::NonPositiveMedian.into());
+
+        // One bad source among good ones still has a positive median
+        let one_bad = [
+            price(-100, PriceSource::Pyth),
+            price(5_000_000_000_000, PriceSource::Switchboard),
+            price(5_001_000_000_000, PriceSource::Internal),
+        ];
+        assert_eq!(consensus_median(&one_bad).unwrap(), 5_000_000_000_000);
+
+        // Even counts average the middle pair without overflowing
+        let large = [price(i64::MAX, PriceSource::Pyth), price(i64::MAX - 2, PriceSource::Switchboard)];
+        assert_eq!(consensus_median(&large).unwrap(), i64::MAX - 1);
+    }
+
+    #[test]
+    fn test_basis_point_boundaries() {
+        // Exactly at the limit passes, one unit over fails
+        assert!(!exceeds_basis_points(100, 10_000, 100));
+        assert!(exceeds_basis_points(101, 10_000, 100));
+        assert_eq!(basis_points(100, 10_000), 100);
+        assert_eq!(basis_points(101, 10_000), 101);
+
+        // Anything nonzero exceeds every limit against a zero whole
+        assert!(!exceeds_basis_points(0, 0, 100));
+        assert!(exceeds_basis_points(1, 0, 100));
+        assert!(exceeds_basis_points(1, 0, u64::MAX));
+        assert_eq!(basis_points(1, 0), u64::MAX);
+
+        // Extremes neither overflow nor round
+        assert!(!exceeds_basis_points(u64::MAX, u64::MAX, 10_000));
+        assert!(exceeds_basis_points(u64::MAX, u64::MAX, 9_999));
+        assert!(exceeds_basis_points(u64::MAX, 1, u64::MAX));
+        assert!(!exceeds_basis_points(0, u64::MAX, 0));
+        assert_eq!(basis_points(u64::MAX, u64::MAX), 10_000);
+        assert_eq!(basis_points(u64::MAX, 1), u64::MAX);
+    }
+
+    #[test]
+    fn test_config_space_fits_longest_symbol() {
+        let config = OracleConfig {
+            symbol: "X".repeat(MAX_SYMBOL_LEN),
+            pyth_feed: Pubkey::default(),
+            switchboard_aggregator: Pubkey::default(),
+            max_staleness: 60,
+            pyth_max_staleness: 5,
+            switchboard_max_staleness: 30,
+            max_confidence: 100,
+            max_deviation: 100,
+        };
+        let serialized = config.try_to_vec().unwrap();
+        assert_eq!(8 + serialized.len(), OracleConfig::SPACE);
+    }
+
+    #[test]
+    fn test_per_source_staleness() {
+        let mut config = OracleConfig {
+            symbol: "SOL/USD".to_string(),
+            pyth_feed: Pubkey::default(),
+            switchboard_aggregator: Pubkey::default(),
+            max_staleness: 60,
+            pyth_max_staleness: 0,
+            switchboard_max_staleness: 0,
+            max_confidence: 100,
+            max_deviation: 100,
+        };
+        assert_eq!(config.max_staleness_for(&PriceSource::Pyth), 60);
+        assert_eq!(config.max_staleness_for(&PriceSource::Switchboard), 60);
+
+        config.pyth_max_staleness = 5;
+        config.switchboard_max_staleness = 30;
+        assert_eq!(config.max_staleness_for(&PriceSource::Pyth), 5);
+        assert_eq!(config.max_staleness_for(&PriceSource::Switchboard), 30);
+        assert_eq!(config.max_staleness_for(&PriceSource::Internal), 60);
+    }
 }
\ No newline at end of file